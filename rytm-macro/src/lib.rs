@@ -139,7 +139,13 @@ pub fn parameter_range(args: TokenStream, input: TokenStream) -> TokenStream {
 ///
 /// `#<number>` annotations denote synth parameter indices used internally.
 ///
-/// The macro also generates a `apply_to_raw_sound_values` method which is used to apply the parameter values to the raw sound struct.
+/// The macro also generates a `apply_to_raw_sound_values` method which is used to apply the parameter values to the raw sound struct
+/// and a `PARAMETERS` constant which holds the metadata of the synth parameters in the order of their numbers.
+/// `get_parameter` and `set_parameter` methods access the parameters by their numbers through the typed getters and setters.
+///
+/// Parameters which are implemented manually are marked with `choice` (an enum with a `u8` representation), `toggle` (a `bool`) or
+/// `wide` (a `u16` stored in both bytes of the synth parameter) after their number.
+/// Only their metadata is generated.
 ///
 /// Please check `rytm-rs` source code for exhaustive usage examples.
 ///
@@ -156,6 +162,12 @@ pub fn parameter_range(args: TokenStream, input: TokenStream) -> TokenStream {
 ///     wav: "0..=2" #7,
 ///     tra: "0..=127" #8,
 /// )]
+/// #[machine_parameters(
+///     lev: "0..=127" #1,
+///     // ...
+///     wav: "0..=11" #7 choice,
+///     osc: "0..=16256" #8 wide,
+/// )]
 /// ```
 #[proc_macro_attribute]
 pub fn machine_parameters(args: TokenStream, input: TokenStream) -> TokenStream {
@@ -163,21 +175,65 @@ pub fn machine_parameters(args: TokenStream, input: TokenStream) -> TokenStream
     let input_struct = parse_macro_input!(input as DeriveInput);
     let struct_name = input_struct.ident.clone();
 
-    let methods = args.0.iter().map(|arg| {
-        // let (param_type, return_type) = determine_types(&arg.range);
-        let setter_and_plock_methods =
-            generate_setter_and_plock_methods_with_range_check(arg, &struct_name);
-        let getter = generate_getter(arg, &struct_name);
-        quote! { #setter_and_plock_methods #getter }
-    });
-
-    let apply_to_raw_sound_values_inner =
-        args.0.iter().map(generate_apply_to_raw_sound_values_inner);
+    let methods = args
+        .0
+        .iter()
+        .filter(|arg| arg.manual_kind.is_none())
+        .map(|arg| {
+            // let (param_type, return_type) = determine_types(&arg.range);
+            let setter_and_plock_methods =
+                generate_setter_and_plock_methods_with_range_check(arg, &struct_name);
+            let getter = generate_getter(arg, &struct_name);
+            quote! { #setter_and_plock_methods #getter }
+        });
+
+    let apply_to_raw_sound_values_inner = args
+        .0
+        .iter()
+        .filter(|arg| arg.manual_kind.is_none())
+        .map(generate_apply_to_raw_sound_values_inner);
+
+    let parameter_getter_arms = args.0.iter().map(generate_parameter_getter_arm);
+    let parameter_setter_arms = args.0.iter().map(generate_parameter_setter_arm);
+
+    let mut parameters = vec![quote! { None }; 8];
+    for arg in &args.0 {
+        let parameter = generate_parameter_metadata(arg);
+        parameters[arg.syn_param_type - 1] = quote! { Some(#parameter) };
+    }
 
     let result = quote! {
         #input_struct
 
         impl #struct_name {
+            /// The synth parameters of the machine in the order of their numbers.
+            ///
+            /// Synth parameters which the machine does not use are `None`.
+            pub(crate) const PARAMETERS: [Option<crate::object::sound::machine::MachineParameter>; 8] = [#(#parameters),*];
+
+            /// Gets the value of a synth parameter by its number in the numeric representation of [`MachineParameter::kind`](crate::object::sound::machine::MachineParameter::kind).
+            ///
+            /// Returns `None` if the machine does not use the synth parameter.
+            pub(crate) fn get_parameter(&self, number: usize) -> Option<f32> {
+                match number {
+                    #(#parameter_getter_arms)*
+                    _ => None,
+                }
+            }
+
+            /// Sets the value of a synth parameter by its number in the numeric representation of [`MachineParameter::kind`](crate::object::sound::machine::MachineParameter::kind).
+            ///
+            /// The value is expected to be validated against the metadata of the parameter.
+            pub(crate) fn set_parameter(&mut self, number: usize, value: f32) -> Result<(), RytmError> {
+                match number {
+                    #(#parameter_setter_arms)*
+                    _ => Err(RytmError::Parameter(ParameterError::Range {
+                        parameter_name: "number".to_string(),
+                        value: number.to_string(),
+                    })),
+                }
+            }
+
             pub(crate) fn apply_to_raw_sound_values(&self, raw_sound: &mut ar_sound_t) {
                 #(#apply_to_raw_sound_values_inner)*
             }
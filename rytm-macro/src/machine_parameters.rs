@@ -22,6 +22,20 @@ pub struct ParameterArg {
     pub range: String,
     pub param_type: Option<String>, // Optional field for type
     pub syn_param_type: usize,
+    pub manual_kind: Option<ManualKind>, // Optional marker for parameters which are implemented manually
+}
+
+/// The kinds of parameters which have manually implemented accessors.
+///
+/// Only the metadata is generated for them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ManualKind {
+    /// An enum with a `u8` representation.
+    Choice,
+    /// A `bool`.
+    Toggle,
+    /// A `u16` which is stored in both bytes of the synth parameter.
+    Wide,
 }
 
 impl Parse for ParameterArg {
@@ -47,15 +61,114 @@ impl Parse for ParameterArg {
             .base10_parse()
             .expect("Syn parameter type number needs to be provided. ");
 
+        if !(1..=8).contains(&syn_param_type) {
+            return Err(syn::Error::new(
+                type_lit_int.span(),
+                "Syn parameter type number needs to be in the range 1..=8.",
+            ));
+        }
+
+        // Optional: Parse the marker of a manually implemented parameter if provided
+        let manual_kind = if input.peek(Ident) {
+            let kind: Ident = input.parse()?;
+            Some(match kind.to_string().as_str() {
+                "choice" => ManualKind::Choice,
+                "toggle" => ManualKind::Toggle,
+                "wide" => ManualKind::Wide,
+                _ => {
+                    return Err(syn::Error::new(
+                        kind.span(),
+                        "Expected one of `choice`, `toggle` or `wide`.",
+                    ))
+                }
+            })
+        } else {
+            None
+        };
+
         Ok(ParameterArg {
             name: name.to_string(),
             range,
             param_type,
             syn_param_type,
+            manual_kind,
         })
     }
 }
 
+pub fn generate_parameter_metadata(parameter: &ParameterArg) -> proc_macro2::TokenStream {
+    let param_name = &parameter.name;
+    let number = parameter.syn_param_type;
+    let range = &parameter.range;
+
+    let parts: Vec<&str> = range.split("..=").collect();
+    assert!(
+        parts.len() == 2,
+        "The range of `{param_name}` needs to be inclusive to generate its metadata."
+    );
+    let start = parse_str::<syn::Expr>(parts[0]).expect("Invalid range start");
+    let end = parse_str::<syn::Expr>(parts[1]).expect("Invalid range end");
+
+    let machine_parameter = quote! { crate::object::sound::machine::MachineParameter };
+    match parameter.manual_kind {
+        Some(ManualKind::Choice) => {
+            quote! { #machine_parameter::choice(#param_name, #number, #end) }
+        }
+        Some(ManualKind::Toggle) => quote! { #machine_parameter::toggle(#param_name, #number) },
+        Some(ManualKind::Wide) => quote! { #machine_parameter::wide(#param_name, #number, #end) },
+        None if parts.iter().any(|&p| p.contains('.')) => {
+            quote! { #machine_parameter::scaled(#param_name, #number, #start, #end) }
+        }
+        None if parts.iter().any(|&p| p.starts_with('-')) => {
+            quote! { #machine_parameter::bipolar(#param_name, #number, #start, #end) }
+        }
+        None => quote! { #machine_parameter::unsigned(#param_name, #number, #end) },
+    }
+}
+
+pub fn generate_parameter_getter_arm(parameter: &ParameterArg) -> proc_macro2::TokenStream {
+    let number = parameter.syn_param_type;
+    let param_ident = parse_str::<Ident>(&parameter.name).unwrap();
+    let getter_fn_name = format_ident!("get_{}", parameter.name);
+
+    match parameter.manual_kind {
+        Some(ManualKind::Choice | ManualKind::Toggle) => {
+            quote! { #number => Some(f32::from(u8::from(self.#param_ident))), }
+        }
+        Some(ManualKind::Wide) => quote! { #number => Some(f32::from(self.#param_ident)), },
+        None => quote! { #number => Some(self.#getter_fn_name() as f32), },
+    }
+}
+
+pub fn generate_parameter_setter_arm(parameter: &ParameterArg) -> proc_macro2::TokenStream {
+    let number = parameter.syn_param_type;
+    let param_ident = parse_str::<Ident>(&parameter.name).unwrap();
+    let setter_fn_name = format_ident!("set_{}", parameter.name);
+    let (param_input_type, _) = determine_types(&parameter.range);
+
+    match parameter.manual_kind {
+        Some(ManualKind::Choice) => quote! {
+            #number => {
+                self.#param_ident = (value as u8).try_into()?;
+                Ok(())
+            }
+        },
+        Some(ManualKind::Toggle) => quote! {
+            #number => {
+                self.#param_ident = value != 0.0;
+                Ok(())
+            }
+        },
+        Some(ManualKind::Wide) => quote! {
+            #number => {
+                self.#param_ident = value as u16;
+                Ok(())
+            }
+        },
+        None => quote! { #number => self.#setter_fn_name(value as #param_input_type), },
+    }
+}
+
 pub fn generate_getter(parameter: &ParameterArg, struct_name: &Ident) -> proc_macro2::TokenStream {
     let param_name = &parameter.name;
     let param_ident = parse_str::<Ident>(param_name).unwrap();
@@ -117,9 +117,25 @@ pub enum RytmError {
 
     #[error(transparent)]
     Serde(#[from] serde_json::Error),
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
 
     #[error("Parameter lock memory full.")]
     ParameterLockMemoryFull,
-    #[error("Parameter lock pool is not set for this trig thus it is not connected to a pattern and orphan. This function can not be called on an orphan trig.")]
+    /// Holds the descriptions of the parameter locks which did not fit in the pool.
+    ///
+    /// Returned by operations which write many parameter locks at once instead of [`RytmError::ParameterLockMemoryFull`].
+    #[error("Parameter lock memory full. Parameter locks which did not fit: {}", .did_not_fit.join(", "))]
+    ParameterLockMemoryFullWith { did_not_fit: Vec<String> },
+    #[error("Parameter lock pool is not set for this trig thus it is not connected to a pattern and orphan. This function can not be called on an orphan trig. Use `Trig::try_detached` to make a trig which holds its parameter locks until it is placed into a track.")]
     OrphanTrig,
+    /// Holds the position and the reason of an error in the grid notation of a pattern.
+    ///
+    /// Lines and columns start from 1.
+    #[error("Grid notation error at line {line}, column {column}: {reason}")]
+    GridNotation {
+        line: usize,
+        column: usize,
+        reason: String,
+    },
 }
@@ -209,6 +209,7 @@
 
 pub(crate) mod defaults;
 pub mod error;
+pub mod library;
 pub mod object;
 pub mod prelude;
 pub mod query;
@@ -0,0 +1,598 @@
+//! An on-disk library of sounds.
+//!
+//! Every sound is stored in a directory as a `.syx` file which holds a pool sound sysex message
+//! and a `.json` sidecar file with the same name which holds its metadata.
+//!
+//! Sounds are indexed by name, machine type, tags and author, can be searched and loaded into
+//! the pool slots of a project or onto the tracks of a kit.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use rytm_rs::{library::{SoundLibrary, SoundSearch}, prelude::*};
+//!
+//! let mut project = RytmProject::try_default().unwrap();
+//! let library = SoundLibrary::open("sounds").unwrap();
+//!
+//! let mut search = SoundSearch::default();
+//! search.set_machine_type(MachineType::BdHard);
+//! search.add_tag("punchy");
+//!
+//! let ids = library
+//!     .search(&search)
+//!     .iter()
+//!     .map(|entry| entry.id().to_owned())
+//!     .collect::<Vec<_>>();
+//! let assignments = ids
+//!     .iter()
+//!     .enumerate()
+//!     .map(|(slot, id)| (slot, id.as_str()))
+//!     .collect::<Vec<_>>();
+//!
+//! library.load_into_pool(&mut project, &assignments).unwrap();
+//! ```
+
+use crate::{
+    error::{ParameterError, RytmError},
+    object::{kit::Kit, sound::types::MachineType, Sound},
+    RytmProject, SysexCompatible,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// Extension of the files which hold the sounds.
+const SYSEX_EXTENSION: &str = "syx";
+/// Extension of the sidecar files which hold the metadata of the sounds.
+const METADATA_EXTENSION: &str = "json";
+
+/// Returns the name of a sound without the padding of the fixed length name.
+fn sound_name(sound: &Sound) -> String {
+    sound.name().trim_end_matches(['\0', ' ']).to_owned()
+}
+
+/// Metadata of a sound in a [`SoundLibrary`].
+///
+/// The name and the machine type are read from the sound, tags and the author are kept in the sidecar file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SoundMetadata {
+    name: String,
+    machine_type: MachineType,
+    #[serde(default)]
+    tags: Vec<String>,
+    #[serde(default)]
+    author: Option<String>,
+}
+
+impl SoundMetadata {
+    fn new(sound: &Sound, tags: &[&str], author: Option<&str>) -> Self {
+        let mut metadata = Self {
+            name: sound_name(sound),
+            machine_type: sound.machine_type(),
+            tags: Vec::new(),
+            author: None,
+        };
+        metadata.set_tags(tags);
+        metadata.set_author(author);
+        metadata
+    }
+
+    /// Sets the tags, they are trimmed, lower cased and deduplicated.
+    fn set_tags(&mut self, tags: &[&str]) {
+        let mut tags = tags
+            .iter()
+            .map(|tag| tag.trim().to_lowercase())
+            .filter(|tag| !tag.is_empty())
+            .collect::<Vec<_>>();
+        tags.sort_unstable();
+        tags.dedup();
+        self.tags = tags;
+    }
+
+    fn set_author(&mut self, author: Option<&str>) {
+        self.author = author
+            .map(str::trim)
+            .filter(|author| !author.is_empty())
+            .map(str::to_owned);
+    }
+
+    /// Returns the name of the sound.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the machine type of the sound.
+    pub const fn machine_type(&self) -> MachineType {
+        self.machine_type
+    }
+
+    /// Returns the tags of the sound in lower case.
+    pub fn tags(&self) -> &[String] {
+        &self.tags
+    }
+
+    /// Returns the author of the sound if set.
+    pub fn author(&self) -> Option<&str> {
+        self.author.as_deref()
+    }
+
+    /// Checks if the sound has a tag, ignoring case.
+    pub fn has_tag(&self, tag: &str) -> bool {
+        let tag = tag.trim().to_lowercase();
+        self.tags.contains(&tag)
+    }
+}
+
+/// A sound in a [`SoundLibrary`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LibraryEntry {
+    id: String,
+    metadata: SoundMetadata,
+}
+
+impl LibraryEntry {
+    /// Returns the id of the entry which is the name of its files without the extension.
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// Returns the metadata of the entry.
+    pub const fn metadata(&self) -> &SoundMetadata {
+        &self.metadata
+    }
+}
+
+/// A file which is skipped when a [`SoundLibrary`] is opened because it can not be read.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SkippedFile {
+    path: PathBuf,
+    reason: String,
+}
+
+impl SkippedFile {
+    /// Returns the path of the `.syx` file which is skipped.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Returns why the file is skipped.
+    pub fn reason(&self) -> &str {
+        &self.reason
+    }
+}
+
+/// Criteria to search the sounds of a [`SoundLibrary`].
+///
+/// Every criterion which is set must match, an empty search matches every sound.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SoundSearch {
+    text: Option<String>,
+    machine_type: Option<MachineType>,
+    tags: Vec<String>,
+    author: Option<String>,
+}
+
+impl SoundSearch {
+    /// Sets a text which must be a part of the name, one of the tags or the author of a sound, ignoring case.
+    pub fn set_text(&mut self, text: &str) {
+        self.text = Some(text.trim().to_lowercase());
+    }
+
+    /// Sets the machine type which a sound must use.
+    pub const fn set_machine_type(&mut self, machine_type: MachineType) {
+        self.machine_type = Some(machine_type);
+    }
+
+    /// Adds a tag which a sound must have, ignoring case.
+    pub fn add_tag(&mut self, tag: &str) {
+        self.tags.push(tag.trim().to_lowercase());
+    }
+
+    /// Sets the author of a sound, ignoring case.
+    pub fn set_author(&mut self, author: &str) {
+        self.author = Some(author.trim().to_lowercase());
+    }
+
+    /// Clears the text.
+    pub fn clear_text(&mut self) {
+        self.text = None;
+    }
+
+    /// Clears the machine type.
+    pub const fn clear_machine_type(&mut self) {
+        self.machine_type = None;
+    }
+
+    /// Clears the tags.
+    pub fn clear_tags(&mut self) {
+        self.tags.clear();
+    }
+
+    /// Clears the author.
+    pub fn clear_author(&mut self) {
+        self.author = None;
+    }
+
+    /// Checks if a sound matches the search.
+    pub fn matches(&self, metadata: &SoundMetadata) -> bool {
+        let author = metadata.author().map(str::to_lowercase);
+        let matches_text = self.text.as_ref().is_none_or(|text| {
+            metadata.name().to_lowercase().contains(text)
+                || metadata.tags().iter().any(|tag| tag.contains(text))
+                || author.as_ref().is_some_and(|author| author.contains(text))
+        });
+
+        matches_text
+            && self
+                .machine_type
+                .is_none_or(|machine_type| machine_type == metadata.machine_type())
+            && self.tags.iter().all(|tag| metadata.has_tag(tag))
+            && self
+                .author
+                .as_ref()
+                .is_none_or(|expected| author.as_ref() == Some(expected))
+    }
+}
+
+/// A library of sounds stored in a directory.
+///
+/// The index is built when the library is opened and kept in sync with the files by the methods which change the library.
+#[derive(Debug, Clone)]
+pub struct SoundLibrary {
+    directory: PathBuf,
+    entries: BTreeMap<String, LibraryEntry>,
+    skipped_files: Vec<SkippedFile>,
+}
+
+impl SoundLibrary {
+    /// Opens the library in a directory, creating the directory if it does not exist.
+    ///
+    /// Every `.syx` file in the directory is indexed, sounds without a sidecar file have no tags and no author.
+    /// Files which are not valid sounds or have an invalid sidecar file are skipped and listed in [`SoundLibrary::skipped_files`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the directory can not be created or read.
+    pub fn open(directory: impl AsRef<Path>) -> Result<Self, RytmError> {
+        let directory = directory.as_ref().to_path_buf();
+        fs::create_dir_all(&directory)?;
+
+        let mut entries = BTreeMap::new();
+        let mut skipped_files = Vec::new();
+        for dir_entry in fs::read_dir(&directory)? {
+            let path = dir_entry?.path();
+            if path.extension().and_then(|extension| extension.to_str()) != Some(SYSEX_EXTENSION) {
+                continue;
+            }
+            let Some(id) = path.file_stem().and_then(|stem| stem.to_str()) else {
+                continue;
+            };
+
+            match Self::read_metadata(&path) {
+                Ok(metadata) => {
+                    entries.insert(
+                        id.to_owned(),
+                        LibraryEntry {
+                            id: id.to_owned(),
+                            metadata,
+                        },
+                    );
+                }
+                Err(error) => skipped_files.push(SkippedFile {
+                    path,
+                    reason: error.to_string(),
+                }),
+            }
+        }
+        skipped_files.sort_unstable_by(|a, b| a.path.cmp(&b.path));
+
+        Ok(Self {
+            directory,
+            entries,
+            skipped_files,
+        })
+    }
+
+    /// Reads the metadata of the sound in a `.syx` file from the sound and its sidecar file.
+    fn read_metadata(path: &Path) -> Result<SoundMetadata, RytmError> {
+        let sound = Sound::try_from_sysex(&fs::read(path)?)?;
+        let metadata_path = path.with_extension(METADATA_EXTENSION);
+        let mut metadata = if metadata_path.exists() {
+            serde_json::from_slice::<SoundMetadata>(&fs::read(&metadata_path)?)?
+        } else {
+            SoundMetadata::new(&sound, &[], None)
+        };
+        // The sound is the source of truth for its name and machine.
+        metadata.name = sound_name(&sound);
+        metadata.machine_type = sound.machine_type();
+        Ok(metadata)
+    }
+
+    /// Returns the files which are skipped when the library is opened ordered by their paths.
+    ///
+    /// They are left in the directory as they are and are not part of the library.
+    pub fn skipped_files(&self) -> &[SkippedFile] {
+        &self.skipped_files
+    }
+
+    /// Returns the directory of the library.
+    pub fn directory(&self) -> &Path {
+        &self.directory
+    }
+
+    /// Returns the entries of the library ordered by their ids.
+    pub fn entries(&self) -> Vec<&LibraryEntry> {
+        self.entries.values().collect()
+    }
+
+    /// Returns an entry by its id.
+    pub fn entry(&self, id: &str) -> Option<&LibraryEntry> {
+        self.entries.get(id)
+    }
+
+    /// Returns the entries which match a search ordered by their ids.
+    pub fn search(&self, search: &SoundSearch) -> Vec<&LibraryEntry> {
+        self.entries
+            .values()
+            .filter(|entry| search.matches(&entry.metadata))
+            .collect()
+    }
+
+    /// Returns the entries which have a name, ignoring case.
+    pub fn entries_by_name(&self, name: &str) -> Vec<&LibraryEntry> {
+        let name = name.to_lowercase();
+        self.entries
+            .values()
+            .filter(|entry| entry.metadata.name.to_lowercase() == name)
+            .collect()
+    }
+
+    /// Returns the entries which use a machine.
+    pub fn entries_by_machine_type(&self, machine_type: MachineType) -> Vec<&LibraryEntry> {
+        self.entries
+            .values()
+            .filter(|entry| entry.metadata.machine_type == machine_type)
+            .collect()
+    }
+
+    /// Returns the tags used in the library with the number of sounds which have them.
+    pub fn tags(&self) -> BTreeMap<&str, usize> {
+        let mut tags = BTreeMap::new();
+        for tag in self.entries.values().flat_map(|entry| &entry.metadata.tags) {
+            *tags.entry(tag.as_str()).or_insert(0) += 1;
+        }
+        tags
+    }
+
+    /// Returns the authors of the sounds in the library with the number of sounds they made.
+    pub fn authors(&self) -> BTreeMap<&str, usize> {
+        let mut authors = BTreeMap::new();
+        for author in self
+            .entries
+            .values()
+            .filter_map(|entry| entry.metadata.author())
+        {
+            *authors.entry(author).or_insert(0) += 1;
+        }
+        authors
+    }
+
+    /// Stores a sound in the library and returns its entry.
+    ///
+    /// The id is made from the name of the sound, a number is appended if the id is taken.
+    /// The sound is stored as a pool sound sysex message, keeping its pool index if it is a pool sound.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the sound does not have a machine set or the files can not be written.
+    pub fn add(
+        &mut self,
+        sound: &Sound,
+        tags: &[&str],
+        author: Option<&str>,
+    ) -> Result<&LibraryEntry, RytmError> {
+        if sound.machine_type() == MachineType::Unset {
+            return Err(ParameterError::Compatibility {
+                value: sound.machine_type().to_string(),
+                parameter_name: "machine_type".into(),
+                reason: Some("A sound without a machine can not be stored.".into()),
+            }
+            .into());
+        }
+
+        let mut stored = Sound::try_default(sound.pool_index().unwrap_or(0))?;
+        stored.replace_with(sound)?;
+
+        let id = self.free_id(&sound_name(sound));
+        let entry = LibraryEntry {
+            id: id.clone(),
+            metadata: SoundMetadata::new(sound, tags, author),
+        };
+
+        fs::write(self.path(&id, SYSEX_EXTENSION), stored.as_sysex()?)?;
+        self.write_metadata(&entry)?;
+        Ok(self.entries.entry(id).or_insert(entry))
+    }
+
+    /// Sets the tags of a sound, they are trimmed, lower cased and deduplicated.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if there is no sound with the id or the sidecar file can not be written.
+    pub fn set_tags(&mut self, id: &str, tags: &[&str]) -> Result<(), RytmError> {
+        let mut entry = self.try_entry(id)?.clone();
+        entry.metadata.set_tags(tags);
+        self.write_metadata(&entry)?;
+        self.entries.insert(entry.id.clone(), entry);
+        Ok(())
+    }
+
+    /// Sets or clears the author of a sound.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if there is no sound with the id or the sidecar file can not be written.
+    pub fn set_author(&mut self, id: &str, author: Option<&str>) -> Result<(), RytmError> {
+        let mut entry = self.try_entry(id)?.clone();
+        entry.metadata.set_author(author);
+        self.write_metadata(&entry)?;
+        self.entries.insert(entry.id.clone(), entry);
+        Ok(())
+    }
+
+    /// Removes a sound and its sidecar file from the library.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if there is no sound with the id or the files can not be removed.
+    pub fn remove(&mut self, id: &str) -> Result<(), RytmError> {
+        self.try_entry(id)?;
+        fs::remove_file(self.path(id, SYSEX_EXTENSION))?;
+        let metadata_path = self.path(id, METADATA_EXTENSION);
+        if metadata_path.exists() {
+            fs::remove_file(metadata_path)?;
+        }
+        self.entries.remove(id);
+        Ok(())
+    }
+
+    /// Reads a sound from the library.
+    ///
+    /// The sound is a pool sound which is not linked to any parameter lock pool.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if there is no sound with the id or its file can not be read.
+    pub fn load(&self, id: &str) -> Result<Sound, RytmError> {
+        self.try_entry(id)?;
+        Sound::try_from_sysex(&fs::read(self.path(id, SYSEX_EXTENSION))?)
+    }
+
+    /// Loads a sound from the library into an existing sound.
+    ///
+    /// The name and the data of the sound are replaced,
+    /// its index, track assignment and link to the parameter lock pool are kept.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the sound can not be read or the sound is assigned to a track which its machine is not compatible with.
+    pub fn load_into(&self, id: &str, sound: &mut Sound) -> Result<(), RytmError> {
+        sound.replace_with(&self.load(id)?)
+    }
+
+    /// Loads sounds from the library into the pool slots of a project.
+    ///
+    /// Assignments are pairs of a pool slot and the id of a sound.
+    ///
+    /// Range `0..=127` for the slots.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a slot is out of range or a sound can not be read.
+    /// In that case the project is left untouched.
+    pub fn load_into_pool(
+        &self,
+        project: &mut RytmProject,
+        assignments: &[(usize, &str)],
+    ) -> Result<(), RytmError> {
+        self.load_into_sounds(project.pool_sounds_mut(), assignments, "pool_slot")
+    }
+
+    /// Loads sounds from the library onto the tracks of a kit.
+    ///
+    /// Assignments are pairs of a track index and the id of a sound.
+    ///
+    /// Range `0..=11` for the track indexes.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a track index is out of range, a sound can not be read
+    /// or the machine of a sound is not compatible with its track.
+    /// In that case the kit is left untouched.
+    pub fn load_into_kit(
+        &self,
+        kit: &mut Kit,
+        assignments: &[(usize, &str)],
+    ) -> Result<(), RytmError> {
+        self.load_into_sounds(kit.sounds_mut(), assignments, "track_index")
+    }
+
+    /// Loads sounds into the given slots, every sound is loaded before any of the slots is changed.
+    fn load_into_sounds(
+        &self,
+        sounds: &mut [Sound],
+        assignments: &[(usize, &str)],
+        parameter_name: &str,
+    ) -> Result<(), RytmError> {
+        let mut loaded = Vec::with_capacity(assignments.len());
+        for &(slot, id) in assignments {
+            let mut sound = sounds
+                .get(slot)
+                .ok_or_else(|| ParameterError::Range {
+                    value: slot.to_string(),
+                    parameter_name: parameter_name.into(),
+                })?
+                .clone();
+            self.load_into(id, &mut sound)?;
+            loaded.push((slot, sound));
+        }
+
+        for (slot, sound) in loaded {
+            sounds[slot] = sound;
+        }
+        Ok(())
+    }
+
+    fn try_entry(&self, id: &str) -> Result<&LibraryEntry, RytmError> {
+        self.entries.get(id).ok_or_else(|| {
+            ParameterError::Compatibility {
+                value: id.to_owned(),
+                parameter_name: "id".into(),
+                reason: Some("There is no sound with this id in the library.".into()),
+            }
+            .into()
+        })
+    }
+
+    fn write_metadata(&self, entry: &LibraryEntry) -> Result<(), RytmError> {
+        fs::write(
+            self.path(&entry.id, METADATA_EXTENSION),
+            serde_json::to_string_pretty(&entry.metadata)?,
+        )?;
+        Ok(())
+    }
+
+    fn path(&self, id: &str, extension: &str) -> PathBuf {
+        self.directory.join(format!("{id}.{extension}"))
+    }
+
+    /// Makes an id from a name which is safe to be used as a file name and not taken in the library.
+    fn free_id(&self, name: &str) -> String {
+        let base = name
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || c == '-' {
+                    c.to_ascii_lowercase()
+                } else {
+                    '_'
+                }
+            })
+            .collect::<String>();
+        let base = if base.is_empty() {
+            "sound".to_owned()
+        } else {
+            base
+        };
+
+        let mut id = base.clone();
+        let mut number = 2;
+        while self.entries.contains_key(&id) || self.path(&id, SYSEX_EXTENSION).exists() {
+            id = format!("{base}_{number}");
+            number += 1;
+        }
+        id
+    }
+}
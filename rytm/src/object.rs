@@ -2,6 +2,8 @@
 //!
 //! To know more about the objects, please check their own module documentation.
 
+/// Structural differences between objects and their application as patches.
+pub mod diff;
 /// Holds the global object structure.
 pub mod global;
 /// Holds the kit object structure.
@@ -0,0 +1,970 @@
+// All casts in this file are intended or safe within the context of this library.
+//
+// One can change `allow` to `warn` to review them if necessary.
+#![allow(clippy::cast_possible_truncation)]
+
+use crate::{
+    error::{ParameterError, RytmError},
+    object::{
+        pattern::{
+            locks::LockedParameter,
+            plock::{ParameterLockPool, RawPlockValue},
+            track::trig::parameter::PlockParameter,
+        },
+        sound::{machine_change::clear_plock, parameter::SoundParameter, types::MachineType},
+        types::ObjectName,
+        Global, Kit, Pattern, Settings, Sound,
+    },
+    util::is_machine_compatible_for_track,
+    RytmProject,
+};
+use parking_lot::Mutex;
+use rytm_sys::ar_sound_t;
+use serde::{de::DeserializeOwned, Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::{Map, Value};
+use std::{collections::BTreeMap, sync::Arc};
+
+/// Keys which tell where an object is stored or how it is transferred rather than what it holds.
+///
+/// Keys starting with `__` are left out as well.
+const IGNORED_KEYS: &[&str] = &[
+    "sysex_meta",
+    "version",
+    "index",
+    "track_index",
+    "owner_pattern_index",
+    "is_owner_pattern_work_buffer",
+    "parameter_lock_pool",
+    "pool_index",
+    "assigned_track",
+];
+
+/// Keys which tell where a sound is stored.
+const IGNORED_SOUND_KEYS: &[&str] = &["kit_number"];
+
+/// Key of the parameter locks of a trig in a pattern snapshot.
+const PLOCKS_KEY: &str = "plocks";
+/// Prefix of the keys of synth parameter locks in a pattern snapshot, followed by the number of the synth parameter.
+const MACHINE_PARAMETER_PREFIX: &str = "machine_parameter_";
+/// Prefix of the keys of unknown parameter locks in a pattern snapshot, followed by the raw plock type.
+const UNKNOWN_PREFIX: &str = "unknown_";
+
+/// Parameter locks of a pattern keyed by track and trig.
+type TrigPlocks = BTreeMap<(u8, usize), Map<String, Value>>;
+
+/// A segment of the path of a parameter.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum PathSegment {
+    /// A named field.
+    Field(String),
+    /// An element of a list.
+    Index(usize),
+}
+
+/// The path of a parameter in an object, for example `kits[3].sounds[0].filter.cutoff`.
+///
+/// Paths are serialized in this text form.
+#[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct DiffPath(Vec<PathSegment>);
+
+impl DiffPath {
+    /// Returns the segments of the path.
+    pub fn segments(&self) -> &[PathSegment] {
+        &self.0
+    }
+
+    fn child(&self, segment: PathSegment) -> Self {
+        let mut segments = self.0.clone();
+        segments.push(segment);
+        Self(segments)
+    }
+
+    fn tail(&self, start: usize) -> Self {
+        Self(self.0[start..].to_vec())
+    }
+
+    fn has_field(&self, name: &str) -> bool {
+        self.0
+            .iter()
+            .any(|segment| matches!(segment, PathSegment::Field(field) if field == name))
+    }
+}
+
+impl std::fmt::Display for DiffPath {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (position, segment) in self.0.iter().enumerate() {
+            match segment {
+                PathSegment::Field(field) if position == 0 => write!(f, "{field}")?,
+                PathSegment::Field(field) => write!(f, ".{field}")?,
+                PathSegment::Index(index) => write!(f, "[{index}]")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+impl TryFrom<&str> for DiffPath {
+    type Error = RytmError;
+
+    fn try_from(path: &str) -> Result<Self, Self::Error> {
+        let invalid = || {
+            ParameterError::Compatibility {
+            value: path.to_owned(),
+            parameter_name: "path".into(),
+            reason: Some("Paths are field names separated by dots, list indices follow in square brackets. For example kits[3].sounds[0].filter.cutoff".into()),
+        }
+        };
+
+        let mut segments = Vec::new();
+        for part in path.split('.') {
+            let field_end = part.find('[').unwrap_or(part.len());
+            let (field, mut indices) = part.split_at(field_end);
+            if field.is_empty()
+                || !field
+                    .chars()
+                    .all(|character| character.is_ascii_alphanumeric() || character == '_')
+            {
+                return Err(invalid().into());
+            }
+            segments.push(PathSegment::Field(field.to_owned()));
+
+            while let Some(rest) = indices.strip_prefix('[') {
+                let (index, rest) = rest.split_once(']').ok_or_else(invalid)?;
+                segments.push(PathSegment::Index(index.parse().map_err(|_| invalid())?));
+                indices = rest;
+            }
+            if !indices.is_empty() {
+                return Err(invalid().into());
+            }
+        }
+
+        Ok(Self(segments))
+    }
+}
+
+impl Serialize for DiffPath {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for DiffPath {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let path = String::deserialize(deserializer)?;
+        Self::try_from(path.as_str()).map_err(serde::de::Error::custom)
+    }
+}
+
+/// A parameter which differs between two objects.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Change {
+    /// Where the parameter is.
+    pub path: DiffPath,
+    /// Value of the parameter before the change, `None` if it did not exist.
+    pub old: Option<Value>,
+    /// Value of the parameter after the change, `None` if it does not exist anymore.
+    pub new: Option<Value>,
+}
+
+impl std::fmt::Display for Change {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        fn value(value: Option<&Value>) -> String {
+            match value {
+                None => "none".to_owned(),
+                Some(Value::String(text)) => text.clone(),
+                Some(value) => value.to_string(),
+            }
+        }
+        write!(
+            f,
+            "{}: {} -> {}",
+            self.path,
+            value(self.old.as_ref()),
+            value(self.new.as_ref())
+        )
+    }
+}
+
+/// The parameters which differ between two objects.
+///
+/// A diff can be applied to another object of the same type as a patch, see [`Diffable::apply_diff`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Diff {
+    changes: Vec<Change>,
+}
+
+impl From<Vec<Change>> for Diff {
+    fn from(changes: Vec<Change>) -> Self {
+        Self { changes }
+    }
+}
+
+impl std::fmt::Display for Diff {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for change in &self.changes {
+            writeln!(f, "{change}")?;
+        }
+        Ok(())
+    }
+}
+
+impl Diff {
+    /// Try to construct a diff from a JSON string.
+    ///
+    /// # Errors
+    /// - JSON might be corrupted.
+    pub fn try_from_str(diff_content: &str) -> Result<Self, RytmError> {
+        let diff = serde_json::from_str(diff_content)?;
+        Ok(diff)
+    }
+
+    /// Try to convert the diff to a JSON string.
+    ///
+    /// # Errors
+    /// - JSON might be corrupted.
+    pub fn try_to_string(&self) -> Result<String, RytmError> {
+        let diff_content = serde_json::to_string(self)?;
+        Ok(diff_content)
+    }
+
+    /// Returns the changed parameters ordered by their paths.
+    pub fn changes(&self) -> &[Change] {
+        &self.changes
+    }
+
+    /// Returns the number of changed parameters.
+    pub const fn len(&self) -> usize {
+        self.changes.len()
+    }
+
+    /// Returns `true` if no parameter differs.
+    pub const fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+}
+
+/// Objects which can be compared parameter by parameter and patched with the differences.
+///
+/// Where an object is stored and how it is transferred, for example its index or its sysex meta, is not compared.
+/// Parameter locks of patterns are listed per trig under `plocks`, for example `tracks[2].trigs[5].plocks.amp_decay`.
+pub trait Diffable {
+    /// Describes the parameters of the object as a tree keyed by their paths.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the object can not be serialized.
+    fn snapshot(&self) -> Result<Value, RytmError>;
+
+    /// Sets every parameter of the diff to its new value.
+    ///
+    /// Old values are not checked so a diff taken between two objects can be applied to a third one.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a path does not exist in this object or a new value is not accepted.
+    ///
+    /// In case of an error this object is left untouched.
+    fn apply_diff(&mut self, diff: &Diff) -> Result<(), RytmError>;
+
+    /// Lists the parameters which differ from the given object, from the values of this object to the values of the given one.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if one of the objects can not be serialized.
+    fn diff(&self, other: &Self) -> Result<Diff, RytmError> {
+        let mut changes = Vec::new();
+        diff_values(
+            &DiffPath::default(),
+            Some(&self.snapshot()?),
+            Some(&other.snapshot()?),
+            &mut changes,
+        );
+        Ok(Diff { changes })
+    }
+}
+
+impl Diffable for Global {
+    fn snapshot(&self) -> Result<Value, RytmError> {
+        object_snapshot(self, &[])
+    }
+
+    fn apply_diff(&mut self, diff: &Diff) -> Result<(), RytmError> {
+        *self = patched(self, &diff.changes.iter().collect::<Vec<_>>())?;
+        Ok(())
+    }
+}
+
+impl Diffable for Settings {
+    fn snapshot(&self) -> Result<Value, RytmError> {
+        object_snapshot(self, &[])
+    }
+
+    fn apply_diff(&mut self, diff: &Diff) -> Result<(), RytmError> {
+        *self = patched(self, &diff.changes.iter().collect::<Vec<_>>())?;
+        Ok(())
+    }
+}
+
+impl Diffable for Sound {
+    fn snapshot(&self) -> Result<Value, RytmError> {
+        object_snapshot(self, IGNORED_SOUND_KEYS)
+    }
+
+    fn apply_diff(&mut self, diff: &Diff) -> Result<(), RytmError> {
+        *self = patched_sound(self, &diff.changes.iter().collect::<Vec<_>>())?;
+        Ok(())
+    }
+}
+
+impl Diffable for Kit {
+    fn snapshot(&self) -> Result<Value, RytmError> {
+        object_snapshot(self, IGNORED_SOUND_KEYS)
+    }
+
+    fn apply_diff(&mut self, diff: &Diff) -> Result<(), RytmError> {
+        *self = patched_kit(self, &diff.changes.iter().collect::<Vec<_>>())?;
+        Ok(())
+    }
+}
+
+impl Diffable for Pattern {
+    fn snapshot(&self) -> Result<Value, RytmError> {
+        let mut snapshot = object_snapshot(self, &[])?;
+        let mut plocks = pattern_plocks(self)?;
+        if let Some(snapshot) = snapshot.as_object_mut() {
+            if let Some(tracks) = snapshot.get_mut("tracks").and_then(Value::as_array_mut) {
+                for (track_index, track) in tracks.iter_mut().enumerate() {
+                    insert_plocks(track, track_index as u8, &mut plocks);
+                }
+            }
+            if let Some(fx_track) = snapshot.get_mut("fx_track") {
+                insert_plocks(fx_track, 12, &mut plocks);
+            }
+        }
+        Ok(snapshot)
+    }
+
+    fn apply_diff(&mut self, diff: &Diff) -> Result<(), RytmError> {
+        let patched = patched_pattern(self, &diff.changes.iter().collect::<Vec<_>>())?;
+        self.copy_data_from(&patched);
+        Ok(())
+    }
+}
+
+/// The object of a project which a change belongs to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum ProjectTarget {
+    Pattern(usize),
+    Kit(usize),
+    PoolSound(usize),
+    Global(usize),
+    Settings,
+    WorkBufferPattern,
+    WorkBufferKit,
+    WorkBufferSound(usize),
+    WorkBufferGlobal,
+}
+
+/// A patched copy of an object of a project waiting to replace the original.
+///
+/// `None` stands for the object in the work buffer.
+enum StagedObject {
+    Pattern(Option<usize>, Pattern),
+    Kit(Option<usize>, Box<Kit>),
+    PoolSound(usize, Sound),
+    WorkBufferSound(usize, Sound),
+    Global(Option<usize>, Global),
+    Settings(Box<Settings>),
+}
+
+impl Diffable for RytmProject {
+    fn snapshot(&self) -> Result<Value, RytmError> {
+        let mut work_buffer = Map::new();
+        work_buffer.insert("pattern".into(), self.work_buffer.pattern.snapshot()?);
+        work_buffer.insert("kit".into(), self.work_buffer.kit.snapshot()?);
+        work_buffer.insert("sounds".into(), snapshots(&self.work_buffer.sounds)?);
+        work_buffer.insert("global".into(), self.work_buffer.global.snapshot()?);
+
+        let mut snapshot = Map::new();
+        snapshot.insert("patterns".into(), snapshots(&self.patterns)?);
+        snapshot.insert("kits".into(), snapshots(&self.kits)?);
+        snapshot.insert("pool_sounds".into(), snapshots(&self.pool_sounds)?);
+        snapshot.insert("globals".into(), snapshots(&self.globals)?);
+        snapshot.insert("settings".into(), self.settings.snapshot()?);
+        snapshot.insert("work_buffer".into(), Value::Object(work_buffer));
+        Ok(Value::Object(snapshot))
+    }
+
+    fn apply_diff(&mut self, diff: &Diff) -> Result<(), RytmError> {
+        let mut targets = BTreeMap::<ProjectTarget, Vec<Change>>::new();
+        for change in &diff.changes {
+            let (target, start) =
+                project_target(&change.path).ok_or_else(|| missing(&change.path))?;
+            targets.entry(target).or_default().push(Change {
+                path: change.path.tail(start),
+                old: change.old.clone(),
+                new: change.new.clone(),
+            });
+        }
+
+        // Every object is patched first so that nothing changes if one of them fails.
+        let mut staged = Vec::new();
+        for (&target, changes) in &targets {
+            let changes = changes.iter().collect::<Vec<_>>();
+            let not_found = || ParameterError::Compatibility {
+                value: format!("{target:?}"),
+                parameter_name: "path".into(),
+                reason: Some("The project does not have this object.".into()),
+            };
+            staged.push(match target {
+                ProjectTarget::Pattern(index) => StagedObject::Pattern(
+                    Some(index),
+                    patched_pattern(self.patterns.get(index).ok_or_else(not_found)?, &changes)?,
+                ),
+                ProjectTarget::WorkBufferPattern => StagedObject::Pattern(
+                    None,
+                    patched_pattern(&self.work_buffer.pattern, &changes)?,
+                ),
+                ProjectTarget::Kit(index) => StagedObject::Kit(
+                    Some(index),
+                    Box::new(patched_kit(
+                        self.kits.get(index).ok_or_else(not_found)?,
+                        &changes,
+                    )?),
+                ),
+                ProjectTarget::WorkBufferKit => StagedObject::Kit(
+                    None,
+                    Box::new(patched_kit(&self.work_buffer.kit, &changes)?),
+                ),
+                ProjectTarget::PoolSound(index) => StagedObject::PoolSound(
+                    index,
+                    patched_sound(self.pool_sounds.get(index).ok_or_else(not_found)?, &changes)?,
+                ),
+                ProjectTarget::WorkBufferSound(index) => StagedObject::WorkBufferSound(
+                    index,
+                    patched_sound(
+                        self.work_buffer.sounds.get(index).ok_or_else(not_found)?,
+                        &changes,
+                    )?,
+                ),
+                ProjectTarget::Global(index) => StagedObject::Global(
+                    Some(index),
+                    patched(self.globals.get(index).ok_or_else(not_found)?, &changes)?,
+                ),
+                ProjectTarget::WorkBufferGlobal => {
+                    StagedObject::Global(None, patched(&self.work_buffer.global, &changes)?)
+                }
+                ProjectTarget::Settings => {
+                    StagedObject::Settings(Box::new(patched(&self.settings, &changes)?))
+                }
+            });
+        }
+
+        for object in staged {
+            match object {
+                StagedObject::Pattern(Some(index), pattern) => {
+                    self.patterns[index].copy_data_from(&pattern);
+                }
+                StagedObject::Pattern(None, pattern) => {
+                    self.work_buffer.pattern.copy_data_from(&pattern);
+                }
+                StagedObject::Kit(Some(index), kit) => self.kits[index] = *kit,
+                StagedObject::Kit(None, kit) => self.work_buffer.kit = *kit,
+                StagedObject::PoolSound(index, sound) => self.pool_sounds[index] = sound,
+                StagedObject::WorkBufferSound(index, sound) => {
+                    self.work_buffer.sounds[index] = sound;
+                }
+                StagedObject::Global(Some(index), global) => self.globals[index] = global,
+                StagedObject::Global(None, global) => self.work_buffer.global = global,
+                StagedObject::Settings(settings) => self.settings = *settings,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Finds the object of a project which a path belongs to and the number of segments which lead to it.
+fn project_target(path: &DiffPath) -> Option<(ProjectTarget, usize)> {
+    use PathSegment::{Field, Index};
+    Some(match path.segments() {
+        [Field(field), Index(index), ..] => (
+            match field.as_str() {
+                "patterns" => ProjectTarget::Pattern(*index),
+                "kits" => ProjectTarget::Kit(*index),
+                "pool_sounds" => ProjectTarget::PoolSound(*index),
+                "globals" => ProjectTarget::Global(*index),
+                _ => return None,
+            },
+            2,
+        ),
+        [Field(field), ..] if field == "settings" => (ProjectTarget::Settings, 1),
+        [Field(work_buffer), Field(field), Index(index), ..]
+            if work_buffer == "work_buffer" && field == "sounds" =>
+        {
+            (ProjectTarget::WorkBufferSound(*index), 3)
+        }
+        [Field(work_buffer), Field(field), ..] if work_buffer == "work_buffer" => (
+            match field.as_str() {
+                "pattern" => ProjectTarget::WorkBufferPattern,
+                "kit" => ProjectTarget::WorkBufferKit,
+                "global" => ProjectTarget::WorkBufferGlobal,
+                _ => return None,
+            },
+            2,
+        ),
+        _ => return None,
+    })
+}
+
+fn missing(path: &DiffPath) -> RytmError {
+    ParameterError::Compatibility {
+        value: path.to_string(),
+        parameter_name: "path".into(),
+        reason: Some("The object does not have a parameter at this path.".into()),
+    }
+    .into()
+}
+
+fn snapshots<T: Diffable>(objects: &[T]) -> Result<Value, RytmError> {
+    objects
+        .iter()
+        .map(Diffable::snapshot)
+        .collect::<Result<Vec<_>, _>>()
+        .map(Value::Array)
+}
+
+/// Serializes an object leaving out the keys which are not compared.
+fn object_snapshot<T: Serialize>(object: &T, ignored_keys: &[&str]) -> Result<Value, RytmError> {
+    let mut snapshot = serde_json::to_value(object)?;
+    strip(&mut snapshot, ignored_keys);
+    Ok(snapshot)
+}
+
+fn is_ignored(key: &str, ignored_keys: &[&str]) -> bool {
+    key.starts_with("__") || IGNORED_KEYS.contains(&key) || ignored_keys.contains(&key)
+}
+
+/// Removes the keys which are not compared and turns object names into text.
+fn strip(value: &mut Value, ignored_keys: &[&str]) {
+    match value {
+        Value::Object(map) => {
+            map.retain(|key, _| !is_ignored(key, ignored_keys));
+            for (key, value) in map.iter_mut() {
+                if key == "name" {
+                    if let Ok(name) = serde_json::from_value::<ObjectName>(value.clone()) {
+                        *value = Value::String(name.to_string());
+                        continue;
+                    }
+                }
+                strip(value, ignored_keys);
+            }
+        }
+        Value::Array(values) => {
+            for value in values {
+                strip(value, ignored_keys);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Collects the differences of two snapshots, a key or an element which exists on one side only is a single change.
+fn diff_values(
+    path: &DiffPath,
+    old: Option<&Value>,
+    new: Option<&Value>,
+    changes: &mut Vec<Change>,
+) {
+    match (old, new) {
+        (Some(Value::Object(old)), Some(Value::Object(new))) => {
+            let mut keys = old.keys().chain(new.keys()).collect::<Vec<_>>();
+            keys.sort();
+            keys.dedup();
+            for key in keys {
+                diff_values(
+                    &path.child(PathSegment::Field(key.clone())),
+                    old.get(key),
+                    new.get(key),
+                    changes,
+                );
+            }
+        }
+        (Some(Value::Array(old)), Some(Value::Array(new))) => {
+            for index in 0..old.len().max(new.len()) {
+                diff_values(
+                    &path.child(PathSegment::Index(index)),
+                    old.get(index),
+                    new.get(index),
+                    changes,
+                );
+            }
+        }
+        (old, new) if old != new => changes.push(Change {
+            path: path.clone(),
+            old: old.cloned(),
+            new: new.cloned(),
+        }),
+        _ => {}
+    }
+}
+
+/// Sets the value at a path of a serialized object, `None` removes it.
+///
+/// Removing a value which does not exist does nothing.
+fn set_value(root: &mut Value, path: &DiffPath, new: Option<&Value>) -> Result<(), RytmError> {
+    let (last, parents) = path.segments().split_last().ok_or_else(|| missing(path))?;
+    let mut target = root;
+    for segment in parents {
+        let next = match (segment, target) {
+            (PathSegment::Field(field), Value::Object(map)) => map.get_mut(field),
+            (PathSegment::Index(index), Value::Array(values)) => values.get_mut(*index),
+            _ => None,
+        };
+        target = match next {
+            Some(next) => next,
+            None if new.is_none() => return Ok(()),
+            None => return Err(missing(path)),
+        };
+    }
+
+    match (last, target, new) {
+        (PathSegment::Field(field), Value::Object(map), Some(new)) => {
+            // Names are compared as text but stored as bytes.
+            let new = match (map.get(field), new) {
+                (Some(Value::Array(_)), Value::String(name)) if field == "name" => {
+                    serde_json::to_value(ObjectName::try_from(name.as_str())?)?
+                }
+                _ => new.clone(),
+            };
+            map.insert(field.clone(), new);
+        }
+        (PathSegment::Field(field), Value::Object(map), None) => {
+            map.remove(field);
+        }
+        (PathSegment::Index(index), Value::Array(values), Some(new)) if *index < values.len() => {
+            values[*index] = new.clone();
+        }
+        (PathSegment::Index(index), Value::Array(values), Some(new)) if *index == values.len() => {
+            values.push(new.clone());
+        }
+        (PathSegment::Index(index), Value::Array(values), None) => values.truncate(*index),
+        _ => return Err(missing(path)),
+    }
+    Ok(())
+}
+
+/// Makes a copy of an object with the changes applied through its serialized form.
+fn patched<T: Serialize + DeserializeOwned>(
+    object: &T,
+    changes: &[&Change],
+) -> Result<T, RytmError> {
+    let mut value = serde_json::to_value(object)?;
+    for change in changes {
+        let compared =
+            change.path.segments().iter().all(
+                |segment| !matches!(segment, PathSegment::Field(field) if is_ignored(field, &[])),
+            );
+        if !compared {
+            return Err(missing(&change.path));
+        }
+        set_value(&mut value, &change.path, change.new.as_ref())?;
+    }
+    let patched: T = serde_json::from_value(value.clone())?;
+
+    // Fields which the object does not have are dropped silently when deserializing, make sure every change took effect.
+    let result = serde_json::to_value(&patched)?;
+    for change in changes {
+        let expected = value_at(&value, &change.path);
+        let actual = value_at(&result, &change.path);
+        let took_effect = match (expected, actual) {
+            (Some(Value::Number(expected)), Some(Value::Number(actual))) => {
+                expected.as_f64().map(|value| value as f32)
+                    == actual.as_f64().map(|value| value as f32)
+            }
+            (expected, actual) => expected == actual,
+        };
+        if !took_effect {
+            return Err(missing(&change.path));
+        }
+    }
+    Ok(patched)
+}
+
+/// Returns the value at a path of a serialized object.
+fn value_at<'a>(root: &'a Value, path: &DiffPath) -> Option<&'a Value> {
+    path.segments()
+        .iter()
+        .try_fold(root, |value, segment| match (segment, value) {
+            (PathSegment::Field(field), Value::Object(map)) => map.get(field),
+            (PathSegment::Index(index), Value::Array(values)) => values.get(*index),
+            _ => None,
+        })
+}
+
+fn patched_sound(sound: &Sound, changes: &[&Change]) -> Result<Sound, RytmError> {
+    let mut patched_sound = patched(sound, changes)?;
+    relink_sound(&mut patched_sound, sound.parameter_lock_pool.clone())?;
+    Ok(patched_sound)
+}
+
+fn patched_kit(kit: &Kit, changes: &[&Change]) -> Result<Kit, RytmError> {
+    let mut patched_kit = patched(kit, changes)?;
+    for (patched_sound, sound) in patched_kit.sounds_mut().iter_mut().zip(kit.sounds()) {
+        relink_sound(patched_sound, sound.parameter_lock_pool.clone())?;
+    }
+    Ok(patched_kit)
+}
+
+/// Links a patched sound to the parameter lock pool of the original and rebuilds its machine parameters for its track.
+fn relink_sound(
+    sound: &mut Sound,
+    parameter_lock_pool: Option<Arc<Mutex<ParameterLockPool>>>,
+) -> Result<(), RytmError> {
+    let machine_type = sound.machine_type();
+    if let Some(assigned_track) = sound.assigned_track() {
+        if !is_machine_compatible_for_track(assigned_track, machine_type) {
+            return Err(ParameterError::Compatibility {
+                value: machine_type.to_string(),
+                parameter_name: "Machine".to_string(),
+                reason: Some(format!(
+                    "Given machine {machine_type} is not compatible for track {assigned_track}"
+                )),
+            }
+            .into());
+        }
+    }
+
+    sound.parameter_lock_pool = parameter_lock_pool;
+    if machine_type != MachineType::Unset {
+        // Rebuilding through the raw sound quantizes some values, set them back through the setters of the machine.
+        let values = machine_type
+            .parameters()
+            .into_iter()
+            .flatten()
+            .map(|parameter| {
+                let parameter = SoundParameter::Machine(parameter.number());
+                sound.get(parameter).map(|value| (parameter, value))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let raw_sound = ar_sound_t::from(&*sound);
+        sound.set_machine_parameters_from_raw_sound(&raw_sound)?;
+        for (parameter, value) in values {
+            sound.set(parameter, value)?;
+        }
+    }
+    Ok(())
+}
+
+/// Makes a copy of a pattern with the changes applied, parameter locks are set through the trigs of the copy.
+fn patched_pattern(pattern: &Pattern, changes: &[&Change]) -> Result<Pattern, RytmError> {
+    let (plock_changes, changes): (Vec<&Change>, Vec<&Change>) = changes
+        .iter()
+        .partition(|change| change.path.has_field(PLOCKS_KEY));
+
+    let patched_pattern = patched(pattern, &changes)?;
+    for change in plock_changes {
+        apply_plock_change(&patched_pattern, change)?;
+    }
+    Ok(patched_pattern)
+}
+
+fn apply_plock_change(pattern: &Pattern, change: &Change) -> Result<(), RytmError> {
+    use PathSegment::{Field, Index};
+    let (track_index, trig_index, name) = match change.path.segments() {
+        [Field(tracks), Index(track_index), Field(trigs), Index(trig_index), Field(plocks), Field(name)]
+            if tracks == "tracks"
+                && trigs == "trigs"
+                && plocks == PLOCKS_KEY
+                && *track_index < 12 =>
+        {
+            (*track_index as u8, *trig_index, name)
+        }
+        [Field(fx_track), Field(trigs), Index(trig_index), Field(plocks), Field(name)]
+            if fx_track == "fx_track" && trigs == "trigs" && plocks == PLOCKS_KEY =>
+        {
+            (12, *trig_index, name)
+        }
+        _ => return Err(missing(&change.path)),
+    };
+    // FX values are stored for the step, any track can set them.
+    let trig = pattern.tracks()[usize::from(track_index % 12)]
+        .trigs()
+        .get(trig_index)
+        .ok_or_else(|| missing(&change.path))?;
+
+    if let Ok(parameter) = PlockParameter::try_from(name.as_str()) {
+        if parameter.is_fx() != (track_index == 12) {
+            return Err(missing(&change.path));
+        }
+        return match &change.new {
+            None => trig.plock_clear_parameter(parameter),
+            Some(value) => trig.plock_set_parameter(
+                parameter,
+                value
+                    .as_f64()
+                    .ok_or_else(|| ParameterError::Compatibility {
+                        value: value.to_string(),
+                        parameter_name: name.clone(),
+                        reason: Some("Parameter lock values are numbers.".into()),
+                    })? as f32,
+            ),
+        };
+    }
+
+    let plock_type = raw_plock_type(track_index, name).ok_or_else(|| missing(&change.path))?;
+    let new = change
+        .new
+        .clone()
+        .map(serde_json::from_value::<RawPlockValue>)
+        .transpose()?;
+
+    let mut pool = pattern.parameter_lock_pool.lock();
+    let current = pool
+        .values()
+        .into_iter()
+        .find(|&(track, trig, stored_type, _)| {
+            track == track_index && trig == trig_index && stored_type == plock_type
+        });
+    if let Some((_, _, _, value)) = current {
+        clear_plock(&mut pool, trig_index, track_index, plock_type, value);
+    }
+    match new {
+        Some(RawPlockValue::Basic(value)) => {
+            pool.set_basic_plock(trig_index, track_index, plock_type, value)
+        }
+        Some(RawPlockValue::Compound(value)) => {
+            pool.set_compound_plock(trig_index, track_index, plock_type, value)
+        }
+        None => Ok(()),
+    }
+}
+
+/// Finds the plock type of a synth parameter or unknown parameter lock key.
+fn raw_plock_type(track_index: u8, name: &str) -> Option<u8> {
+    if let Some(number) = name.strip_prefix(MACHINE_PARAMETER_PREFIX) {
+        let number = number
+            .parse::<u8>()
+            .ok()
+            .filter(|number| (1..=8).contains(number))?;
+        return (track_index != 12).then_some(rytm_sys::AR_PLOCK_TYPE_MP0 as u8 + number - 1);
+    }
+    name.strip_prefix(UNKNOWN_PREFIX)?.parse().ok()
+}
+
+/// Collects the parameter locks of a pattern per track and trig, keyed as they appear in a snapshot.
+fn pattern_plocks(pattern: &Pattern) -> Result<TrigPlocks, RytmError> {
+    let mut plocks = TrigPlocks::new();
+    let values = pattern.parameter_lock_pool.lock().values();
+    for (track_index, trig_index, plock_type, raw_value) in values {
+        let (key, value) = match LockedParameter::new(track_index, plock_type) {
+            LockedParameter::Parameter(parameter) => {
+                let trig = &pattern.tracks()[usize::from(track_index % 12)].trigs()[trig_index];
+                let Some(value) = trig.plock_get_parameter(parameter)? else {
+                    continue;
+                };
+                let value = if value.fract() == 0.0 {
+                    Value::from(value as i64)
+                } else {
+                    Value::from(f64::from(value))
+                };
+                (<&str>::from(parameter).to_owned(), value)
+            }
+            LockedParameter::MachineParameter(number) => (
+                format!("{MACHINE_PARAMETER_PREFIX}{number}"),
+                serde_json::to_value(raw_value)?,
+            ),
+            LockedParameter::Unknown(plock_type) => (
+                format!("{UNKNOWN_PREFIX}{plock_type}"),
+                serde_json::to_value(raw_value)?,
+            ),
+        };
+        plocks
+            .entry((track_index, trig_index))
+            .or_default()
+            .insert(key, value);
+    }
+    Ok(plocks)
+}
+
+/// Adds the parameter locks of each trig of a serialized track, trigs without parameter locks get an empty list.
+fn insert_plocks(track: &mut Value, track_index: u8, plocks: &mut TrigPlocks) {
+    let Some(trigs) = track.get_mut("trigs").and_then(Value::as_array_mut) else {
+        return;
+    };
+    for (trig_index, trig) in trigs.iter_mut().enumerate() {
+        if let Value::Object(trig) = trig {
+            let trig_plocks = plocks
+                .remove(&(track_index, trig_index))
+                .unwrap_or_default();
+            trig.insert(PLOCKS_KEY.into(), Value::Object(trig_plocks));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::object::pattern::track::trig::HoldsTrigFlags;
+
+    /// Returns a project which differs from the default one in a pattern, a kit and a pool sound.
+    fn changed_project() -> RytmProject {
+        let mut project = RytmProject::try_default().unwrap();
+
+        let trig = &mut project.patterns[3].tracks_mut()[0].trigs_mut()[4];
+        trig.set_trig_enable(true);
+        trig.set_velocity(110).unwrap();
+        trig.plock_set_parameter(PlockParameter::FilterCutoff, 40.0)
+            .unwrap();
+
+        project.kits[1].set_name("DIFFED").unwrap();
+        project.kits[1].set_track_level(2, 90).unwrap();
+
+        let sound = &mut project.pool_sounds[5];
+        sound.set_machine_type(MachineType::BdHard).unwrap();
+        let tun = sound.parameter_by_name("tun").unwrap();
+        sound.set(tun, 1.3).unwrap();
+
+        project
+    }
+
+    #[test]
+    fn serialized_diff_patches_a_copy_into_the_other_project() {
+        let a = RytmProject::try_default().unwrap();
+        let b = changed_project();
+
+        let diff = a.diff(&b).unwrap();
+        assert!(!diff.is_empty());
+        let diff = Diff::try_from_str(&diff.try_to_string().unwrap()).unwrap();
+
+        // A fresh project rather than a clone, clones of patterns share their parameter lock pool.
+        let mut copy_of_a = RytmProject::try_default().unwrap();
+        copy_of_a.apply_diff(&diff).unwrap();
+
+        assert!(copy_of_a.diff(&b).unwrap().is_empty());
+        assert_eq!(copy_of_a.snapshot().unwrap(), b.snapshot().unwrap());
+    }
+
+    #[test]
+    fn bad_path_leaves_the_project_untouched() {
+        let mut project = RytmProject::try_default().unwrap();
+        let before = project.snapshot().unwrap();
+
+        // The first change is valid, it must not be applied either.
+        let diff = Diff::from(vec![
+            Change {
+                path: DiffPath::try_from("kits[1].name").unwrap(),
+                old: None,
+                new: Some(Value::String("DIFFED".into())),
+            },
+            Change {
+                path: DiffPath::try_from("kits[1].no_such_parameter").unwrap(),
+                old: None,
+                new: Some(Value::from(1)),
+            },
+        ]);
+
+        assert!(project.apply_diff(&diff).is_err());
+        assert_eq!(project.snapshot().unwrap(), before);
+    }
+}
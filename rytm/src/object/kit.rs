@@ -18,6 +18,8 @@ pub mod delay;
 pub mod dist;
 /// Holds the structure to represent lfo fx parameters.
 pub mod lfo;
+/// Holds the identifiers of the parameters of a kit.
+pub mod parameter;
 /// Holds the structure to represent retrig settings scoped to a track.
 pub mod retrig;
 /// Holds the structure to represent reverb fx parameters.
@@ -0,0 +1,415 @@
+// All casts in this file are intended or safe within the context of this library.
+//
+// One can change `allow` to `warn` to review them if necessary.
+#![allow(
+    clippy::cast_possible_truncation,
+    clippy::cast_precision_loss,
+    clippy::cast_sign_loss
+)]
+#![allow(clippy::enum_glob_use, clippy::too_many_lines)]
+
+use super::Kit;
+use crate::{
+    error::{ParameterError, RytmError},
+    object::{
+        pattern::track::trig::{
+            parameter::{PlockParameter, PlockValueKind},
+            Trig,
+        },
+        sound::parameter::SoundParameter,
+        Sound,
+    },
+};
+use serde::{Deserialize, Serialize};
+use std::ops::RangeInclusive;
+
+/// Identifies a parameter of a kit.
+///
+/// Values of the parameters are numeric, see [`KitParameter::kind`] for how they are interpreted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum KitParameter {
+    /// A parameter of the sound of a track.
+    ///
+    /// Range `0..=11` for the track index.
+    Sound {
+        track_index: usize,
+        parameter: SoundParameter,
+    },
+    /// A parameter of the FX track.
+    ///
+    /// Only the FX parameters of [`PlockParameter`] are FX parameters, see [`PlockParameter::is_fx`].
+    Fx(PlockParameter),
+    /// The level of a track.
+    ///
+    /// Range `0..=12` for the track index, `12` is the FX track.
+    ///
+    /// Track levels can not be locked.
+    TrackLevel(usize),
+}
+
+impl KitParameter {
+    /// Returns how the numeric value of the parameter is interpreted in the given kit.
+    ///
+    /// Returns `None` if the kit does not have the parameter.
+    pub fn kind(self, kit: &Kit) -> Option<PlockValueKind> {
+        match self {
+            Self::Sound {
+                track_index,
+                parameter,
+            } => kit
+                .sounds()
+                .get(track_index)
+                .and_then(|sound| parameter.kind(sound.machine_type())),
+            Self::Fx(parameter) => parameter.is_fx().then(|| parameter.kind()),
+            Self::TrackLevel(track_index) => (track_index <= 12).then_some(PlockValueKind::Integer),
+        }
+    }
+
+    /// Returns the range of the numeric value of the parameter in the given kit.
+    ///
+    /// Returns `None` if the kit does not have the parameter.
+    pub fn range(self, kit: &Kit) -> Option<RangeInclusive<f32>> {
+        match self {
+            Self::Sound {
+                track_index,
+                parameter,
+            } => kit
+                .sounds()
+                .get(track_index)
+                .and_then(|sound| parameter.range(sound.machine_type())),
+            Self::Fx(parameter) => parameter.is_fx().then(|| parameter.range()),
+            Self::TrackLevel(track_index) => (track_index <= 12).then_some(0.0..=127.0),
+        }
+    }
+
+    /// Checks if the parameter can be locked in a trig.
+    pub const fn is_lockable(self) -> bool {
+        match self {
+            Self::Sound { parameter, .. } => parameter.is_lockable(),
+            Self::Fx(_) => true,
+            Self::TrackLevel(_) => false,
+        }
+    }
+}
+
+const fn toggle(value: bool) -> f32 {
+    if value {
+        1.0
+    } else {
+        0.0
+    }
+}
+
+fn choice<T: Into<u8>>(value: T) -> f32 {
+    f32::from(value.into())
+}
+
+impl Kit {
+    /// Returns every parameter of this kit.
+    ///
+    /// The parameters of the sounds in the order of the tracks are followed by the FX parameters and the track levels.
+    pub fn parameters(&self) -> Vec<KitParameter> {
+        self.sounds()
+            .iter()
+            .enumerate()
+            .flat_map(|(track_index, sound)| {
+                sound
+                    .parameters()
+                    .into_iter()
+                    .map(move |parameter| KitParameter::Sound {
+                        track_index,
+                        parameter,
+                    })
+            })
+            .chain(
+                PlockParameter::ALL
+                    .into_iter()
+                    .filter(|parameter| parameter.is_fx())
+                    .map(KitParameter::Fx),
+            )
+            .chain((0..=12).map(KitParameter::TrackLevel))
+            .collect()
+    }
+
+    /// Gets the numeric value of a parameter of this kit.
+    ///
+    /// The value is represented according to [`KitParameter::kind`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if this kit does not have the parameter.
+    pub fn get(&self, parameter: KitParameter) -> Result<f32, RytmError> {
+        use PlockParameter::*;
+        let fx_parameter = match parameter {
+            KitParameter::Sound {
+                track_index,
+                parameter,
+            } => return self.sound_of_track(track_index)?.get(parameter),
+            KitParameter::Fx(fx_parameter) => fx_parameter,
+            KitParameter::TrackLevel(track_index) => {
+                return Ok(self.track_level(track_index)? as f32)
+            }
+        };
+
+        let (delay, reverb, distortion, compressor, lfo) = (
+            self.fx_delay(),
+            self.fx_reverb(),
+            self.fx_distortion(),
+            self.fx_compressor(),
+            self.fx_lfo(),
+        );
+        Ok(match fx_parameter {
+            FxDelayTime => delay.time() as f32,
+            FxDelayPingPong => toggle(delay.ping_pong()),
+            FxDelayStereoWidth => delay.stereo_width() as f32,
+            FxDelayFeedback => delay.feedback() as f32,
+            FxDelayHpf => delay.hpf() as f32,
+            FxDelayLpf => delay.lpf() as f32,
+            FxDelayReverbSend => delay.reverb_send() as f32,
+            FxDelayVolume => delay.volume() as f32,
+            FxReverbPreDelay => reverb.pre_delay() as f32,
+            FxReverbDecay => reverb.decay() as f32,
+            FxReverbFreq => reverb.freq() as f32,
+            FxReverbGain => reverb.gain() as f32,
+            FxReverbHpf => reverb.hpf() as f32,
+            FxReverbLpf => reverb.lpf() as f32,
+            FxReverbVolume => reverb.volume() as f32,
+            FxDistortionReverbPost => toggle(distortion.reverb_post()),
+            FxDistortionDelayOverdrive => distortion.delay_overdrive() as f32,
+            FxDistortionDelayPost => toggle(distortion.delay_post()),
+            FxDistortionAmount => distortion.amount() as f32,
+            FxDistortionSymmetry => distortion.symmetry() as f32,
+            FxCompressorThreshold => compressor.threshold() as f32,
+            FxCompressorAttack => choice(*compressor.attack()),
+            FxCompressorRelease => choice(*compressor.release()),
+            FxCompressorRatio => choice(*compressor.ratio()),
+            FxCompressorSideChainEq => choice(*compressor.side_chain_eq()),
+            FxCompressorGain => compressor.gain() as f32,
+            FxCompressorMix => compressor.mix() as f32,
+            FxCompressorVolume => compressor.volume() as f32,
+            FxLfoSpeed => lfo.speed() as f32,
+            FxLfoMultiplier => choice(*lfo.multiplier()),
+            FxLfoFade => lfo.fade() as f32,
+            FxLfoDestination => choice(*lfo.destination()),
+            FxLfoWaveform => choice(*lfo.waveform()),
+            FxLfoStartPhase => lfo.start_phase_or_slew() as f32,
+            FxLfoMode => choice(*lfo.mode()),
+            FxLfoDepth => lfo.depth(),
+            _ => return Err(not_an_fx_parameter(fx_parameter)),
+        })
+    }
+
+    /// Sets a parameter of this kit by its identifier and numeric value.
+    ///
+    /// The value is interpreted according to [`KitParameter::kind`] and validated against [`KitParameter::range`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if this kit does not have the parameter or the value is not accepted by the parameter.
+    pub fn set(&mut self, parameter: KitParameter, value: f32) -> Result<(), RytmError> {
+        use PlockParameter::*;
+        let fx_parameter = match parameter {
+            KitParameter::Sound {
+                track_index,
+                parameter,
+            } => {
+                self.sound_of_track(track_index)?;
+                return self.sounds_mut()[track_index].set(parameter, value);
+            }
+            KitParameter::Fx(fx_parameter) if !fx_parameter.is_fx() => {
+                return Err(not_an_fx_parameter(fx_parameter))
+            }
+            KitParameter::Fx(fx_parameter) => fx_parameter,
+            KitParameter::TrackLevel(track_index) => {
+                return self.set_track_level(track_index, value.round() as usize)
+            }
+        };
+
+        let value = fx_parameter.validate(value)?;
+        let code = value as u8;
+        let whole = value as isize;
+        let toggle = value != 0.0;
+
+        match fx_parameter {
+            FxDelayTime => self.fx_delay_mut().set_time(whole as usize),
+            FxDelayPingPong => {
+                self.fx_delay_mut().set_ping_pong(toggle);
+                Ok(())
+            }
+            FxDelayStereoWidth => self.fx_delay_mut().set_stereo_width(whole),
+            FxDelayFeedback => self.fx_delay_mut().set_feedback(whole as usize),
+            FxDelayHpf => self.fx_delay_mut().set_hpf(whole as usize),
+            FxDelayLpf => self.fx_delay_mut().set_lpf(whole as usize),
+            FxDelayReverbSend => self.fx_delay_mut().set_reverb_send(whole as usize),
+            FxDelayVolume => self.fx_delay_mut().set_volume(whole as usize),
+            FxReverbPreDelay => self.fx_reverb_mut().set_pre_delay(whole as usize),
+            FxReverbDecay => self.fx_reverb_mut().set_decay(whole as usize),
+            FxReverbFreq => self.fx_reverb_mut().set_freq(whole as usize),
+            FxReverbGain => self.fx_reverb_mut().set_gain(whole as usize),
+            FxReverbHpf => self.fx_reverb_mut().set_hpf(whole as usize),
+            FxReverbLpf => self.fx_reverb_mut().set_lpf(whole as usize),
+            FxReverbVolume => self.fx_reverb_mut().set_volume(whole as usize),
+            FxDistortionReverbPost => {
+                self.fx_distortion_mut().set_reverb_post(toggle);
+                Ok(())
+            }
+            FxDistortionDelayOverdrive => {
+                self.fx_distortion_mut().set_delay_overdrive(whole as usize)
+            }
+            FxDistortionDelayPost => {
+                self.fx_distortion_mut().set_delay_post(toggle);
+                Ok(())
+            }
+            FxDistortionAmount => self.fx_distortion_mut().set_amount(whole as usize),
+            FxDistortionSymmetry => self.fx_distortion_mut().set_symmetry(whole),
+            FxCompressorThreshold => self.fx_compressor_mut().set_threshold(whole as usize),
+            FxCompressorAttack => {
+                self.fx_compressor_mut().set_attack(code.try_into()?);
+                Ok(())
+            }
+            FxCompressorRelease => {
+                self.fx_compressor_mut().set_release(code.try_into()?);
+                Ok(())
+            }
+            FxCompressorRatio => {
+                self.fx_compressor_mut().set_ratio(code.try_into()?);
+                Ok(())
+            }
+            FxCompressorSideChainEq => {
+                self.fx_compressor_mut().set_side_chain_eq(code.try_into()?);
+                Ok(())
+            }
+            FxCompressorGain => self.fx_compressor_mut().set_gain(whole as usize),
+            FxCompressorMix => self.fx_compressor_mut().set_mix(whole as usize),
+            FxCompressorVolume => self.fx_compressor_mut().set_volume(whole as usize),
+            FxLfoSpeed => self.fx_lfo_mut().set_speed(whole),
+            FxLfoMultiplier => {
+                self.fx_lfo_mut().set_multiplier(code.try_into()?);
+                Ok(())
+            }
+            FxLfoFade => self.fx_lfo_mut().set_fade(whole),
+            FxLfoDestination => {
+                self.fx_lfo_mut().set_destination(code.try_into()?);
+                Ok(())
+            }
+            FxLfoWaveform => {
+                self.fx_lfo_mut().set_waveform(code.try_into()?);
+                Ok(())
+            }
+            FxLfoStartPhase => self.fx_lfo_mut().set_start_phase(whole as usize),
+            FxLfoMode => {
+                self.fx_lfo_mut().set_mode(code.try_into()?);
+                Ok(())
+            }
+            FxLfoDepth => self.fx_lfo_mut().set_depth(value),
+            _ => unreachable!("Sound parameters are rejected above."),
+        }
+    }
+
+    /// Sets a parameter lock for a parameter of this kit in a trig.
+    ///
+    /// Sound parameters can only be locked in the trigs of their track, FX parameters can be locked in any trig.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the parameter can not be locked in the trig, the value is not accepted by the parameter,
+    /// the trig is orphan or the parameter lock memory is full.
+    pub fn plock_set(
+        &self,
+        trig: &Trig,
+        parameter: KitParameter,
+        value: f32,
+    ) -> Result<(), RytmError> {
+        match parameter {
+            KitParameter::Sound {
+                track_index,
+                parameter,
+            } => self
+                .sound_of_track(track_index)?
+                .plock_set(trig, parameter, value),
+            KitParameter::Fx(fx_parameter) if fx_parameter.is_fx() => {
+                trig.plock_set_parameter(fx_parameter, value)
+            }
+            _ => Err(not_lockable(parameter)),
+        }
+    }
+
+    /// Gets the numeric value of a parameter lock for a parameter of this kit in a trig.
+    ///
+    /// The value is represented according to [`KitParameter::kind`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the parameter can not be locked in the trig or the trig is orphan.
+    pub fn plock_get(
+        &self,
+        trig: &Trig,
+        parameter: KitParameter,
+    ) -> Result<Option<f32>, RytmError> {
+        match parameter {
+            KitParameter::Sound {
+                track_index,
+                parameter,
+            } => self.sound_of_track(track_index)?.plock_get(trig, parameter),
+            KitParameter::Fx(fx_parameter) if fx_parameter.is_fx() => {
+                trig.plock_get_parameter(fx_parameter)
+            }
+            _ => Err(not_lockable(parameter)),
+        }
+    }
+
+    /// Clears a parameter lock for a parameter of this kit in a trig.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the parameter can not be locked in the trig or the trig is orphan.
+    pub fn plock_clear(&self, trig: &Trig, parameter: KitParameter) -> Result<(), RytmError> {
+        match parameter {
+            KitParameter::Sound {
+                track_index,
+                parameter,
+            } => self
+                .sound_of_track(track_index)?
+                .plock_clear(trig, parameter),
+            KitParameter::Fx(fx_parameter) if fx_parameter.is_fx() => {
+                trig.plock_clear_parameter(fx_parameter)
+            }
+            _ => Err(not_lockable(parameter)),
+        }
+    }
+
+    /// Returns the sound of a track.
+    fn sound_of_track(&self, track_index: usize) -> Result<&Sound, RytmError> {
+        self.sounds().get(track_index).ok_or_else(|| {
+            ParameterError::Range {
+                value: track_index.to_string(),
+                parameter_name: "track_index".into(),
+            }
+            .into()
+        })
+    }
+}
+
+fn not_an_fx_parameter(parameter: PlockParameter) -> RytmError {
+    ParameterError::Compatibility {
+        value: <&str>::from(parameter).into(),
+        parameter_name: "parameter".into(),
+        reason: Some(
+            "Sound parameters are parameters of the sounds of the kit, use `KitParameter::Sound` for them."
+                .into(),
+        ),
+    }
+    .into()
+}
+
+fn not_lockable(parameter: KitParameter) -> RytmError {
+    match parameter {
+        KitParameter::Fx(fx_parameter) => not_an_fx_parameter(fx_parameter),
+        _ => ParameterError::Compatibility {
+            value: format!("{parameter:?}"),
+            parameter_name: "parameter".into(),
+            reason: Some("Track levels can not be locked.".into()),
+        }
+        .into(),
+    }
+}
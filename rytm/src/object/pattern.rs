@@ -13,6 +13,28 @@
 pub(crate) mod de;
 pub(crate) mod plock;
 
+/// Rhythm analysis reports for patterns.
+pub mod analysis;
+/// Inspection and compaction of the parameter lock budget of patterns.
+pub mod budget;
+/// Fill variations of patterns.
+pub mod fill;
+/// Seeded generation of patterns from constraints.
+pub mod generator;
+/// Iteration over the parameter locks of patterns, tracks and trigs.
+pub mod locks;
+/// Merging of patterns track by track.
+pub mod merge;
+/// Statistical models of patterns learned from examples.
+pub mod model;
+/// A compact text notation for patterns.
+pub mod notation;
+/// Offline recording of note and controller input into trigs and parameter locks.
+pub mod recording;
+/// Selections of steps across tracks and bulk editing.
+pub mod selection;
+/// Rhythmic similarity of patterns and tracks.
+pub mod similarity;
 /// Holds the structure to represent a track.
 pub mod track;
 /// Holds the types used in pattern.
@@ -27,7 +49,10 @@ use crate::{
     error::{ParameterError, RytmError, SysexConversionError},
     impl_sysex_compatible,
     object::pattern::track::Track,
-    sysex::{SysexCompatible, SysexMeta, SysexType, PATTERN_SYSEX_SIZE},
+    sysex::{
+        decode_sysex_response_to_raw, split_sysex_messages, SysexCompatible, SysexMeta, SysexType,
+        PATTERN_SYSEX_SIZE,
+    },
     util::{
         arc_mutex_owner, assemble_u32_from_u8_array_be, break_u32_into_u8_array_be, from_s_u16_t,
         to_s_u16_t_union_b,
@@ -174,9 +199,15 @@ impl Pattern {
     ///
     /// This function only copies the data from the given pattern including the parameter locks.
     pub fn copy_data_from(&mut self, object: &Self) {
-        self.tracks = object.tracks.iter().map(std::clone::Clone::clone).collect();
-        *self.fx_track.lock() = object.fx_track.lock().clone();
-        *self.parameter_lock_pool.lock() = object.parameter_lock_pool.lock().clone();
+        // Tracks keep their links to the parameter lock pool and the FX track of this pattern.
+        for (track, source_track) in self.tracks.iter_mut().zip(&object.tracks) {
+            track.copy_data_from(source_track);
+        }
+        // Clone first, patterns which are cloned from each other share the same FX track and pool.
+        let fx_track = object.fx_track.lock().clone();
+        self.fx_track.lock().copy_data_from(&fx_track);
+        let parameter_lock_pool = object.parameter_lock_pool.lock().clone();
+        *self.parameter_lock_pool.lock() = parameter_lock_pool;
         self.master_length = object.master_length;
         self.master_change = object.master_change;
         self.kit_number = object.kit_number;
@@ -188,6 +219,39 @@ impl Pattern {
         self.pad_scale_per_pattern = object.pad_scale_per_pattern;
     }
 
+    /// Makes a pattern from a pattern sysex message, for example one read from a `.syx` file.
+    ///
+    /// The parameter lock pool of the pattern is not linked to any kit.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the message is not a complete pattern sysex message.
+    pub fn try_from_sysex(sysex: &[u8]) -> Result<Self, RytmError> {
+        if sysex.len() < 2 || sysex[0] != 0xF0 || sysex[sysex.len() - 1] != 0xF7 {
+            return Err(SysexConversionError::NotASysexMsg.into());
+        }
+
+        let (raw, meta) = decode_sysex_response_to_raw(sysex)?;
+        if meta.object_type()? != SysexType::Pattern {
+            return Err(SysexConversionError::NotAPattern.into());
+        }
+
+        let raw_pattern: &ar_pattern_t = unsafe { &*raw.as_ptr().cast::<ar_pattern_t>() };
+        Self::try_from_raw(meta, raw_pattern)
+    }
+
+    /// Makes patterns from a buffer which holds consecutive pattern sysex messages, for example a `.syx` library file.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if any of the messages is not a complete pattern sysex message.
+    pub fn try_many_from_sysex(data: &[u8]) -> Result<Vec<Self>, RytmError> {
+        split_sysex_messages(data)
+            .into_iter()
+            .map(Self::try_from_sysex)
+            .collect()
+    }
+
     #[allow(clippy::too_many_arguments)]
     pub(crate) fn try_from_raw(
         sysex_meta: SysexMeta,
@@ -543,7 +607,123 @@ impl Pattern {
         Ok(())
     }
 
+    /// Mimics the copy and paste track function in rytm.
+    ///
+    /// Copies the trigs, the settings and the parameter locks of a track in the given pattern to a track in this pattern.
+    ///
+    /// The destination track is replaced entirely including its parameter locks.
+    ///
+    /// Range `0..=11` for both track indexes.
+    ///
+    /// # Errors
+    ///
+    /// Parameter locks of the source track are merged into the parameter lock pool of this pattern.
+    /// If they do not fit [`RytmError::ParameterLockMemoryFullWith`] is returned listing the parameter locks which did not fit.
+    /// In that case this pattern is left untouched.
+    #[parameter_range(
+        range = "source_track_index:0..=11",
+        range = "destination_track_index:0..=11"
+    )]
+    pub fn copy_track_from(
+        &mut self,
+        source: &Self,
+        source_track_index: usize,
+        destination_track_index: usize,
+    ) -> Result<(), RytmError> {
+        // Clone the source pool first, patterns which are cloned from each other may share the same pool.
+        let source_pool = source.parameter_lock_pool.lock().clone();
+
+        self.parameter_lock_pool.lock().copy_plocks_of_track_from(
+            &source_pool,
+            source_track_index as u8,
+            destination_track_index as u8,
+        )?;
+
+        self.tracks[destination_track_index].copy_data_from(&source.tracks[source_track_index]);
+
+        Ok(())
+    }
+
     pub(crate) fn set_device_id(&mut self, device_id: u8) {
         self.sysex_meta.set_device_id(device_id);
     }
+
+    /// Takes a copy of the trigs and the parameter locks to restore if an edit fails halfway.
+    pub(crate) fn backup(&self) -> PatternBackup {
+        PatternBackup {
+            parameter_lock_pool: self.parameter_lock_pool.lock().clone(),
+            tracks: self.tracks.clone(),
+            fx_track: self.fx_track.lock().clone(),
+        }
+    }
+
+    /// Restores the trigs, the number of steps of the tracks and the parameter locks from a backup.
+    ///
+    /// The tracks keep their links to the parameter lock pool of this pattern.
+    pub(crate) fn restore(&mut self, backup: &PatternBackup) {
+        *self.parameter_lock_pool.lock() = backup.parameter_lock_pool.clone();
+        for (track, backup) in self
+            .tracks
+            .iter_mut()
+            .chain(std::iter::once(&mut *self.fx_track.lock()))
+            .zip(
+                backup
+                    .tracks
+                    .iter()
+                    .chain(std::iter::once(&backup.fx_track)),
+            )
+        {
+            track.number_of_steps = backup.number_of_steps;
+            for (trig, backup) in track.trigs.iter_mut().zip(&backup.trigs) {
+                trig.copy_data_from(backup);
+            }
+        }
+    }
+}
+
+/// A copy of the trigs and the parameter locks of a pattern.
+pub(crate) struct PatternBackup {
+    parameter_lock_pool: ParameterLockPool,
+    tracks: Vec<Track>,
+    fx_track: Track,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::object::pattern::track::trig::{parameter::PlockParameter, HoldsTrigFlags};
+
+    #[test]
+    fn copied_tracks_write_parameter_locks_into_the_destination_pool() {
+        let mut source = Pattern::try_default(0).unwrap();
+        let trig = &mut source.tracks_mut()[0].trigs_mut()[4];
+        trig.set_trig_enable(true);
+        trig.plock_set_parameter(PlockParameter::FilterCutoff, 40.0)
+            .unwrap();
+
+        let mut destination = Pattern::try_default(1).unwrap();
+        destination.copy_data_from(&source);
+
+        let trig = &destination.tracks()[0].trigs()[4];
+        assert_eq!(
+            trig.plock_get_parameter(PlockParameter::FilterCutoff)
+                .unwrap(),
+            Some(40.0)
+        );
+
+        trig.plock_set_parameter(PlockParameter::FilterResonance, 20.0)
+            .unwrap();
+        assert_eq!(
+            destination.tracks()[0].trigs()[4]
+                .plock_get_parameter(PlockParameter::FilterResonance)
+                .unwrap(),
+            Some(20.0)
+        );
+        assert_eq!(
+            source.tracks()[0].trigs()[4]
+                .plock_get_parameter(PlockParameter::FilterResonance)
+                .unwrap(),
+            None
+        );
+    }
 }
@@ -0,0 +1,274 @@
+// All casts in this file are intended or safe within the context of this library.
+//
+// One can change `allow` to `warn` to review them if necessary.
+#![allow(clippy::cast_possible_truncation, clippy::cast_precision_loss)]
+
+use super::{
+    track::{
+        trig::{types::TrigCondition, HoldsTrigFlags, Trig},
+        Track,
+    },
+    types::Speed,
+    Pattern,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Rhythmic characteristics of a track.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TrackAnalysis {
+    /// Index of the track.
+    pub track_index: usize,
+    /// Number of steps of the track.
+    pub number_of_steps: usize,
+    /// Speed of the track.
+    pub speed: Speed,
+    /// Steps which hold an enabled trig which is not a lock trig.
+    pub onsets: Vec<usize>,
+    /// Steps which hold a lock trig.
+    pub lock_trigs: Vec<usize>,
+    /// Ratio of the onsets to the number of steps, `0.0..=1.0`.
+    pub density: f32,
+    /// Average syncopation of the onsets.
+    ///
+    /// An onset on a weaker position of the metric grid followed by a rest on a stronger position is syncopated,
+    /// the score is the difference of their metrical levels.
+    /// The grid is derived from the number of steps, `16` steps are a bar of sixteenths and `12` steps three beats of four.
+    /// `0.0` means no syncopation.
+    pub syncopation: f32,
+    /// Steps of the onsets which have the accent flag set.
+    pub accents: Vec<usize>,
+    /// Ratio of the accented onsets to all onsets, `0.0..=1.0`.
+    pub accent_ratio: f32,
+    /// Average velocity of the onsets, the track default is used for trigs without a velocity.
+    pub average_velocity: Option<f32>,
+    /// Number of onsets which are not on the grid.
+    pub micro_timed_onsets: usize,
+    /// Average distance of the onsets from the grid, in micro timing units of `1/384`.
+    pub average_absolute_micro_timing: f32,
+    /// Number of onsets which have the swing flag set.
+    pub swing_flagged_onsets: usize,
+    /// Number of onsets per trig condition, onsets without a condition are not counted.
+    pub trig_conditions: BTreeMap<TrigCondition, usize>,
+    /// Number of parameter lock pool slots which the track holds.
+    pub plock_slots: usize,
+}
+
+/// Rhythmic characteristics of a pattern.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PatternAnalysis {
+    /// Index of the pattern.
+    pub pattern_index: usize,
+    /// Master length of the pattern.
+    pub master_length: usize,
+    /// Swing amount of the pattern.
+    pub swing_amount: usize,
+    /// Analysis of the 12 tracks.
+    pub tracks: Vec<TrackAnalysis>,
+    /// Number of parameter lock pool slots which the FX track holds.
+    pub fx_plock_slots: usize,
+    /// Number of parameter lock pool slots in use.
+    pub plock_slots_used: usize,
+    /// Total number of parameter lock pool slots.
+    pub plock_slots_total: usize,
+}
+
+/// Returns the next coarser division of a span of steps in the metric grid.
+///
+/// Odd factors divide first so a span of `12` steps splits into three beats of four steps
+/// and a span of `48` steps into three bars of sixteen steps, the rest is split in halves.
+const fn next_division(span: usize) -> usize {
+    let mut factor = 3;
+    let mut largest_odd_factor = 1;
+    let mut rest = span;
+    while rest.is_multiple_of(2) {
+        rest /= 2;
+    }
+    while factor <= rest {
+        if rest.is_multiple_of(factor) {
+            largest_odd_factor = factor;
+            rest /= factor;
+        } else {
+            factor += 2;
+        }
+    }
+    if largest_odd_factor > 1 {
+        span / largest_odd_factor
+    } else {
+        span / 2
+    }
+}
+
+/// Returns the metrical level of a step in the metric grid of a cycle of the given length.
+///
+/// The grid is derived from the length by dividing the cycle repeatedly, see [`next_division`].
+/// The level is the number of divisions which start on the step, so the downbeat is the strongest position.
+/// For `16` steps this is `4` for the downbeat, `3` for the half bar, `2` for the quarters, `1` for the eighths and `0` for the rest.
+const fn metrical_level(step: usize, number_of_steps: usize) -> usize {
+    let step = step % number_of_steps;
+    let mut level = 0;
+    let mut span = number_of_steps;
+    while span > 1 {
+        if step.is_multiple_of(span) {
+            level += 1;
+        }
+        span = next_division(span);
+    }
+    level
+}
+
+/// Average syncopation of the onsets in a cycle of the given length.
+fn syncopation(onsets: &[usize], number_of_steps: usize) -> f32 {
+    if onsets.is_empty() {
+        return 0.0;
+    }
+
+    let total = onsets
+        .iter()
+        .enumerate()
+        .map(|(i, &onset)| {
+            let next = onsets
+                .get(i + 1)
+                .copied()
+                .unwrap_or(onsets[0] + number_of_steps);
+            let strongest_rest = (onset + 1..next)
+                .map(|step| metrical_level(step, number_of_steps))
+                .max()
+                .unwrap_or(0);
+            strongest_rest.saturating_sub(metrical_level(onset, number_of_steps))
+        })
+        .sum::<usize>();
+
+    total as f32 / onsets.len() as f32
+}
+
+impl Track {
+    /// Analyzes the rhythmic characteristics of this track.
+    ///
+    /// Only the steps in the number of steps of the track are analyzed.
+    pub fn analyze(&self) -> TrackAnalysis {
+        let number_of_steps = self.number_of_steps as usize;
+        let steps = &self.trigs[..number_of_steps];
+
+        let onsets = steps
+            .iter()
+            .filter(|trig| trig.enabled_trig() && !trig.enabled_lock_trig())
+            .map(Trig::index)
+            .collect::<Vec<_>>();
+
+        let lock_trigs = steps
+            .iter()
+            .filter(|trig| trig.enabled_lock_trig())
+            .map(Trig::index)
+            .collect::<Vec<_>>();
+
+        let accents = onsets
+            .iter()
+            .copied()
+            .filter(|&i| self.trigs[i].enabled_accent())
+            .collect::<Vec<_>>();
+
+        let ratio = |count: usize, of: usize| {
+            if of == 0 {
+                0.0
+            } else {
+                count as f32 / of as f32
+            }
+        };
+
+        let micro_timings = onsets
+            .iter()
+            .map(|&i| self.trigs[i].micro_timing_value().unsigned_abs())
+            .collect::<Vec<_>>();
+
+        let mut trig_conditions = BTreeMap::new();
+        for &i in &onsets {
+            let trig_condition = self.trigs[i].trig_condition();
+            if trig_condition != TrigCondition::default() {
+                *trig_conditions.entry(trig_condition).or_insert(0) += 1;
+            }
+        }
+
+        TrackAnalysis {
+            track_index: self.index,
+            number_of_steps,
+            speed: self.speed,
+            density: ratio(onsets.len(), number_of_steps),
+            syncopation: syncopation(&onsets, number_of_steps),
+            accent_ratio: ratio(accents.len(), onsets.len()),
+            average_velocity: (!onsets.is_empty()).then(|| {
+                onsets
+                    .iter()
+                    .map(|&i| self.effective_velocity(i) as f32)
+                    .sum::<f32>()
+                    / onsets.len() as f32
+            }),
+            micro_timed_onsets: micro_timings.iter().filter(|&&value| value != 0).count(),
+            average_absolute_micro_timing: ratio(micro_timings.iter().sum(), onsets.len()),
+            swing_flagged_onsets: onsets
+                .iter()
+                .filter(|&&i| self.trigs[i].enabled_swing())
+                .count(),
+            trig_conditions,
+            plock_slots: self.parameter_lock_pool.as_ref().map_or(0, |pool| {
+                pool.lock().number_of_slots_used_by_track(self.index as u8)
+            }),
+            onsets,
+            lock_trigs,
+            accents,
+        }
+    }
+}
+
+impl Pattern {
+    /// Analyzes the rhythmic characteristics of this pattern and its tracks.
+    pub fn analyze(&self) -> PatternAnalysis {
+        let tracks = self.tracks.iter().map(Track::analyze).collect::<Vec<_>>();
+
+        let pool = self.parameter_lock_pool.lock();
+        let plock_slots_total = pool.inner.len();
+        let plock_slots_used = pool
+            .inner
+            .iter()
+            .filter(|plock_seq| plock_seq.track_nr != 0xFF && plock_seq.plock_type != 0xFF)
+            .count();
+        let fx_plock_slots = pool.number_of_slots_used_by_track(12);
+        drop(pool);
+
+        PatternAnalysis {
+            pattern_index: self.index,
+            master_length: self.master_length(),
+            swing_amount: self.swing_amount(),
+            tracks,
+            fx_plock_slots,
+            plock_slots_used,
+            plock_slots_total,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn metric_grid_follows_the_number_of_steps() {
+        let levels = |number_of_steps| {
+            (0..number_of_steps)
+                .map(|step| metrical_level(step, number_of_steps))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(levels(16), [4, 0, 1, 0, 2, 0, 1, 0, 3, 0, 1, 0, 2, 0, 1, 0]);
+        assert_eq!(levels(12), [3, 0, 1, 0, 2, 0, 1, 0, 2, 0, 1, 0]);
+        assert_eq!(levels(48)[16], levels(16)[0]);
+        assert_eq!(levels(7), [1, 0, 0, 0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn onsets_on_the_beats_are_not_syncopated() {
+        assert!(syncopation(&[0, 4, 8, 12], 16).abs() < f32::EPSILON);
+        assert!(syncopation(&[0, 4, 8], 12).abs() < f32::EPSILON);
+        assert!(syncopation(&[0, 3, 7, 11], 12) > 0.0);
+    }
+}
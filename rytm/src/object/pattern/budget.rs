@@ -0,0 +1,86 @@
+// All casts in this file are intended or safe within the context of this library.
+//
+// One can change `allow` to `warn` to review them if necessary.
+#![allow(clippy::cast_possible_truncation)]
+
+use super::Pattern;
+use crate::error::{ParameterError, RytmError};
+use rytm_rs_macro::parameter_range;
+use serde::{Deserialize, Serialize};
+
+/// What a slot of the parameter lock pool of a pattern is used for.
+///
+/// A pattern has `72` slots, each holding the values of one parameter of one track for all the trigs of the track.
+/// Compound parameter locks use an adjacent companion slot for their least significant bytes.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PlockSlot {
+    /// The slot is not used by any parameter lock.
+    Free,
+    /// The slot holds the values of a parameter lock.
+    Owned {
+        /// The track which owns the slot.
+        ///
+        /// Range `0..=12`, `12` is the FX track.
+        track_index: usize,
+        /// The raw parameter lock type.
+        plock_type: u8,
+        /// Name of the parameter following the naming of the plock setters, `None` for unknown types.
+        parameter: Option<String>,
+        /// Number of trigs which have a value in this slot.
+        ///
+        /// A slot without values still takes up space until the pool is compacted.
+        number_of_values: usize,
+    },
+    /// The slot holds the least significant bytes of the compound parameter lock in the previous slot.
+    Companion,
+}
+
+impl Pattern {
+    /// Returns what each of the `72` slots of the parameter lock pool is used for.
+    pub fn plock_slots(&self) -> Vec<PlockSlot> {
+        self.parameter_lock_pool.lock().slots()
+    }
+
+    /// Returns the number of slots of the parameter lock pool which are in use, including companion slots.
+    pub fn number_of_used_plock_slots(&self) -> usize {
+        let pool = self.parameter_lock_pool.lock();
+        pool.inner.len() - pool.number_of_free_slots()
+    }
+
+    /// Returns the number of slots of the parameter lock pool which are free.
+    ///
+    /// A basic parameter lock takes one slot and a compound parameter lock takes two.
+    pub fn number_of_free_plock_slots(&self) -> usize {
+        self.parameter_lock_pool.lock().number_of_free_slots()
+    }
+
+    /// Returns the number of slots of the parameter lock pool which a track uses, including companion slots.
+    ///
+    /// Range `0..=12`, `12` is the FX track.
+    #[parameter_range(range = "track_index:0..=12")]
+    pub fn number_of_plock_slots_used_by_track(
+        &self,
+        track_index: usize,
+    ) -> Result<usize, RytmError> {
+        Ok(self
+            .parameter_lock_pool
+            .lock()
+            .number_of_slots_used_by_track(track_index as u8))
+    }
+
+    /// Returns the indexes of the slots of the parameter lock pool which are owned by a parameter lock but hold no values.
+    ///
+    /// These slots can be released with [`Pattern::compact_plock_pool`].
+    pub fn empty_plock_slots(&self) -> Vec<usize> {
+        self.parameter_lock_pool.lock().empty_slots()
+    }
+
+    /// Releases the slots of the parameter lock pool which hold no values, see [`Pattern::empty_plock_slots`].
+    ///
+    /// Parameter locks which have values are not changed.
+    ///
+    /// Returns the number of released slots, including companion slots.
+    pub fn compact_plock_pool(&mut self) -> usize {
+        self.parameter_lock_pool.lock().compact()
+    }
+}
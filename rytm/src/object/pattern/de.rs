@@ -41,8 +41,8 @@ impl<'de> Deserialize<'de> for Pattern {
                 let mut bpm = None;
                 let mut pad_scale_per_pattern = None;
 
-                while let Some(key) = map.next_key()? {
-                    match key {
+                while let Some(key) = map.next_key::<String>()? {
+                    match key.as_str() {
                         "sysex_meta" => sysex_meta = Some(map.next_value()?),
                         "index" => index = Some(map.next_value()?),
                         "version" => version = Some(map.next_value()?),
@@ -77,7 +77,15 @@ impl<'de> Deserialize<'de> for Pattern {
                             parameter_lock_pool =
                                 Some(Arc::new(Mutex::new(parameter_lock_pool_value)));
                         }
-                        _ => return Err(V::Error::unknown_field(key, FIELDS)),
+                        _ => return Err(V::Error::unknown_field(&key, FIELDS)),
+                    }
+                }
+
+                // Keys may come in any order, link the tracks and their trigs once the pool and the FX track are read.
+                if let (Some(pool), Some(fx_track)) = (&parameter_lock_pool, &fx_track) {
+                    link_track(&mut fx_track.lock(), pool, None);
+                    for track in tracks.iter_mut().flatten() {
+                        link_track(track, pool, Some(fx_track));
                     }
                 }
 
@@ -129,6 +137,20 @@ impl<'de> Deserialize<'de> for Pattern {
     }
 }
 
+/// Links a track and its trigs to the parameter lock pool and the FX track of their pattern.
+pub(super) fn link_track(
+    track: &mut Track,
+    parameter_lock_pool: &Arc<Mutex<ParameterLockPool>>,
+    fx_track_ref: Option<&Arc<Mutex<Track>>>,
+) {
+    track.parameter_lock_pool = Some(Arc::clone(parameter_lock_pool));
+    track.fx_track_ref = fx_track_ref.cloned();
+    for trig in &mut track.trigs {
+        trig.parameter_lock_pool = Some(Arc::clone(parameter_lock_pool));
+        trig.fx_track_ref = fx_track_ref.cloned();
+    }
+}
+
 // Tracks array deserializer
 struct TracksArrayDeserializerSeed<'a> {
     fx_track_ref: &'a Option<Arc<Mutex<Track>>>,
@@ -246,8 +268,8 @@ impl<'a, 'de> de::Visitor<'de> for TrackVisitor<'a> {
         let mut __maybe_useful_flag_from_default_trig_note = None;
         let mut __maybe_useful_flags_from_flags_and_speed = None;
 
-        while let Some(key) = map.next_key()? {
-            match key {
+        while let Some(key) = map.next_key::<String>()? {
+            match key.as_str() {
                 "is_owner_pattern_work_buffer" => {
                     is_owner_pattern_work_buffer = Some(map.next_value()?);
                 }
@@ -283,7 +305,7 @@ impl<'a, 'de> de::Visitor<'de> for TrackVisitor<'a> {
                 "__maybe_useful_flags_from_flags_and_speed" => {
                     __maybe_useful_flags_from_flags_and_speed = Some(map.next_value()?);
                 }
-                _ => return Err(de::Error::unknown_field(key, FIELDS)),
+                _ => return Err(de::Error::unknown_field(&key, FIELDS)),
             }
         }
 
@@ -416,3 +438,67 @@ impl<'de> de::DeserializeSeed<'de> for TrigsArrayVisitor {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::object::pattern::track::trig::{parameter::PlockParameter, HoldsTrigFlags};
+    use serde_json::Value;
+
+    /// Writes the fields of a serialized struct in reverse order.
+    fn reversed(value: &Value) -> String {
+        let fields = value
+            .as_object()
+            .unwrap()
+            .iter()
+            .rev()
+            .map(|(key, value)| format!("{key:?}:{value}"))
+            .collect::<Vec<_>>();
+        format!("{{{}}}", fields.join(","))
+    }
+
+    #[test]
+    fn patterns_round_trip_with_owned_keys_in_any_order() {
+        let mut pattern = Pattern::try_default(0).unwrap();
+        let trig = &mut pattern.tracks_mut()[0].trigs_mut()[4];
+        trig.set_trig_enable(true);
+        trig.plock_set_parameter(PlockParameter::FilterCutoff, 40.0)
+            .unwrap();
+        let value = serde_json::to_value(&pattern).unwrap();
+
+        // Values hold owned keys, the reversed text has the tracks before the pool and the FX track.
+        let from_value: Pattern = serde_json::from_value(value.clone()).unwrap();
+        let from_reversed: Pattern = serde_json::from_str(&reversed(&value)).unwrap();
+
+        for copy in [from_value, from_reversed] {
+            assert_eq!(serde_json::to_value(&copy).unwrap(), value);
+
+            let pool = &copy.parameter_lock_pool;
+            let fx_track = &copy.fx_track;
+            assert!(Arc::ptr_eq(
+                fx_track.lock().parameter_lock_pool.as_ref().unwrap(),
+                pool
+            ));
+            for track in &copy.tracks {
+                assert!(Arc::ptr_eq(
+                    track.parameter_lock_pool.as_ref().unwrap(),
+                    pool
+                ));
+                assert!(Arc::ptr_eq(track.fx_track_ref.as_ref().unwrap(), fx_track));
+                for trig in track.trigs() {
+                    assert!(Arc::ptr_eq(
+                        trig.parameter_lock_pool.as_ref().unwrap(),
+                        pool
+                    ));
+                }
+            }
+
+            assert_eq!(
+                copy.tracks()[0].trigs()[4]
+                    .plock_get_parameter(PlockParameter::FilterCutoff)
+                    .unwrap(),
+                Some(40.0)
+            );
+        }
+    }
+}
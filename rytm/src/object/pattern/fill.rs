@@ -0,0 +1,405 @@
+// All casts in this file are intended or safe within the context of this library.
+//
+// One can change `allow` to `warn` to review them if necessary.
+#![allow(
+    clippy::cast_possible_truncation,
+    clippy::cast_sign_loss,
+    clippy::cast_precision_loss
+)]
+
+use super::{
+    track::trig::{
+        types::{Length, RetrigRate, TrigCondition},
+        HoldsTrigFlags, Trig,
+    },
+    Pattern,
+};
+use crate::{
+    error::{ParameterError, RytmError},
+    util::SeededRng,
+};
+use rytm_rs_macro::parameter_range;
+use serde::{Deserialize, Serialize};
+
+/// Settings of a fill variation.
+///
+/// A fill only changes the last steps of each track, see [`FillSettings::set_length`].
+/// Ghost notes, rolls and build-ups are only placed on empty steps so the groove of the source pattern is kept.
+///
+/// Defaults to a fill over the last `4` steps with
+///
+/// - a snare build-up on the SD track,
+/// - rolls on the SD, LT, MT and HT tracks,
+/// - ghost notes on the SD and CH tracks,
+/// - a crash on the last step of the CY track.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FillSettings {
+    length: usize,
+    build_up_track: Option<usize>,
+    roll_tracks: Vec<usize>,
+    roll_probability: f32,
+    roll_rate: RetrigRate,
+    ghost_note_tracks: Vec<usize>,
+    ghost_note_probability: f32,
+    ghost_note_velocity: usize,
+    crash_track: Option<usize>,
+    drop_tracks: Vec<usize>,
+}
+
+impl Default for FillSettings {
+    fn default() -> Self {
+        Self {
+            length: 4,
+            build_up_track: Some(1),
+            roll_tracks: vec![1, 5, 6, 7],
+            roll_probability: 0.25,
+            roll_rate: RetrigRate::_1B32,
+            ghost_note_tracks: vec![1, 8],
+            ghost_note_probability: 0.35,
+            ghost_note_velocity: 32,
+            crash_track: Some(10),
+            drop_tracks: Vec::new(),
+        }
+    }
+}
+
+impl FillSettings {
+    /// Makes new settings which do not change anything.
+    ///
+    /// Use the setters to enable the parts of the fill.
+    pub const fn empty() -> Self {
+        Self {
+            length: 4,
+            build_up_track: None,
+            roll_tracks: Vec::new(),
+            roll_probability: 0.0,
+            roll_rate: RetrigRate::_1B32,
+            ghost_note_tracks: Vec::new(),
+            ghost_note_probability: 0.0,
+            ghost_note_velocity: 32,
+            crash_track: None,
+            drop_tracks: Vec::new(),
+        }
+    }
+
+    /// Sets how many steps at the end of each track the fill spans.
+    ///
+    /// Range `1..=64`
+    #[parameter_range(range = "length:1..=64")]
+    pub fn set_length(&mut self, length: usize) -> Result<(), RytmError> {
+        self.length = length;
+        Ok(())
+    }
+
+    /// Sets the track which builds up with a trig on every empty step, rising in velocity towards the end.
+    ///
+    /// Range `0..=11`
+    #[parameter_range(range = "track_index:0..=11")]
+    pub fn set_build_up_track(&mut self, track_index: usize) -> Result<(), RytmError> {
+        self.build_up_track = Some(track_index);
+        Ok(())
+    }
+
+    /// Sets the tracks which may get retrig rolls on their empty steps.
+    ///
+    /// Range `0..=11` for each track.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a track index is out of range.
+    pub fn set_roll_tracks(&mut self, track_indexes: &[usize]) -> Result<(), RytmError> {
+        self.roll_tracks = check_track_indexes(track_indexes, "roll_tracks")?;
+        Ok(())
+    }
+
+    /// Sets the probability of a roll on an empty step of a roll track.
+    ///
+    /// Range `0.0..=1.0`
+    #[parameter_range(range = "roll_probability:0.0..=1.0")]
+    pub fn set_roll_probability(&mut self, roll_probability: f32) -> Result<(), RytmError> {
+        self.roll_probability = roll_probability;
+        Ok(())
+    }
+
+    /// Sets the retrig rate of the rolls.
+    pub const fn set_roll_rate(&mut self, roll_rate: RetrigRate) {
+        self.roll_rate = roll_rate;
+    }
+
+    /// Sets the tracks which may get ghost notes on their empty steps.
+    ///
+    /// Range `0..=11` for each track.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a track index is out of range.
+    pub fn set_ghost_note_tracks(&mut self, track_indexes: &[usize]) -> Result<(), RytmError> {
+        self.ghost_note_tracks = check_track_indexes(track_indexes, "ghost_note_tracks")?;
+        Ok(())
+    }
+
+    /// Sets the probability of a ghost note on an empty step of a ghost note track.
+    ///
+    /// Range `0.0..=1.0`
+    #[parameter_range(range = "ghost_note_probability:0.0..=1.0")]
+    pub fn set_ghost_note_probability(
+        &mut self,
+        ghost_note_probability: f32,
+    ) -> Result<(), RytmError> {
+        self.ghost_note_probability = ghost_note_probability;
+        Ok(())
+    }
+
+    /// Sets the velocity of the ghost notes which is also where the build-up starts from.
+    ///
+    /// Range `1..=127`
+    #[parameter_range(range = "ghost_note_velocity:1..=127")]
+    pub fn set_ghost_note_velocity(&mut self, ghost_note_velocity: usize) -> Result<(), RytmError> {
+        self.ghost_note_velocity = ghost_note_velocity;
+        Ok(())
+    }
+
+    /// Sets the track which plays an accented crash on its last step.
+    ///
+    /// Range `0..=11`
+    #[parameter_range(range = "track_index:0..=11")]
+    pub fn set_crash_track(&mut self, track_index: usize) -> Result<(), RytmError> {
+        self.crash_track = Some(track_index);
+        Ok(())
+    }
+
+    /// Sets the tracks which drop out during the fill.
+    ///
+    /// These tracks do not get any trigs from the fill even if they are set for other parts of it.
+    ///
+    /// Range `0..=11` for each track.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a track index is out of range.
+    pub fn set_drop_tracks(&mut self, track_indexes: &[usize]) -> Result<(), RytmError> {
+        self.drop_tracks = check_track_indexes(track_indexes, "drop_tracks")?;
+        Ok(())
+    }
+
+    /// Removes the build-up.
+    pub const fn clear_build_up_track(&mut self) {
+        self.build_up_track = None;
+    }
+
+    /// Removes the crash.
+    pub const fn clear_crash_track(&mut self) {
+        self.crash_track = None;
+    }
+
+    /// Returns how many steps at the end of each track the fill spans.
+    pub const fn length(&self) -> usize {
+        self.length
+    }
+
+    /// Returns the track which builds up.
+    pub const fn build_up_track(&self) -> Option<usize> {
+        self.build_up_track
+    }
+
+    /// Returns the tracks which may get rolls.
+    pub fn roll_tracks(&self) -> &[usize] {
+        &self.roll_tracks
+    }
+
+    /// Returns the probability of a roll.
+    pub const fn roll_probability(&self) -> f32 {
+        self.roll_probability
+    }
+
+    /// Returns the retrig rate of the rolls.
+    pub const fn roll_rate(&self) -> RetrigRate {
+        self.roll_rate
+    }
+
+    /// Returns the tracks which may get ghost notes.
+    pub fn ghost_note_tracks(&self) -> &[usize] {
+        &self.ghost_note_tracks
+    }
+
+    /// Returns the probability of a ghost note.
+    pub const fn ghost_note_probability(&self) -> f32 {
+        self.ghost_note_probability
+    }
+
+    /// Returns the velocity of the ghost notes.
+    pub const fn ghost_note_velocity(&self) -> usize {
+        self.ghost_note_velocity
+    }
+
+    /// Returns the track which plays the crash.
+    pub const fn crash_track(&self) -> Option<usize> {
+        self.crash_track
+    }
+
+    /// Returns the tracks which drop out during the fill.
+    pub fn drop_tracks(&self) -> &[usize] {
+        &self.drop_tracks
+    }
+}
+
+/// Returns the track indexes if they are all in range.
+fn check_track_indexes(
+    track_indexes: &[usize],
+    parameter_name: &str,
+) -> Result<Vec<usize>, RytmError> {
+    if let Some(track_index) = track_indexes.iter().find(|&&track_index| track_index > 11) {
+        return Err(ParameterError::Range {
+            value: track_index.to_string(),
+            parameter_name: parameter_name.into(),
+        }
+        .into());
+    }
+    Ok(track_indexes.to_vec())
+}
+
+/// Checks if a trig neither plays nor holds parameter locks.
+fn is_empty_step(trig: &Trig) -> bool {
+    !trig.enabled_trig() && !trig.enabled_lock_trig()
+}
+
+impl Pattern {
+    /// Generates a fill variation of this pattern into a new pattern.
+    ///
+    /// The new pattern is a copy of this pattern with the fill written over the last steps of its tracks.
+    /// Tracks which drop out lose their trigs in the fill, parameter locks on those steps are kept.
+    ///
+    /// The same seed and settings always generate the same fill.
+    ///
+    /// Range `0..=127` for the index of the new pattern.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the index is out of range.
+    #[parameter_range(range = "target_index:0..=127")]
+    pub fn generate_fill(
+        &self,
+        target_index: usize,
+        settings: &FillSettings,
+        seed: u64,
+    ) -> Result<Self, RytmError> {
+        let mut pattern = Self::try_default(target_index)?;
+        pattern.copy_data_from(self);
+        pattern.write_fill(settings, seed, false)?;
+        Ok(pattern)
+    }
+
+    /// Adds a fill variation to this pattern which only plays when the fill mode of the device is active.
+    ///
+    /// Added trigs get the [`TrigCondition::Fill`] condition.
+    /// Trigs of the tracks which drop out get the [`TrigCondition::FillNot`] condition unless they already have a condition.
+    ///
+    /// Since a step holds a single trig, existing trigs are never changed by the fill.
+    /// A crash on a step which already plays is left as it is.
+    ///
+    /// The same seed and settings always generate the same fill.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a trig can not be written.
+    pub fn add_conditional_fill(
+        &mut self,
+        settings: &FillSettings,
+        seed: u64,
+    ) -> Result<(), RytmError> {
+        self.write_fill(settings, seed, true)
+    }
+
+    /// Writes a fill over the last steps of the tracks.
+    ///
+    /// Conditional fills only add trigs with conditions, others may change existing trigs.
+    fn write_fill(
+        &mut self,
+        settings: &FillSettings,
+        seed: u64,
+        conditional: bool,
+    ) -> Result<(), RytmError> {
+        for track_index in 0..12 {
+            // Each track draws from its own sequence like the generator.
+            let mut rng = SeededRng::new(seed.wrapping_add(track_index as u64));
+            let track = &mut self.tracks[track_index];
+            let number_of_steps = track.number_of_steps as usize;
+            let start = number_of_steps.saturating_sub(settings.length);
+            let trigs = &mut track.trigs[start..number_of_steps];
+
+            let new_trig = |trig: &mut Trig, velocity: usize| -> Result<(), RytmError> {
+                let default = Trig::try_default(trig.index(), trig.track_index())?;
+                trig.copy_data_from(&default);
+                trig.set_trig_enable(true);
+                trig.set_velocity(velocity)?;
+                if conditional {
+                    trig.set_trig_condition(TrigCondition::Fill);
+                }
+                Ok(())
+            };
+
+            if settings.drop_tracks.contains(&track_index) {
+                for trig in trigs.iter_mut().filter(|trig| trig.enabled_trig()) {
+                    if !conditional {
+                        trig.set_trig_enable(false);
+                    } else if trig.trig_condition() == TrigCondition::Unset {
+                        trig.set_trig_condition(TrigCondition::FillNot);
+                    }
+                }
+                // Tracks which drop out stay silent during the fill.
+                continue;
+            }
+
+            if settings.crash_track == Some(track_index) {
+                if let Some(trig) = trigs.last_mut() {
+                    if is_empty_step(trig) {
+                        new_trig(trig, 127)?;
+                        trig.set_accent(true);
+                    } else if !conditional {
+                        trig.set_trig_enable(true);
+                        trig.set_velocity(127)?;
+                        trig.set_accent(true);
+                    }
+                }
+            }
+
+            if settings.build_up_track == Some(track_index) {
+                let length = trigs.len();
+                for (i, trig) in trigs.iter_mut().enumerate() {
+                    if !is_empty_step(trig) {
+                        continue;
+                    }
+                    let progress = if length > 1 {
+                        i as f32 / (length - 1) as f32
+                    } else {
+                        1.0
+                    };
+                    let velocity = ((127 - settings.ghost_note_velocity) as f32)
+                        .mul_add(progress, settings.ghost_note_velocity as f32);
+                    new_trig(trig, velocity.round() as usize)?;
+                }
+            }
+
+            if settings.roll_tracks.contains(&track_index) {
+                for trig in trigs.iter_mut() {
+                    if is_empty_step(trig) && rng.chance(settings.roll_probability) {
+                        new_trig(trig, 100)?;
+                        trig.set_retrig(true);
+                        trig.set_retrig_rate(settings.roll_rate);
+                        trig.set_retrig_length(Length::_16th);
+                    }
+                }
+            }
+
+            if settings.ghost_note_tracks.contains(&track_index) {
+                for trig in trigs.iter_mut() {
+                    if is_empty_step(trig) && rng.chance(settings.ghost_note_probability) {
+                        new_trig(trig, settings.ghost_note_velocity)?;
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,567 @@
+// All casts in this file are intended or safe within the context of this library.
+//
+// One can change `allow` to `warn` to review them if necessary.
+#![allow(
+    clippy::cast_lossless,
+    clippy::cast_possible_truncation,
+    clippy::cast_sign_loss,
+    clippy::cast_possible_wrap,
+    clippy::cast_precision_loss
+)]
+
+use super::{
+    de::link_track,
+    track::trig::{
+        parameter::{PlockParameter, PlockValueKind},
+        types::TrigCondition,
+        HoldsTrigFlags, Trig,
+    },
+    Pattern,
+};
+use crate::{
+    error::{ParameterError, RytmError},
+    util::SeededRng,
+};
+use parking_lot::Mutex;
+use rytm_rs_macro::parameter_range;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+/// A range of values for a parameter lock to be generated.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PlockRange {
+    parameter: PlockParameter,
+    min: f32,
+    max: f32,
+}
+
+impl PlockRange {
+    /// Makes a new parameter lock range.
+    ///
+    /// Both ends are inclusive and need to be in the range of the parameter, see [`PlockParameter::range`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the ends are out of the range of the parameter or `min` is greater than `max`.
+    /// For parameters of kind [`PlockValueKind::Choice`], returns an error if there are no valid choices in the range.
+    pub fn try_new(parameter: PlockParameter, min: f32, max: f32) -> Result<Self, RytmError> {
+        let range = Self {
+            parameter,
+            min,
+            max,
+        };
+
+        let parameter_range = parameter.range();
+        if min > max || !parameter_range.contains(&min) || !parameter_range.contains(&max) {
+            return Err(ParameterError::Range {
+                value: format!("{min}..={max}"),
+                parameter_name: <&str>::from(parameter).to_owned(),
+            }
+            .into());
+        }
+
+        if parameter.kind() == PlockValueKind::Choice && range.choices().is_empty() {
+            return Err(ParameterError::Range {
+                value: format!("{min}..={max}"),
+                parameter_name: <&str>::from(parameter).to_owned(),
+            }
+            .into());
+        }
+
+        Ok(range)
+    }
+
+    /// Returns the parameter of this range.
+    pub const fn parameter(&self) -> PlockParameter {
+        self.parameter
+    }
+
+    /// Returns the lower end of this range.
+    pub const fn min(&self) -> f32 {
+        self.min
+    }
+
+    /// Returns the upper end of this range.
+    pub const fn max(&self) -> f32 {
+        self.max
+    }
+
+    /// Returns the valid raw values in the range for parameters of kind [`PlockValueKind::Choice`].
+    fn choices(&self) -> Vec<u8> {
+        (self.min.ceil() as u8..=self.max.floor() as u8)
+            .filter(|&code| self.parameter.accepts(code as f32))
+            .collect()
+    }
+
+    /// Picks a value in the range which is accepted by the parameter.
+    fn pick(&self, rng: &mut SeededRng) -> f32 {
+        match self.parameter.kind() {
+            PlockValueKind::Float => rng.range_f32(self.min, self.max),
+            PlockValueKind::Integer | PlockValueKind::Toggle => {
+                rng.range_isize(self.min.ceil() as isize, self.max.floor() as isize) as f32
+            }
+            PlockValueKind::Choice => {
+                let choices = self.choices();
+                choices[rng.range_usize(0, choices.len() - 1)] as f32
+            }
+        }
+    }
+}
+
+/// Constraints to generate the trigs of a single track.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TrackConstraints {
+    density: f32,
+    allowed_steps: Vec<usize>,
+    accent_probability: f32,
+    note_range: Option<(usize, usize)>,
+    velocity_range: Option<(usize, usize)>,
+    trig_conditions: Vec<(TrigCondition, usize)>,
+    plock_ranges: Vec<PlockRange>,
+}
+
+impl Default for TrackConstraints {
+    fn default() -> Self {
+        Self {
+            density: 0.5,
+            allowed_steps: (0..64).collect(),
+            accent_probability: 0.0,
+            note_range: None,
+            velocity_range: None,
+            trig_conditions: Vec::new(),
+            plock_ranges: Vec::new(),
+        }
+    }
+}
+
+impl TrackConstraints {
+    /// Sets the probability of an allowed step to hold an enabled trig.
+    ///
+    /// Range `0.0..=1.0`
+    #[parameter_range(range = "density:0.0..=1.0")]
+    pub fn set_density(&mut self, density: f32) -> Result<(), RytmError> {
+        self.density = density;
+        Ok(())
+    }
+
+    /// Sets the steps which are allowed to hold an enabled trig.
+    ///
+    /// Steps beyond the number of steps of the track are ignored.
+    ///
+    /// Range `0..=63` for each step.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if any of the steps is out of range.
+    pub fn set_allowed_steps(&mut self, allowed_steps: &[usize]) -> Result<(), RytmError> {
+        if let Some(&step) = allowed_steps.iter().find(|&&step| step > 63) {
+            return Err(ParameterError::Range {
+                value: step.to_string(),
+                parameter_name: "allowed_steps".into(),
+            }
+            .into());
+        }
+
+        let mut allowed_steps = allowed_steps.to_vec();
+        allowed_steps.sort_unstable();
+        allowed_steps.dedup();
+        self.allowed_steps = allowed_steps;
+        Ok(())
+    }
+
+    /// Sets the probability of a generated trig to be accented.
+    ///
+    /// Range `0.0..=1.0`
+    #[parameter_range(range = "accent_probability:0.0..=1.0")]
+    pub fn set_accent_probability(&mut self, accent_probability: f32) -> Result<(), RytmError> {
+        self.accent_probability = accent_probability;
+        Ok(())
+    }
+
+    /// Sets the inclusive range of notes for the generated trigs.
+    ///
+    /// When not set, generated trigs keep the default note.
+    ///
+    /// Range `36..=84`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the notes are out of range or `min` is greater than `max`.
+    #[parameter_range(range = "min:36..=84", range = "max:36..=84")]
+    pub fn set_note_range(&mut self, min: usize, max: usize) -> Result<(), RytmError> {
+        if min > max {
+            return Err(ParameterError::Range {
+                value: format!("{min}..={max}"),
+                parameter_name: "note_range".into(),
+            }
+            .into());
+        }
+        self.note_range = Some((min, max));
+        Ok(())
+    }
+
+    /// Sets the inclusive range of velocities for the generated trigs.
+    ///
+    /// When not set, generated trigs use the default velocity of the track.
+    ///
+    /// Range `1..=127`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the velocities are out of range or `min` is greater than `max`.
+    #[parameter_range(range = "min:1..=127", range = "max:1..=127")]
+    pub fn set_velocity_range(&mut self, min: usize, max: usize) -> Result<(), RytmError> {
+        if min > max {
+            return Err(ParameterError::Range {
+                value: format!("{min}..={max}"),
+                parameter_name: "velocity_range".into(),
+            }
+            .into());
+        }
+        self.velocity_range = Some((min, max));
+        Ok(())
+    }
+
+    /// Adds a trig condition to the distribution which the conditions of generated trigs are picked from.
+    ///
+    /// Conditions are picked proportional to their weights.
+    /// When the distribution is empty, generated trigs have no condition.
+    ///
+    /// Range `1..=1000` for weight.
+    #[parameter_range(range = "weight:1..=1000")]
+    pub fn add_trig_condition(
+        &mut self,
+        trig_condition: TrigCondition,
+        weight: usize,
+    ) -> Result<(), RytmError> {
+        self.trig_conditions.push((trig_condition, weight));
+        Ok(())
+    }
+
+    /// Adds a range of parameter lock values to be generated for every generated trig.
+    ///
+    /// FX parameters are locked in the FX track on the same step.
+    pub fn add_plock_range(&mut self, plock_range: PlockRange) {
+        self.plock_ranges.push(plock_range);
+    }
+
+    /// Clears the note range.
+    pub const fn clear_note_range(&mut self) {
+        self.note_range = None;
+    }
+
+    /// Clears the velocity range.
+    pub const fn clear_velocity_range(&mut self) {
+        self.velocity_range = None;
+    }
+
+    /// Clears the trig condition distribution.
+    pub fn clear_trig_conditions(&mut self) {
+        self.trig_conditions.clear();
+    }
+
+    /// Clears the parameter lock ranges.
+    pub fn clear_plock_ranges(&mut self) {
+        self.plock_ranges.clear();
+    }
+
+    /// Returns the probability of an allowed step to hold an enabled trig.
+    pub const fn density(&self) -> f32 {
+        self.density
+    }
+
+    /// Returns the steps which are allowed to hold an enabled trig.
+    pub fn allowed_steps(&self) -> &[usize] {
+        &self.allowed_steps
+    }
+
+    /// Returns the probability of a generated trig to be accented.
+    pub const fn accent_probability(&self) -> f32 {
+        self.accent_probability
+    }
+
+    /// Returns the inclusive range of notes if set.
+    pub const fn note_range(&self) -> Option<(usize, usize)> {
+        self.note_range
+    }
+
+    /// Returns the inclusive range of velocities if set.
+    pub const fn velocity_range(&self) -> Option<(usize, usize)> {
+        self.velocity_range
+    }
+
+    /// Returns the trig condition distribution with weights.
+    pub fn trig_conditions(&self) -> &[(TrigCondition, usize)] {
+        &self.trig_conditions
+    }
+
+    /// Returns the parameter lock ranges.
+    pub fn plock_ranges(&self) -> &[PlockRange] {
+        &self.plock_ranges
+    }
+
+    /// Picks a trig condition from the distribution.
+    fn pick_trig_condition(&self, rng: &mut SeededRng) -> Option<TrigCondition> {
+        let total = self
+            .trig_conditions
+            .iter()
+            .map(|(_, weight)| weight)
+            .sum::<usize>();
+        if total == 0 {
+            return None;
+        }
+
+        let mut target = rng.range_usize(0, total - 1);
+        for &(trig_condition, weight) in &self.trig_conditions {
+            if target < weight {
+                return Some(trig_condition);
+            }
+            target -= weight;
+        }
+
+        None
+    }
+
+    /// Generates a trig in place.
+    fn generate_trig(&self, trig: &mut Trig, rng: &mut SeededRng) -> Result<(), RytmError> {
+        trig.set_trig_enable(true);
+
+        if let Some((min, max)) = self.note_range {
+            trig.set_note(rng.range_usize(min, max))?;
+        }
+
+        if let Some((min, max)) = self.velocity_range {
+            trig.set_velocity(rng.range_usize(min, max))?;
+        }
+
+        trig.set_accent(rng.chance(self.accent_probability));
+
+        if let Some(trig_condition) = self.pick_trig_condition(rng) {
+            trig.set_trig_condition(trig_condition);
+        }
+
+        for plock_range in &self.plock_ranges {
+            trig.plock_set_parameter(plock_range.parameter, plock_range.pick(rng))?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Constraints to generate the trigs of a pattern.
+///
+/// Tracks without constraints are left untouched by the generator.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PatternConstraints {
+    tracks: Vec<Option<TrackConstraints>>,
+}
+
+impl Default for PatternConstraints {
+    fn default() -> Self {
+        Self {
+            tracks: vec![None; 12],
+        }
+    }
+}
+
+impl PatternConstraints {
+    /// Sets the constraints of a track.
+    ///
+    /// Range `0..=11`
+    #[parameter_range(range = "track_index:0..=11")]
+    pub fn set_track(
+        &mut self,
+        track_index: usize,
+        constraints: TrackConstraints,
+    ) -> Result<(), RytmError> {
+        self.tracks[track_index] = Some(constraints);
+        Ok(())
+    }
+
+    /// Removes the constraints of a track so the generator leaves it untouched.
+    ///
+    /// Range `0..=11`
+    #[parameter_range(range = "track_index:0..=11")]
+    pub fn clear_track(&mut self, track_index: usize) -> Result<(), RytmError> {
+        self.tracks[track_index] = None;
+        Ok(())
+    }
+
+    /// Returns the constraints of a track if set.
+    ///
+    /// Range `0..=11`
+    #[parameter_range(range = "track_index:0..=11")]
+    pub fn track(&self, track_index: usize) -> Result<Option<&TrackConstraints>, RytmError> {
+        Ok(self.tracks[track_index].as_ref())
+    }
+}
+
+impl Pattern {
+    /// Generates the trigs of the tracks which have constraints.
+    ///
+    /// The trigs and the parameter locks of those tracks are reset before generating.
+    /// The FX track is shared by all tracks, so the FX parameter locks on the steps in range of those tracks are reset as well.
+    /// The track settings like the number of steps are kept, only the steps in range are generated.
+    ///
+    /// The same seed and constraints always generate the same pattern.
+    /// Each track draws from its own sequence so changing the constraints of a track does not change the others.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the parameter lock memory is full.
+    /// In that case this pattern is left untouched.
+    pub fn generate(
+        &mut self,
+        constraints: &PatternConstraints,
+        seed: u64,
+    ) -> Result<(), RytmError> {
+        // Generate into copies linked to a staged pool and FX track so nothing changes unless every track is generated.
+        let pool = Arc::new(Mutex::new(self.parameter_lock_pool.lock().clone()));
+        let fx_track = Arc::new(Mutex::new(self.fx_track.lock().clone()));
+        link_track(&mut fx_track.lock(), &pool, None);
+        let mut tracks = self.tracks.clone();
+        for track in &mut tracks {
+            link_track(track, &pool, Some(&fx_track));
+        }
+
+        // Reset every track first, a generated track may lock FX parameters on the steps of another one.
+        for (track, track_constraints) in tracks.iter_mut().zip(&constraints.tracks) {
+            if track_constraints.is_none() {
+                continue;
+            }
+
+            let mut staged_pool = pool.lock();
+            let mut staged_fx_track = fx_track.lock();
+            staged_pool.clear_all_plocks_for_track(track.index as u8);
+            for step in 0..track.number_of_steps() {
+                staged_pool.clear_plocks_of_trig(step, 12);
+                staged_fx_track.trigs_mut()[step].set_trig_enable(false);
+            }
+            drop(staged_fx_track);
+            drop(staged_pool);
+
+            for trig in track.trigs_mut() {
+                let default = Trig::try_default(trig.index(), trig.track_index())?;
+                trig.copy_data_from(&default);
+            }
+        }
+
+        for (track_index, (track, track_constraints)) in
+            tracks.iter_mut().zip(&constraints.tracks).enumerate()
+        {
+            let Some(track_constraints) = track_constraints else {
+                continue;
+            };
+
+            let mut rng = SeededRng::new(seed.wrapping_add(track_index as u64));
+            let number_of_steps = track.number_of_steps();
+
+            for &step in &track_constraints.allowed_steps {
+                if step >= number_of_steps {
+                    continue;
+                }
+                if rng.chance(track_constraints.density) {
+                    track_constraints.generate_trig(&mut track.trigs_mut()[step], &mut rng)?;
+                }
+            }
+        }
+
+        for (track, staged) in self.tracks.iter_mut().zip(&tracks) {
+            track.copy_data_from(staged);
+        }
+        self.fx_track.lock().copy_data_from(&fx_track.lock());
+        *self.parameter_lock_pool.lock() = pool.lock().clone();
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns the constraints of a track which locks the given parameters on every beat.
+    fn locking_on_beats(parameters: &[PlockParameter]) -> TrackConstraints {
+        let mut constraints = TrackConstraints::default();
+        constraints.set_density(1.0).unwrap();
+        constraints.set_allowed_steps(&[0, 4, 8, 12]).unwrap();
+        for &parameter in parameters {
+            let range = parameter.range();
+            constraints.add_plock_range(
+                PlockRange::try_new(parameter, *range.start(), *range.end()).unwrap(),
+            );
+        }
+        constraints
+    }
+
+    #[test]
+    fn fx_plocks_of_the_regenerated_steps_are_cleared() {
+        let mut pattern = Pattern::try_default(0).unwrap();
+        pattern.tracks()[0].trigs()[5]
+            .plock_set_parameter(PlockParameter::FxDelayTime, 10.0)
+            .unwrap();
+
+        let mut constraints = PatternConstraints::default();
+        constraints
+            .set_track(0, locking_on_beats(&[PlockParameter::FxDelayFeedback]))
+            .unwrap();
+        pattern.generate(&constraints, 1).unwrap();
+
+        let trigs = pattern.tracks()[0].trigs();
+        assert_eq!(
+            trigs[5]
+                .plock_get_parameter(PlockParameter::FxDelayTime)
+                .unwrap(),
+            None
+        );
+        assert!(!pattern.fx_track.lock().trigs()[5].enabled_trig());
+        assert!(trigs[4]
+            .plock_get_parameter(PlockParameter::FxDelayFeedback)
+            .unwrap()
+            .is_some());
+        assert!(pattern.fx_track.lock().trigs()[4].enabled_trig());
+    }
+
+    #[test]
+    fn pattern_is_left_untouched_if_the_plocks_do_not_fit() {
+        let mut pattern = Pattern::try_default(0).unwrap();
+        let mut constraints = PatternConstraints::default();
+        for track_index in 0..12 {
+            let track_constraints = locking_on_beats(&[
+                PlockParameter::FilterAttack,
+                PlockParameter::FilterSustain,
+                PlockParameter::FilterDecay,
+                PlockParameter::FilterRelease,
+                PlockParameter::FilterCutoff,
+                PlockParameter::FilterResonance,
+                PlockParameter::AmplitudeAttack,
+                PlockParameter::AmplitudeHold,
+            ]);
+            constraints
+                .set_track(track_index, track_constraints)
+                .unwrap();
+        }
+
+        let before = serde_json::to_value(&pattern).unwrap();
+        assert!(matches!(
+            pattern.generate(&constraints, 1),
+            Err(RytmError::ParameterLockMemoryFull)
+        ));
+        assert_eq!(serde_json::to_value(&pattern).unwrap(), before);
+    }
+
+    #[test]
+    fn unsorted_allowed_steps_are_generated() {
+        let mut constraints = TrackConstraints::default();
+        constraints.set_density(1.0).unwrap();
+        // Constraints may be deserialized without going through the sorting setter.
+        constraints.allowed_steps = vec![40, 3];
+
+        let mut pattern_constraints = PatternConstraints::default();
+        pattern_constraints.set_track(0, constraints).unwrap();
+        let mut pattern = Pattern::try_default(0).unwrap();
+        pattern.generate(&pattern_constraints, 1).unwrap();
+
+        assert!(pattern.tracks()[0].trigs()[3].enabled_trig());
+    }
+}
@@ -0,0 +1,174 @@
+// All casts in this file are intended or safe within the context of this library.
+//
+// One can change `allow` to `warn` to review them if necessary.
+#![allow(
+    clippy::cast_lossless,
+    clippy::cast_possible_truncation,
+    clippy::cast_precision_loss
+)]
+
+use super::{
+    plock::{plock_name, RawPlockValue},
+    track::{
+        trig::{parameter::PlockParameter, Trig},
+        Track,
+    },
+    Pattern,
+};
+use crate::object::sound::types::MachineType;
+use serde::{Deserialize, Serialize};
+
+/// Identifies the parameter of a parameter lock.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum LockedParameter {
+    /// A synth parameter of the machine of the track.
+    ///
+    /// Range `1..=8`
+    MachineParameter(usize),
+    /// A sound or FX parameter.
+    Parameter(PlockParameter),
+    /// A parameter lock type which is not known, holding the raw type.
+    Unknown(u8),
+}
+
+impl LockedParameter {
+    /// Identifies the parameter of a plock type stored for a track.
+    pub(crate) fn new(track_index: u8, plock_type: u8) -> Self {
+        let machine_parameters = rytm_sys::AR_PLOCK_TYPE_MP0..=rytm_sys::AR_PLOCK_TYPE_MP7;
+        if track_index != 12 && machine_parameters.contains(&(plock_type as u32)) {
+            return Self::MachineParameter(
+                (plock_type as u32 - rytm_sys::AR_PLOCK_TYPE_MP0) as usize + 1,
+            );
+        }
+
+        plock_name(track_index, plock_type)
+            .and_then(|name| PlockParameter::try_from(name).ok())
+            .map_or(Self::Unknown(plock_type), Self::Parameter)
+    }
+}
+
+/// A parameter lock of a trig.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PlockEntry {
+    /// The track which the parameter lock belongs to.
+    ///
+    /// Range `0..=12`, `12` is the FX track.
+    pub track_index: usize,
+    /// The trig which the parameter lock belongs to.
+    ///
+    /// Range `0..=63`
+    pub trig_index: usize,
+    /// The locked parameter.
+    pub parameter: LockedParameter,
+    /// The value of the parameter lock represented according to [`PlockParameter::kind`].
+    ///
+    /// Machine parameters and unknown types hold the raw value since their encoding depends on the machine,
+    /// see [`PlockEntry::machine_value`].
+    pub value: f32,
+    /// The value as it is stored, compound values are combined into 16 bits.
+    pub raw_value: u16,
+}
+
+impl PlockEntry {
+    /// Decodes the value of a machine parameter lock for the machine which the sound of the track uses.
+    ///
+    /// Returns `None` if this is not a machine parameter lock or the machine does not use the parameter.
+    pub fn machine_value(&self, machine: MachineType) -> Option<f32> {
+        let LockedParameter::MachineParameter(number) = self.parameter else {
+            return None;
+        };
+        let parameter = machine.parameter(number)?;
+        let raw = if parameter.is_compound() {
+            RawPlockValue::Compound(self.raw_value)
+        } else {
+            RawPlockValue::Basic(self.raw_value as u8)
+        };
+        Some(parameter.decode(raw))
+    }
+}
+
+/// Turns raw parameter lock values into entries sorted by track, trig and parameter.
+///
+/// Values are decoded through the trig which they belong to, FX values can be decoded through any trig of the same index.
+fn entries<'a>(
+    values: Vec<(u8, usize, u8, RawPlockValue)>,
+    trig: impl Fn(usize, usize) -> &'a Trig,
+) -> std::vec::IntoIter<PlockEntry> {
+    let mut entries = values
+        .into_iter()
+        .map(|(track_index, trig_index, plock_type, raw)| {
+            let raw_value = match raw {
+                RawPlockValue::Basic(value) => value as u16,
+                RawPlockValue::Compound(value) => value,
+            };
+            let parameter = LockedParameter::new(track_index, plock_type);
+            let value = match parameter {
+                LockedParameter::Parameter(parameter) => trig(track_index as usize, trig_index)
+                    .plock_get_parameter(parameter)
+                    .ok()
+                    .flatten(),
+                _ => None,
+            };
+            PlockEntry {
+                track_index: track_index as usize,
+                trig_index,
+                parameter,
+                value: value.unwrap_or(raw_value as f32),
+                raw_value,
+            }
+        })
+        .collect::<Vec<_>>();
+    entries.sort_by_key(|entry| (entry.track_index, entry.trig_index, entry.parameter));
+    entries.into_iter()
+}
+
+impl Pattern {
+    /// Iterates over every parameter lock in this pattern including the ones of the FX track.
+    ///
+    /// Entries are ordered by track, trig and parameter.
+    pub fn plocks(&self) -> impl Iterator<Item = PlockEntry> {
+        let values = self.parameter_lock_pool.lock().values();
+        entries(values, |track_index, trig_index| {
+            // FX values are stored for the step, any track can decode them.
+            &self.tracks[track_index % 12].trigs[trig_index]
+        })
+    }
+}
+
+impl Track {
+    /// Iterates over every parameter lock of this track ordered by trig and parameter.
+    ///
+    /// The FX track lists the FX parameter locks.
+    /// An orphan track does not have any parameter locks.
+    pub fn plocks(&self) -> impl Iterator<Item = PlockEntry> {
+        let values = self
+            .parameter_lock_pool
+            .as_ref()
+            .map(|pool| pool.lock().values())
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|&(track_index, ..)| track_index as usize == self.index)
+            .collect();
+        entries(values, |_, trig_index| &self.trigs[trig_index])
+    }
+}
+
+impl Trig {
+    /// Iterates over every parameter lock of this trig ordered by parameter.
+    ///
+    /// Trigs of the FX track list the FX parameter locks of their step.
+    /// An orphan trig does not have any parameter locks.
+    pub fn plocks(&self) -> impl Iterator<Item = PlockEntry> {
+        let values = self
+            .parameter_lock_pool
+            .as_ref()
+            .map(|pool| pool.lock().values())
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|&(track_index, trig_index, ..)| {
+                track_index as usize == self.track_index() && trig_index == self.index()
+            })
+            .collect();
+        entries(values, |_, _| self)
+    }
+}
@@ -0,0 +1,350 @@
+// All casts in this file are intended or safe within the context of this library.
+//
+// One can change `allow` to `warn` to review them if necessary.
+#![allow(clippy::cast_possible_truncation)]
+
+use super::{
+    model::velocity,
+    plock::ParameterLockPool,
+    track::{
+        trig::{HoldsTrigFlags, Trig},
+        Track,
+    },
+    Pattern,
+};
+use crate::error::{ParameterError, RytmError};
+use rytm_rs_macro::parameter_range;
+use serde::{Deserialize, Serialize};
+
+/// How a step is resolved when both tracks of an overlay have a trig on it.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MergeConflict {
+    /// Keeps the trig of pattern A.
+    #[default]
+    KeepA,
+    /// Keeps the trig of pattern B.
+    KeepB,
+    /// Keeps the trig which plays louder, the trig of pattern A wins a tie.
+    ///
+    /// Trigs without a velocity play with the default velocity of their track, lock trigs do not play at all.
+    KeepLouder,
+}
+
+/// How a track of a merged pattern is made.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TrackMerge {
+    /// Takes the track of pattern A.
+    #[default]
+    TakeA,
+    /// Takes the track of pattern B including its settings.
+    TakeB,
+    /// Overlays the trigs of both tracks step by step.
+    ///
+    /// Steps which have a trig in one track take that trig, conflicts are resolved by the given policy.
+    /// The settings of the track of pattern A are kept, the number of steps becomes the larger one of both.
+    /// Trigs of pattern B without a velocity get the default velocity of their track if the defaults differ.
+    Overlay(MergeConflict),
+}
+
+/// Describes how each track of two patterns is merged.
+///
+/// Defaults to taking every track from pattern A.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MergePlan {
+    tracks: [TrackMerge; 12],
+    fx_track: TrackMerge,
+}
+
+impl MergePlan {
+    /// Makes a plan which overlays every track including the FX track with the given policy.
+    pub const fn overlay(conflict: MergeConflict) -> Self {
+        Self {
+            tracks: [TrackMerge::Overlay(conflict); 12],
+            fx_track: TrackMerge::Overlay(conflict),
+        }
+    }
+
+    /// Sets how a track is merged.
+    ///
+    /// Range `0..=11`
+    #[parameter_range(range = "track_index:0..=11")]
+    pub fn set_track(&mut self, track_index: usize, merge: TrackMerge) -> Result<(), RytmError> {
+        self.tracks[track_index] = merge;
+        Ok(())
+    }
+
+    /// Sets how the FX track is merged.
+    pub const fn set_fx_track(&mut self, merge: TrackMerge) {
+        self.fx_track = merge;
+    }
+
+    /// Returns how a track is merged.
+    ///
+    /// Returns `None` if the track index is out of range.
+    pub fn track(&self, track_index: usize) -> Option<TrackMerge> {
+        self.tracks.get(track_index).copied()
+    }
+
+    /// Returns how the FX track is merged.
+    pub const fn fx_track(&self) -> TrackMerge {
+        self.fx_track
+    }
+}
+
+/// Returns how loud a trig plays, `None` for lock trigs.
+fn loudness(trig: &Trig, track: &Track) -> Option<usize> {
+    if trig.enabled_lock_trig() {
+        None
+    } else {
+        Some(velocity(trig, track))
+    }
+}
+
+/// Returns the steps of an overlay which take the trig of pattern B.
+fn steps_taken_from_b(a: &Track, b: &Track, conflict: MergeConflict) -> Vec<usize> {
+    a.trigs
+        .iter()
+        .zip(&b.trigs)
+        .enumerate()
+        .filter(|(_, (trig_a, trig_b))| {
+            if !trig_b.enabled_trig() {
+                return false;
+            }
+            if !trig_a.enabled_trig() {
+                return true;
+            }
+            match conflict {
+                MergeConflict::KeepA => false,
+                MergeConflict::KeepB => true,
+                MergeConflict::KeepLouder => loudness(trig_b, b) > loudness(trig_a, a),
+            }
+        })
+        .map(|(step, _)| step)
+        .collect()
+}
+
+/// Merges the parameter locks of a track of pattern B into the pool and returns the steps which take the trigs of pattern B.
+///
+/// Parameter locks which do not fit are added to the given list.
+fn merge_plocks_of_track(
+    pool: &mut ParameterLockPool,
+    source_pool: &ParameterLockPool,
+    a: &Track,
+    b: &Track,
+    merge: TrackMerge,
+    did_not_fit: &mut Vec<String>,
+) -> Result<Vec<usize>, RytmError> {
+    let track_index = a.index as u8;
+    let (result, steps) = match merge {
+        TrackMerge::TakeA => return Ok(Vec::new()),
+        TrackMerge::TakeB => (
+            pool.copy_plocks_of_track_from(source_pool, track_index, track_index),
+            Vec::new(),
+        ),
+        TrackMerge::Overlay(conflict) => {
+            let steps = steps_taken_from_b(a, b, conflict);
+            // Go through every step so the list of parameter locks which do not fit is complete.
+            for &step in &steps {
+                match pool.copy_plocks_of_trig_from(source_pool, step, step, track_index) {
+                    Ok(()) => {}
+                    Err(RytmError::ParameterLockMemoryFullWith {
+                        did_not_fit: descriptions,
+                    }) => {
+                        for description in descriptions {
+                            if !did_not_fit.contains(&description) {
+                                did_not_fit.push(description);
+                            }
+                        }
+                    }
+                    Err(error) => return Err(error),
+                }
+            }
+            (Ok(()), steps)
+        }
+    };
+
+    match result {
+        Ok(()) => Ok(steps),
+        Err(RytmError::ParameterLockMemoryFullWith {
+            did_not_fit: mut descriptions,
+        }) => {
+            did_not_fit.append(&mut descriptions);
+            Ok(steps)
+        }
+        Err(error) => Err(error),
+    }
+}
+
+/// Writes the trigs of a track of pattern B into a track of pattern A.
+fn merge_trigs_of_track(a: &mut Track, b: &Track, merge: TrackMerge, steps: &[usize]) {
+    match merge {
+        TrackMerge::TakeA => {}
+        TrackMerge::TakeB => a.copy_data_from(b),
+        TrackMerge::Overlay(_) => {
+            for &step in steps {
+                let trig = &mut a.trigs[step];
+                trig.copy_data_from(&b.trigs[step]);
+                // The trig would play with the default velocity of track A otherwise.
+                if trig.velocity() > 127 && a.default_trig_velocity != b.default_trig_velocity {
+                    trig.set_velocity(velocity(&b.trigs[step], b))
+                        .expect("Velocities are masked to 0..=127.");
+                }
+            }
+            a.number_of_steps = a.number_of_steps.max(b.number_of_steps);
+        }
+    }
+}
+
+impl Pattern {
+    /// Merges this pattern as pattern A with another pattern as pattern B into a new pattern.
+    ///
+    /// The new pattern starts as a copy of this pattern and then is merged like [`Pattern::merge_from`].
+    ///
+    /// Range `0..=127` for the target index.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the target index is out of range or the merge fails, see [`Pattern::merge_from`].
+    pub fn merge(
+        &self,
+        other: &Self,
+        target_index: usize,
+        plan: &MergePlan,
+    ) -> Result<Self, RytmError> {
+        let mut pattern = Self::try_default(target_index)?;
+        pattern.copy_data_from(self);
+        pattern.merge_from(other, plan)?;
+        Ok(pattern)
+    }
+
+    /// Merges another pattern as pattern B into this pattern as pattern A track by track.
+    ///
+    /// Every track including the FX track is taken from pattern A, taken from pattern B or overlaid as the plan describes.
+    /// Trigs keep all their attributes and bring their parameter locks along into the parameter lock pool of this pattern.
+    /// Parameter locks of trigs which are not taken are dropped.
+    ///
+    /// Pattern level settings like the speed and the master length are kept from pattern A.
+    ///
+    /// # Errors
+    ///
+    /// If the parameter locks do not fit [`RytmError::ParameterLockMemoryFullWith`] is returned listing the parameter locks which did not fit.
+    /// In that case this pattern is left untouched.
+    pub fn merge_from(&mut self, other: &Self, plan: &MergePlan) -> Result<(), RytmError> {
+        // Clone first, patterns which are cloned from each other share the same FX track and pool.
+        let source_pool = other.parameter_lock_pool.lock().clone();
+        let other_fx_track = other.fx_track.lock().clone();
+        let mut pool = self.parameter_lock_pool.lock().clone();
+        let mut fx_track = self.fx_track.lock().clone();
+
+        // Parameter locks are merged into a staged pool first so nothing changes unless everything fits.
+        let mut did_not_fit = Vec::new();
+        let mut steps = Vec::with_capacity(12);
+        for ((a, b), &merge) in self.tracks.iter().zip(&other.tracks).zip(&plan.tracks) {
+            steps.push(merge_plocks_of_track(
+                &mut pool,
+                &source_pool,
+                a,
+                b,
+                merge,
+                &mut did_not_fit,
+            )?);
+        }
+        let fx_steps = merge_plocks_of_track(
+            &mut pool,
+            &source_pool,
+            &fx_track,
+            &other_fx_track,
+            plan.fx_track,
+            &mut did_not_fit,
+        )?;
+
+        if !did_not_fit.is_empty() {
+            return Err(RytmError::ParameterLockMemoryFullWith { did_not_fit });
+        }
+
+        for (((a, b), &merge), steps) in self
+            .tracks
+            .iter_mut()
+            .zip(&other.tracks)
+            .zip(&plan.tracks)
+            .zip(&steps)
+        {
+            merge_trigs_of_track(a, b, merge, steps);
+        }
+        merge_trigs_of_track(&mut fx_track, &other_fx_track, plan.fx_track, &fx_steps);
+
+        self.fx_track.lock().copy_data_from(&fx_track);
+        *self.parameter_lock_pool.lock() = pool;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::object::pattern::track::trig::parameter::PlockParameter;
+
+    #[test]
+    fn louder_trigs_are_kept_counting_unset_velocities_as_the_track_default() {
+        let mut a = Pattern::try_default(0).unwrap();
+        let track_a = &mut a.tracks_mut()[0];
+        track_a.set_default_trig_velocity(100).unwrap();
+        for step in 0..3 {
+            track_a.trigs_mut()[step].set_trig_enable(true);
+        }
+
+        let mut b = Pattern::try_default(1).unwrap();
+        let track_b = &mut b.tracks_mut()[0];
+        track_b.set_default_trig_velocity(120).unwrap();
+        for step in 0..3 {
+            track_b.trigs_mut()[step].set_trig_enable(true);
+        }
+        track_b.trigs_mut()[0].set_velocity(90).unwrap();
+        track_b.trigs_mut()[1].set_velocity(110).unwrap();
+
+        a.merge_from(&b, &MergePlan::overlay(MergeConflict::KeepLouder))
+            .unwrap();
+
+        let trigs = a.tracks()[0].trigs();
+        // Pattern A plays at 100 against 90.
+        assert!(trigs[0].velocity() > 127);
+        // Pattern B plays at 110 against 100.
+        assert_eq!(trigs[1].velocity(), 110);
+        // Pattern B plays at its own default of 120 against 100, which is kept by setting the velocity.
+        assert_eq!(trigs[2].velocity(), 120);
+        assert_eq!(a.tracks()[0].default_trig_velocity(), 100);
+    }
+
+    #[test]
+    fn pattern_is_left_untouched_if_the_plocks_do_not_fit() {
+        let mut a = Pattern::try_default(0).unwrap();
+        for track in a.tracks() {
+            for parameter in [
+                PlockParameter::FilterAttack,
+                PlockParameter::FilterSustain,
+                PlockParameter::FilterDecay,
+                PlockParameter::FilterRelease,
+                PlockParameter::FilterCutoff,
+                PlockParameter::FilterResonance,
+            ] {
+                track.trigs()[0]
+                    .plock_set_parameter(parameter, 10.0)
+                    .unwrap();
+            }
+        }
+
+        let mut b = Pattern::try_default(1).unwrap();
+        let trig = &mut b.tracks_mut()[0].trigs_mut()[1];
+        trig.set_trig_enable(true);
+        trig.plock_set_parameter(PlockParameter::AmplitudeAttack, 10.0)
+            .unwrap();
+
+        let before = serde_json::to_value(&a).unwrap();
+        assert!(matches!(
+            a.merge_from(&b, &MergePlan::overlay(MergeConflict::KeepB)),
+            Err(RytmError::ParameterLockMemoryFullWith { did_not_fit }) if did_not_fit.len() == 1
+        ));
+        assert_eq!(serde_json::to_value(&a).unwrap(), before);
+    }
+}
@@ -0,0 +1,383 @@
+// All casts in this file are intended or safe within the context of this library.
+//
+// One can change `allow` to `warn` to review them if necessary.
+#![allow(
+    clippy::cast_possible_truncation,
+    clippy::cast_sign_loss,
+    clippy::cast_precision_loss
+)]
+
+use super::{
+    track::{
+        trig::{HoldsTrigFlags, Trig},
+        Track,
+    },
+    Pattern,
+};
+use crate::{error::RytmError, util::SeededRng};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Number of velocity bands which the model distinguishes.
+const VELOCITY_BANDS: usize = 4;
+
+/// Number of step positions in a beat which the transitions depend on.
+const BEAT_POSITIONS: usize = 4;
+
+/// Code of a step which does not play.
+const OFF: u16 = 0;
+
+/// Returns the code of a step which plays with a velocity and note.
+///
+/// Codes start from `1` and are ordered by velocity band and note.
+const fn on(velocity: usize, note: usize) -> u16 {
+    let band = velocity * VELOCITY_BANDS / 128;
+    (1 + band * 128 + note) as u16
+}
+
+/// Returns the velocity band and the note of a step code which plays.
+const fn decode(code: u16) -> (usize, usize) {
+    let code = code as usize - 1;
+    (code / 128, code % 128)
+}
+
+/// Returns the velocity of a trig, trigs without a velocity play with the default velocity of their track.
+pub(super) const fn velocity(trig: &Trig, track: &Track) -> usize {
+    if trig.velocity() > 127 {
+        track.default_trig_velocity as usize & 0x7F
+    } else {
+        trig.velocity()
+    }
+}
+
+/// Returns the code of a trig.
+fn code(trig: &Trig, track: &Track) -> u16 {
+    if trig.enabled_trig() {
+        on(velocity(trig, track), trig.note() & 0x7F)
+    } else {
+        OFF
+    }
+}
+
+/// Statistics of a single track.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+struct TrackModel {
+    /// Counts of step codes keyed by the position in the beat and the code of the previous step.
+    transitions: BTreeMap<usize, BTreeMap<u16, BTreeMap<u16, usize>>>,
+    /// Sums of the velocities in each band.
+    velocity_sums: [usize; VELOCITY_BANDS],
+    /// Number of trigs in each band.
+    velocity_counts: [usize; VELOCITY_BANDS],
+}
+
+impl TrackModel {
+    /// Returns the counts of the codes which follow the previous code at a position, backing off to the position alone.
+    fn distribution(&self, position: usize, previous: u16) -> BTreeMap<u16, usize> {
+        let Some(by_previous) = self.transitions.get(&position) else {
+            return BTreeMap::new();
+        };
+        if let Some(counts) = by_previous.get(&previous) {
+            return counts.clone();
+        }
+        let mut counts = BTreeMap::new();
+        for (&code, &count) in by_previous.values().flatten() {
+            *counts.entry(code).or_insert(0) += count;
+        }
+        counts
+    }
+
+    /// Returns the average velocity of a band.
+    const fn velocity(&self, band: usize) -> usize {
+        if self.velocity_counts[band] == 0 {
+            // Middle of the band.
+            return band * 128 / VELOCITY_BANDS + 128 / VELOCITY_BANDS / 2;
+        }
+        self.velocity_sums[band] / self.velocity_counts[band]
+    }
+
+    /// Checks if the track plays in any of the learned patterns.
+    fn is_learned(&self) -> bool {
+        self.velocity_counts.iter().any(|&count| count > 0)
+    }
+}
+
+/// A statistical model of patterns learned from examples.
+///
+/// For each track the model counts how steps follow each other, depending on the position of the step in the beat.
+/// A step is either silent or plays with a velocity band and a note.
+/// It also counts how often tracks play together on the same step.
+///
+/// Tracks which do not play at all in a pattern are not learned from that pattern,
+/// so empty patterns of a project do not weigh the model down.
+///
+/// Notes are learned as they are stored in the trigs, including notes out of the device range `36..=84`.
+/// See [`Pattern::generate_from_model`] for how they are generated.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PatternModel {
+    tracks: Vec<TrackModel>,
+    /// Number of steps in which two tracks play together, the diagonal holds the number of steps in which a track plays.
+    co_occurrence: Vec<Vec<usize>>,
+}
+
+impl Default for PatternModel {
+    fn default() -> Self {
+        Self {
+            tracks: vec![TrackModel::default(); 12],
+            co_occurrence: vec![vec![0; 12]; 12],
+        }
+    }
+}
+
+impl PatternModel {
+    /// Learns a model from a set of patterns, for example [`RytmProject::patterns`](crate::RytmProject::patterns).
+    pub fn learn<'a>(patterns: impl IntoIterator<Item = &'a Pattern>) -> Self {
+        let mut model = Self::default();
+        for pattern in patterns {
+            model.add_pattern(pattern);
+        }
+        model
+    }
+
+    /// Adds the statistics of a pattern to the model.
+    pub fn add_pattern(&mut self, pattern: &Pattern) {
+        let codes = pattern
+            .tracks
+            .iter()
+            .map(|track| {
+                track.trigs[..track.number_of_steps as usize]
+                    .iter()
+                    .map(|trig| code(trig, track))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+
+        for (track_index, (track, codes)) in pattern.tracks.iter().zip(&codes).enumerate() {
+            if codes.iter().all(|&code| code == OFF) {
+                continue;
+            }
+
+            let model = &mut self.tracks[track_index];
+            for (step, &code) in codes.iter().enumerate() {
+                // Patterns loop so the first step follows the last one.
+                let previous = codes[(step + codes.len() - 1) % codes.len()];
+                *model
+                    .transitions
+                    .entry(step % BEAT_POSITIONS)
+                    .or_default()
+                    .entry(previous)
+                    .or_default()
+                    .entry(code)
+                    .or_insert(0) += 1;
+
+                if code != OFF {
+                    let velocity = velocity(&track.trigs[step], track);
+                    let band = decode(code).0;
+                    model.velocity_sums[band] += velocity;
+                    model.velocity_counts[band] += 1;
+                }
+            }
+        }
+
+        for (i, codes_i) in codes.iter().enumerate() {
+            for (j, codes_j) in codes.iter().enumerate() {
+                self.co_occurrence[i][j] += codes_i
+                    .iter()
+                    .zip(codes_j)
+                    .filter(|&(&a, &b)| a != OFF && b != OFF)
+                    .count();
+            }
+        }
+    }
+
+    /// Returns the probability that a track plays given that another track plays on the same step.
+    fn conditional_probability(&self, track_index: usize, given_track_index: usize) -> Option<f32> {
+        let given = self.co_occurrence[given_track_index][given_track_index];
+        if given == 0 {
+            return None;
+        }
+        Some(self.co_occurrence[track_index][given_track_index] as f32 / given as f32)
+    }
+
+    /// Picks the code of a step.
+    ///
+    /// The probability of playing is blended with how often the track plays together with the tracks which already play on this step.
+    fn pick(
+        &self,
+        track_index: usize,
+        position: usize,
+        previous: u16,
+        playing: &[usize],
+        rng: &mut SeededRng,
+    ) -> u16 {
+        let counts = self.tracks[track_index].distribution(position, previous);
+        let total = counts.values().sum::<usize>();
+        let on_total = counts
+            .iter()
+            .filter(|&(&code, _)| code != OFF)
+            .map(|(_, &count)| count)
+            .sum::<usize>();
+        if on_total == 0 {
+            return OFF;
+        }
+
+        let mut probability = on_total as f32 / total as f32;
+        let conditionals = playing
+            .iter()
+            .filter_map(|&given| self.conditional_probability(track_index, given))
+            .collect::<Vec<_>>();
+        if !conditionals.is_empty() {
+            let conditional = conditionals.iter().sum::<f32>() / conditionals.len() as f32;
+            probability = f32::midpoint(probability, conditional);
+        }
+        if !rng.chance(probability) {
+            return OFF;
+        }
+
+        let mut target = rng.range_usize(0, on_total - 1);
+        for (&code, &count) in counts.iter().filter(|&(&code, _)| code != OFF) {
+            if target < count {
+                return code;
+            }
+            target -= count;
+        }
+
+        OFF
+    }
+}
+
+impl Pattern {
+    /// Generates the trigs of the tracks which the model has learned, in the style of the learned patterns.
+    ///
+    /// The trigs and the parameter locks of those tracks are reset before generating, other tracks are left untouched.
+    /// The track settings like the number of steps are kept, only the steps in range are generated.
+    ///
+    /// Generated trigs get a velocity and a note from the model.
+    /// Only learned notes in the device range `36..=84` are written,
+    /// trigs which are generated from a learned note out of that range keep the default note `60` of a trig.
+    ///
+    /// The same seed and model always generate the same pattern.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a trig can not be written.
+    pub fn generate_from_model(
+        &mut self,
+        model: &PatternModel,
+        seed: u64,
+    ) -> Result<(), RytmError> {
+        let learned = (0..12)
+            .filter(|&track_index| model.tracks[track_index].is_learned())
+            .collect::<Vec<_>>();
+
+        for &track_index in &learned {
+            self.clear_all_plocks_for_track(track_index as u8)?;
+            for trig in &mut self.tracks[track_index].trigs {
+                let default = Trig::try_default(trig.index(), trig.track_index())?;
+                trig.copy_data_from(&default);
+            }
+        }
+
+        let mut rng = SeededRng::new(seed);
+        let mut previous = learned
+            .iter()
+            .map(|&track_index| (track_index, OFF))
+            .collect::<BTreeMap<_, _>>();
+
+        // Steps are generated across the tracks so tracks can follow each other.
+        for step in 0..64 {
+            let mut playing = Vec::new();
+            for &track_index in &learned {
+                let track = &mut self.tracks[track_index];
+                if step >= track.number_of_steps as usize {
+                    continue;
+                }
+
+                let code = model.pick(
+                    track_index,
+                    step % BEAT_POSITIONS,
+                    previous[&track_index],
+                    &playing,
+                    &mut rng,
+                );
+                previous.insert(track_index, code);
+                if code == OFF {
+                    continue;
+                }
+
+                let (band, note) = decode(code);
+                let trig = &mut track.trigs[step];
+                trig.set_trig_enable(true);
+                trig.set_velocity(model.tracks[track_index].velocity(band).clamp(1, 127))?;
+                if (36..=84).contains(&note) {
+                    trig.set_note(note)?;
+                }
+                playing.push(track_index);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns a pattern with a four on the floor kick on the first track.
+    fn four_on_the_floor() -> Pattern {
+        let mut pattern = Pattern::try_default(0).unwrap();
+        for trig in pattern.tracks_mut()[0]
+            .trigs_mut()
+            .iter_mut()
+            .step_by(4)
+            .take(4)
+        {
+            trig.set_trig_enable(true);
+            trig.set_velocity(100).unwrap();
+        }
+        pattern
+    }
+
+    #[test]
+    fn four_on_the_floor_is_reproduced() {
+        let source = four_on_the_floor();
+        let model = PatternModel::learn([&source]);
+
+        let mut generated = Pattern::try_default(1).unwrap();
+        generated.generate_from_model(&model, 3).unwrap();
+
+        for (a, b) in source.tracks()[0]
+            .trigs()
+            .iter()
+            .zip(generated.tracks()[0].trigs())
+        {
+            assert_eq!(a.enabled_trig(), b.enabled_trig());
+            assert_eq!(a.velocity(), b.velocity());
+            assert_eq!(a.note(), b.note());
+        }
+        assert!(generated.tracks()[1..]
+            .iter()
+            .all(|track| track.trigs().iter().all(|trig| !trig.enabled_trig())));
+    }
+
+    #[test]
+    fn same_seed_generates_the_same_pattern() {
+        let mut source = four_on_the_floor();
+        for (step, trig) in source.tracks_mut()[1].trigs_mut()[..16]
+            .iter_mut()
+            .enumerate()
+        {
+            trig.set_trig_enable(step % 3 != 0);
+            trig.set_velocity(20 + step * 7).unwrap();
+            trig.set_note(40 + step).unwrap();
+        }
+        let model = PatternModel::learn([&source]);
+
+        let generate = |seed| {
+            let mut pattern = Pattern::try_default(1).unwrap();
+            pattern.generate_from_model(&model, seed).unwrap();
+            serde_json::to_value(&pattern).unwrap()
+        };
+        assert_eq!(generate(11), generate(11));
+    }
+}
@@ -0,0 +1,969 @@
+#![allow(clippy::too_many_lines)]
+
+use super::{
+    plock::RawPlockValue,
+    track::{
+        trig::{
+            flags::*,
+            parameter::PlockParameter,
+            types::{Length, RetrigRate, TrigCondition},
+            HoldsTrigFlags, Trig,
+        },
+        Track,
+    },
+    Pattern,
+};
+use crate::{defaults::default_trig_array, error::RytmError};
+
+/// Short names of the tracks in the grid notation, the 13th is the FX track.
+pub const TRACK_NAMES: [&str; 13] = [
+    "BD", "SD", "RS", "CP", "BT", "LT", "MT", "HT", "CH", "OH", "CY", "CB", "FX",
+];
+
+/// Trig flags which can be annotated by name, the step character sets the flags of its kind.
+const FLAGS: [(&str, u16); 14] = [
+    ("enable", ENABLE),
+    ("retrig", RETRIG),
+    ("mute", MUTE),
+    ("accent", ACCENT),
+    ("swing", SWING),
+    ("slide", SLIDE),
+    ("lfo_pl_sw", LFO_PL_SW),
+    ("syn_pl_sw", SYN_PL_SW),
+    ("smp_pl_sw", SMP_PL_SW),
+    ("env_pl_sw", ENV_PL_SW),
+    ("lfo_pl_en", LFO_PL_EN),
+    ("syn_pl_en", SYN_PL_EN),
+    ("smp_pl_en", SMP_PL_EN),
+    ("env_pl_en", ENV_PL_EN),
+];
+
+/// Parameter lock type of the first synth parameter of a machine, the others follow it.
+const SYNTH_PLOCK_TYPE: u8 = rytm_sys::AR_PLOCK_TYPE_MP0 as u8;
+
+/// What a step of a row holds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum StepKind {
+    Empty,
+    Trig,
+    Accent,
+    Lock,
+}
+
+impl StepKind {
+    const fn from_char(c: char) -> Option<Self> {
+        match c {
+            '.' => Some(Self::Empty),
+            'x' => Some(Self::Trig),
+            'X' => Some(Self::Accent),
+            '-' => Some(Self::Lock),
+            _ => None,
+        }
+    }
+
+    /// Returns the kind of a trig, the FX row only has empty steps and trigs.
+    fn from_trig(trig: &Trig, is_fx: bool) -> Self {
+        if is_fx {
+            if trig.enabled_trig() {
+                Self::Trig
+            } else {
+                Self::Empty
+            }
+        } else if trig.enabled_lock_trig() {
+            Self::Lock
+        } else if !trig.enabled_trig() {
+            Self::Empty
+        } else if trig.enabled_accent() {
+            Self::Accent
+        } else {
+            Self::Trig
+        }
+    }
+
+    const fn as_char(self) -> char {
+        match self {
+            Self::Empty => '.',
+            Self::Trig => 'x',
+            Self::Accent => 'X',
+            Self::Lock => '-',
+        }
+    }
+
+    /// Sets the flags of this kind on a default trig.
+    fn apply(self, trig: &mut Trig) {
+        match self {
+            Self::Empty => {}
+            Self::Trig => trig.set_trig_enable(true),
+            Self::Accent => {
+                trig.set_trig_enable(true);
+                trig.set_accent(true);
+            }
+            Self::Lock => trig.set_lock_trig(true),
+        }
+    }
+}
+
+/// A parsed step of a row.
+#[derive(Debug, Clone)]
+struct Step {
+    kind: StepKind,
+    velocity: Option<u8>,
+    note: Option<u8>,
+    note_length: Option<Length>,
+    trig_condition: Option<TrigCondition>,
+    micro_timing: Option<isize>,
+    retrig_rate: Option<RetrigRate>,
+    retrig_length: Option<Length>,
+    retrig_velocity_offset: Option<isize>,
+    sound_lock: Option<usize>,
+    flags: Vec<(u16, bool)>,
+    plocks: Vec<(PlockParameter, f32)>,
+    synth_plocks: Vec<(u8, RawPlockValue)>,
+}
+
+impl Step {
+    const fn new(kind: StepKind) -> Self {
+        Self {
+            kind,
+            velocity: None,
+            note: None,
+            note_length: None,
+            trig_condition: None,
+            micro_timing: None,
+            retrig_rate: None,
+            retrig_length: None,
+            retrig_velocity_offset: None,
+            sound_lock: None,
+            flags: Vec::new(),
+            plocks: Vec::new(),
+            synth_plocks: Vec::new(),
+        }
+    }
+}
+
+/// A parsed row.
+#[derive(Debug, Clone)]
+struct Row {
+    track_index: usize,
+    steps: Vec<Step>,
+}
+
+fn error(line: usize, column: usize, reason: impl Into<String>) -> RytmError {
+    RytmError::GridNotation {
+        line,
+        column,
+        reason: reason.into(),
+    }
+}
+
+/// Returns the 1 based column of a byte offset in a line.
+fn column(line: &str, offset: usize) -> usize {
+    line[..offset].chars().count() + 1
+}
+
+/// Splits a part of a line by whitespace keeping the byte offsets of the words in the line.
+fn words(line: &str, start: usize, end: usize) -> Vec<(usize, &str)> {
+    let mut words = Vec::new();
+    let mut word_start = None;
+
+    for (offset, c) in line[start..end]
+        .char_indices()
+        .map(|(i, c)| (start + i, c))
+        .chain(std::iter::once((end, ' ')))
+    {
+        match (word_start, c.is_whitespace()) {
+            (None, false) => word_start = Some(offset),
+            (Some(word_start_offset), true) => {
+                words.push((word_start_offset, &line[word_start_offset..offset]));
+                word_start = None;
+            }
+            _ => {}
+        }
+    }
+
+    words
+}
+
+/// Parses a number which must be in the given range.
+fn parse_in_range<T>(
+    value: &str,
+    range: std::ops::RangeInclusive<T>,
+    name: &str,
+) -> Result<T, String>
+where
+    T: std::str::FromStr + PartialOrd + std::fmt::Display,
+{
+    let parsed = value
+        .parse::<T>()
+        .map_err(|_| format!("\"{value}\" is not a valid {name}"))?;
+    if range.contains(&parsed) {
+        Ok(parsed)
+    } else {
+        Err(format!(
+            "{name} {value} is out of range {}..={}",
+            range.start(),
+            range.end()
+        ))
+    }
+}
+
+/// Parses a field which sets a value of the trig itself.
+///
+/// Returns `None` if the key is not a trig field.
+fn parse_trig_field(key: &str, value: &str, step: &mut Step) -> Option<Result<(), String>> {
+    let length = |name: &str| {
+        Length::try_from(value).map_err(|_| format!("\"{value}\" is not a valid {name}"))
+    };
+
+    let parsed = match key {
+        "v" => parse_in_range(value, 0..=254, "velocity").map(|v| step.velocity = Some(v)),
+        "n" => parse_in_range(value, 0..=127, "note").map(|n| step.note = Some(n)),
+        "l" => length("note length").map(|l| step.note_length = Some(l)),
+        "c" => TrigCondition::try_from(value)
+            .map(|c| step.trig_condition = Some(c))
+            .map_err(|_| format!("\"{value}\" is not a valid trig condition")),
+        "m" => parse_in_range(value, -23..=23, "micro timing").map(|m| step.micro_timing = Some(m)),
+        "rr" => RetrigRate::try_from(value)
+            .map(|r| step.retrig_rate = Some(r))
+            .map_err(|_| format!("\"{value}\" is not a valid retrig rate")),
+        "rl" => length("retrig length").map(|l| step.retrig_length = Some(l)),
+        "rv" => parse_in_range(value, -128..=127, "retrig velocity offset")
+            .map(|r| step.retrig_velocity_offset = Some(r)),
+        "s" => parse_in_range(value, 0..=127, "sound lock").map(|s| step.sound_lock = Some(s)),
+        _ => {
+            let &(name, flag) = FLAGS.iter().find(|(name, _)| *name == key)?;
+            parse_in_range(value, 0..=1, name)
+                .map(|enabled: u8| step.flags.push((flag, enabled == 1)))
+        }
+    };
+
+    Some(parsed)
+}
+
+/// Parses the raw value of a synth parameter lock, a byte in decimal or the two bytes of a compound lock in hexadecimal.
+fn parse_synth_plock(value: &str) -> Result<RawPlockValue, String> {
+    if let Some(hex) = value.strip_prefix("0x") {
+        return u16::from_str_radix(hex, 16)
+            .ok()
+            .filter(|&value| hex.len() == 4 && value >> 8 != 0xFF)
+            .map(RawPlockValue::Compound)
+            .ok_or_else(|| {
+                format!("\"{value}\" is not a compound lock value, range 0x0000..=0xFEFF")
+            });
+    }
+    parse_in_range(value, 0..=254, "lock value").map(RawPlockValue::Basic)
+}
+
+/// Parses the annotation of a step, `offset` is the byte offset of the fields in the line.
+fn parse_fields(
+    line: &str,
+    line_number: usize,
+    offset: usize,
+    fields: &str,
+    is_fx: bool,
+    step: &mut Step,
+) -> Result<(), RytmError> {
+    let mut field_offset = offset;
+    let mut keys = Vec::new();
+
+    for field in fields.split(',') {
+        let at = |offset: usize| column(line, offset);
+
+        let Some((key, value)) = field.split_once('=') else {
+            return Err(error(
+                line_number,
+                at(field_offset),
+                format!("expected key=value but found \"{field}\""),
+            ));
+        };
+        let value_offset = field_offset + key.len() + 1;
+
+        if keys.contains(&key) {
+            return Err(error(
+                line_number,
+                at(field_offset),
+                format!("\"{key}\" is given more than once"),
+            ));
+        }
+        keys.push(key);
+
+        if let Some(parsed) = parse_trig_field(key, value, step) {
+            parsed.map_err(|reason| error(line_number, at(value_offset), reason))?;
+        } else if let Some(number) = key.strip_prefix('#') {
+            let number = parse_in_range(number, 1..=8, "synth parameter")
+                .ok()
+                .filter(|_| !is_fx)
+                .ok_or_else(|| {
+                    error(
+                        line_number,
+                        at(field_offset),
+                        format!("\"{key}\" is not a synth parameter, expected #1 to #8 outside the FX row"),
+                    )
+                })?;
+            let parsed = parse_synth_plock(value)
+                .map_err(|reason| error(line_number, at(value_offset), reason))?;
+            step.synth_plocks
+                .push((SYNTH_PLOCK_TYPE + number - 1, parsed));
+        } else {
+            let parameter = PlockParameter::try_from(key).map_err(|_| {
+                error(
+                    line_number,
+                    at(field_offset),
+                    format!("\"{key}\" is not a valid annotation key"),
+                )
+            })?;
+
+            if parameter.is_fx() != is_fx {
+                let reason = if is_fx {
+                    format!("\"{key}\" can not be locked in the FX row")
+                } else {
+                    format!("\"{key}\" can only be locked in the FX row")
+                };
+                return Err(error(line_number, at(field_offset), reason));
+            }
+
+            let range = parameter.range();
+            let parsed = value
+                .parse::<f32>()
+                .ok()
+                .filter(|value| parameter.accepts(*value))
+                .ok_or_else(|| {
+                    error(
+                        line_number,
+                        at(value_offset),
+                        format!(
+                            "\"{value}\" is not accepted by {key}, range {}..={}",
+                            range.start(),
+                            range.end()
+                        ),
+                    )
+                })?;
+            step.plocks.push((parameter, parsed));
+        }
+
+        field_offset += field.len() + 1;
+    }
+
+    Ok(())
+}
+
+/// Parses a row, returns `None` for blank and comment lines.
+fn parse_row(line: &str, line_number: usize) -> Result<Option<Row>, RytmError> {
+    let trimmed = line.trim_start();
+    if trimmed.is_empty() || trimmed.starts_with('#') {
+        return Ok(None);
+    }
+
+    let separator = line.find('|');
+    let grid = words(line, 0, separator.unwrap_or(line.len()));
+
+    let Some(&(name_offset, name)) = grid.first() else {
+        return Err(error(
+            line_number,
+            column(line, line.len() - trimmed.len()),
+            "expected a track name",
+        ));
+    };
+    let Some(track_index) = TRACK_NAMES
+        .iter()
+        .position(|track_name| track_name.eq_ignore_ascii_case(name))
+    else {
+        return Err(error(
+            line_number,
+            column(line, name_offset),
+            format!(
+                "\"{name}\" is not a track name, expected one of {}",
+                TRACK_NAMES.join(", ")
+            ),
+        ));
+    };
+    let is_fx = track_index == 12;
+
+    let Some(&(steps_offset, steps)) = grid.get(1) else {
+        return Err(error(
+            line_number,
+            column(line, name_offset + name.len()) + 1,
+            "expected the steps of the track",
+        ));
+    };
+    if let Some(&(offset, word)) = grid.get(2) {
+        return Err(error(
+            line_number,
+            column(line, offset),
+            format!("unexpected \"{word}\" after the steps, annotations start with |"),
+        ));
+    }
+
+    let mut parsed_steps = Vec::new();
+    for (i, (offset, c)) in steps.char_indices().enumerate() {
+        let at = column(line, steps_offset + offset);
+        if i == 64 {
+            return Err(error(line_number, at, "a track can have at most 64 steps"));
+        }
+        let kind = StepKind::from_char(c)
+            .filter(|kind| !is_fx || matches!(kind, StepKind::Empty | StepKind::Trig))
+            .ok_or_else(|| {
+                let expected = if is_fx { ". or x" } else { "., x, X or -" };
+                error(
+                    line_number,
+                    at,
+                    format!("\"{c}\" is not a step, expected {expected}"),
+                )
+            })?;
+        parsed_steps.push(Step::new(kind));
+    }
+
+    if let Some(separator) = separator {
+        let mut annotated = vec![false; parsed_steps.len()];
+
+        for (offset, annotation) in words(line, separator + 1, line.len()) {
+            let at = column(line, offset);
+            let Some((step_index, fields)) = annotation.split_once(':') else {
+                return Err(error(
+                    line_number,
+                    at,
+                    format!("expected step:key=value but found \"{annotation}\""),
+                ));
+            };
+
+            let step_index = step_index
+                .parse::<usize>()
+                .ok()
+                .filter(|&step_index| step_index < parsed_steps.len())
+                .ok_or_else(|| {
+                    error(
+                        line_number,
+                        at,
+                        format!(
+                            "\"{step_index}\" is not a step of this track, range 0..={}",
+                            parsed_steps.len() - 1
+                        ),
+                    )
+                })?;
+
+            if annotated[step_index] {
+                return Err(error(
+                    line_number,
+                    at,
+                    format!("step {step_index} is annotated more than once"),
+                ));
+            }
+            annotated[step_index] = true;
+
+            let fields_offset = offset + annotation.len() - fields.len();
+            parse_fields(
+                line,
+                line_number,
+                fields_offset,
+                fields,
+                is_fx,
+                &mut parsed_steps[step_index],
+            )?;
+        }
+    }
+
+    Ok(Some(Row {
+        track_index,
+        steps: parsed_steps,
+    }))
+}
+
+/// Parses all rows, checking that no track is given twice.
+fn parse_rows(notation: &str) -> Result<Vec<Row>, RytmError> {
+    let mut rows: Vec<Row> = Vec::new();
+
+    for (i, line) in notation.lines().enumerate() {
+        let Some(row) = parse_row(line, i + 1)? else {
+            continue;
+        };
+        if rows
+            .iter()
+            .any(|other| other.track_index == row.track_index)
+        {
+            return Err(error(
+                i + 1,
+                column(line, line.len() - line.trim_start().len()),
+                format!(
+                    "track {} is given more than once",
+                    TRACK_NAMES[row.track_index]
+                ),
+            ));
+        }
+        rows.push(row);
+    }
+
+    Ok(rows)
+}
+
+/// Renders the fields of a trig which differ from the trig which its step character is parsed as.
+fn render_trig_fields(trig: &Trig, default: &Trig, is_fx: bool) -> Vec<String> {
+    let mut expected = default.clone();
+    StepKind::from_trig(trig, is_fx).apply(&mut expected);
+
+    let mut fields = Vec::new();
+
+    if trig.velocity() != expected.velocity() {
+        fields.push(format!("v={}", trig.velocity()));
+    }
+    if trig.note() != expected.note() {
+        fields.push(format!("n={}", trig.note()));
+    }
+    if trig.note_length() != expected.note_length() {
+        fields.push(format!("l={}", <&str>::from(trig.note_length())));
+    }
+    if trig.trig_condition() != expected.trig_condition() {
+        fields.push(format!("c={}", <&str>::from(trig.trig_condition())));
+    }
+    if trig.micro_timing() != expected.micro_timing() {
+        fields.push(format!("m={}", trig.micro_timing_value()));
+    }
+    if trig.retrig_rate() != expected.retrig_rate() {
+        fields.push(format!("rr={}", <&str>::from(trig.retrig_rate())));
+    }
+    if trig.retrig_length() != expected.retrig_length() {
+        fields.push(format!("rl={}", <&str>::from(trig.retrig_length())));
+    }
+    if trig.retrig_velocity_offset() != expected.retrig_velocity_offset() {
+        fields.push(format!("rv={}", trig.retrig_velocity_offset()));
+    }
+    if trig.sound_lock() != expected.sound_lock() {
+        fields.push(format!("s={}", trig.sound_lock()));
+    }
+
+    let changed_flags = trig.get_difference_from(&expected);
+    for (name, flag) in FLAGS {
+        if changed_flags & flag != 0 {
+            fields.push(format!(
+                "{name}={}",
+                u8::from(trig.raw_trig_flags() & flag != 0)
+            ));
+        }
+    }
+
+    fields
+}
+
+/// Renders the annotation fields of a step, empty if there is nothing to annotate.
+///
+/// `synth_plocks` are the raw parameter lock values of the pattern.
+fn render_fields(
+    trig: &Trig,
+    default: &Trig,
+    plock_trig: &Trig,
+    synth_plocks: &[(u8, usize, u8, RawPlockValue)],
+    is_fx: bool,
+) -> Vec<String> {
+    let mut fields = render_trig_fields(trig, default, is_fx);
+
+    if !is_fx {
+        for number in 1..=8 {
+            let plock_type = SYNTH_PLOCK_TYPE + number - 1;
+            let value = synth_plocks
+                .iter()
+                .find(|(track_index, trig_index, locked_type, _)| {
+                    usize::from(*track_index) == trig.track_index()
+                        && *trig_index == trig.index()
+                        && *locked_type == plock_type
+                });
+            match value {
+                Some((.., RawPlockValue::Basic(value))) => {
+                    fields.push(format!("#{number}={value}"));
+                }
+                Some((.., RawPlockValue::Compound(value))) => {
+                    fields.push(format!("#{number}=0x{value:04X}"));
+                }
+                None => {}
+            }
+        }
+    }
+
+    for parameter in PlockParameter::ALL
+        .into_iter()
+        .filter(|parameter| parameter.is_fx() == is_fx)
+    {
+        // Trigs of a pattern are never orphan.
+        if let Some(value) = plock_trig.plock_get_parameter(parameter).ok().flatten() {
+            fields.push(format!("{}={value}", <&str>::from(parameter)));
+        }
+    }
+
+    fields
+}
+
+/// Renders a row, FX parameter locks are read through the trigs of `plock_track`.
+fn render_row(
+    name: &str,
+    track: &Track,
+    plock_track: &Track,
+    synth_plocks: &[(u8, usize, u8, RawPlockValue)],
+    is_fx: bool,
+) -> String {
+    let number_of_steps = track.number_of_steps as usize;
+    let defaults = default_trig_array(track.index);
+    let steps = track.trigs[..number_of_steps]
+        .iter()
+        .map(|trig| StepKind::from_trig(trig, is_fx).as_char())
+        .collect::<String>();
+
+    let annotations = track.trigs[..number_of_steps]
+        .iter()
+        .filter_map(|trig| {
+            let fields = render_fields(
+                trig,
+                &defaults[trig.index()],
+                &plock_track.trigs[trig.index()],
+                synth_plocks,
+                is_fx,
+            );
+            (!fields.is_empty()).then(|| format!("{}:{}", trig.index(), fields.join(",")))
+        })
+        .collect::<Vec<_>>();
+
+    if annotations.is_empty() {
+        format!("{name} {steps}")
+    } else {
+        format!("{name} {steps} | {}", annotations.join(" "))
+    }
+}
+
+impl Pattern {
+    /// Renders the tracks of this pattern in the grid notation.
+    ///
+    /// Every track is a row of a name from [`TRACK_NAMES`] followed by its steps, the number of steps of the track decides the length of the row.
+    ///
+    /// - `.` is an empty step.
+    /// - `x` is a trig.
+    /// - `X` is an accented trig.
+    /// - `-` is a lock trig.
+    ///
+    /// Steps can be annotated after a `|` with `step:key=value,key=value` where step starts from 0.
+    /// A step which is not annotated is a default trig of its kind.
+    ///
+    /// - `v` is the velocity, `1..=127` on the device.
+    /// - `n` is the note, `36..=84` on the device, trigs without it get note `60`.
+    /// - `l` is the note length, e.g. `1/16`, `inf` or `unset`.
+    /// - `c` is the trig condition, e.g. `fill`, `1:2` or `50%`.
+    /// - `m` is the micro timing, `-23..=23`.
+    /// - `rr` is the retrig rate, e.g. `1/16`.
+    /// - `rl` is the retrig length, e.g. `1/4` or `inf`.
+    /// - `rv` is the retrig velocity offset, `-128..=127`.
+    /// - `s` is the sound lock, `0..=127`.
+    /// - Trig flags with `0` or `1`, `enable`, `retrig`, `mute`, `accent`, `swing`, `slide`
+    ///   and the parameter lock switches `lfo_pl_sw`, `syn_pl_sw`, `smp_pl_sw`, `env_pl_sw`, `lfo_pl_en`, `syn_pl_en`, `smp_pl_en`, `env_pl_en`.
+    ///   Only flags which differ from the flags of the step character are rendered.
+    /// - `#1` to `#8` are the synth parameter locks of the machine with their raw value,
+    ///   a byte in decimal or the two bytes of a compound lock in hexadecimal, e.g. `#1=64` or `#2=0x4000`.
+    ///   The pattern does not know the machines of the tracks so these are not scaled.
+    /// - Parameter lock names of [`PlockParameter`] with values in their numeric representation, e.g. `filter_cutoff=50`.
+    ///
+    /// Velocities and notes which are out of the range of the device are written as they are so values read from the device are kept.
+    ///
+    /// The FX track is the last row, its steps are `.` or `x` and only FX parameter locks are written there.
+    ///
+    /// ```text
+    /// BD x...x...x...x...
+    /// SD ....X.......x... | 4:v=110,c=fill,filter_cutoff=40 12:m=-3,#1=80
+    /// FX x............... | 0:fx_delay_time=64
+    /// ```
+    ///
+    /// Only the steps in the number of steps of a track are rendered,
+    /// apart from that every value of the trigs and their parameter locks are kept by [`Pattern::apply_grid_notation`].
+    pub fn to_grid_notation(&self) -> String {
+        let synth_plocks = self
+            .parameter_lock_pool
+            .lock()
+            .values()
+            .into_iter()
+            .filter(|&(_, _, plock_type, _)| {
+                (SYNTH_PLOCK_TYPE..SYNTH_PLOCK_TYPE + 8).contains(&plock_type)
+            })
+            .collect::<Vec<_>>();
+
+        let mut rows = self
+            .tracks
+            .iter()
+            .zip(TRACK_NAMES)
+            .map(|(track, name)| render_row(name, track, track, &synth_plocks, false))
+            .collect::<Vec<_>>();
+
+        // FX parameter locks can be read through any track, the first one is used.
+        rows.push(render_row(
+            TRACK_NAMES[12],
+            &self.fx_track.lock(),
+            &self.tracks[0],
+            &synth_plocks,
+            true,
+        ));
+
+        rows.join("\n") + "\n"
+    }
+
+    /// Makes a new pattern from the grid notation.
+    ///
+    /// Tracks which do not have a row are left as default.
+    ///
+    /// Check [`Pattern::to_grid_notation`] for the format.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RytmError::GridNotation`] pointing to the line and column of the first error in the notation.
+    ///
+    /// Returns an error if the parameter locks do not fit in the parameter lock memory.
+    pub fn try_from_grid_notation(index: usize, notation: &str) -> Result<Self, RytmError> {
+        let mut pattern = Self::try_default(index)?;
+        pattern.apply_grid_notation(notation)?;
+        Ok(pattern)
+    }
+
+    /// Replaces the tracks which have a row in the grid notation.
+    ///
+    /// A replaced track loses all of its trigs and parameter locks before the row is written, tracks without a row are left untouched.
+    ///
+    /// Check [`Pattern::to_grid_notation`] for the format.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RytmError::GridNotation`] pointing to the line and column of the first error in the notation.
+    ///
+    /// Returns an error if the parameter locks do not fit in the parameter lock memory.
+    ///
+    /// In both cases the pattern is not modified.
+    pub fn apply_grid_notation(&mut self, notation: &str) -> Result<(), RytmError> {
+        let rows = parse_rows(notation)?;
+
+        let backup = self.backup();
+
+        if let Err(err) = rows.iter().try_for_each(|row| self.write_row(row)) {
+            self.restore(&backup);
+            return Err(err);
+        }
+
+        Ok(())
+    }
+
+    /// Replaces a track with a parsed row.
+    fn write_row(&mut self, row: &Row) -> Result<(), RytmError> {
+        self.clear_all_plocks_for_track(row.track_index as u8)?;
+
+        if row.track_index == 12 {
+            // Locks the FX track on its own, it must not be held here.
+            // The trigs are written afterwards since locking enables them.
+            for (i, step) in row.steps.iter().enumerate() {
+                for &(parameter, value) in &step.plocks {
+                    self.tracks[0].trigs[i].plock_set_parameter(parameter, value)?;
+                }
+            }
+
+            let mut fx_track = self.fx_track.lock();
+            fx_track.set_number_of_steps(row.steps.len())?;
+            for trig in &mut fx_track.trigs {
+                write_step(trig, row.steps.get(trig.index()))?;
+            }
+            drop(fx_track);
+
+            return Ok(());
+        }
+
+        let track = &mut self.tracks[row.track_index];
+        track.set_number_of_steps(row.steps.len())?;
+
+        for trig in &mut track.trigs {
+            write_step(trig, row.steps.get(trig.index()))?;
+
+            let Some(step) = row.steps.get(trig.index()) else {
+                continue;
+            };
+            for &(plock_type, value) in &step.synth_plocks {
+                let mut pool = self.parameter_lock_pool.lock();
+                let result = match value {
+                    RawPlockValue::Basic(value) => {
+                        pool.set_basic_plock(trig.index(), row.track_index as u8, plock_type, value)
+                    }
+                    RawPlockValue::Compound(value) => pool.set_compound_plock(
+                        trig.index(),
+                        row.track_index as u8,
+                        plock_type,
+                        value,
+                    ),
+                };
+                drop(pool);
+                result?;
+            }
+            for &(parameter, value) in &step.plocks {
+                trig.plock_set_parameter(parameter, value)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Resets a trig to the default and writes a parsed step into it, steps past the end of a row stay default.
+fn write_step(trig: &mut Trig, step: Option<&Step>) -> Result<(), RytmError> {
+    let default = Trig::try_default(trig.index(), trig.track_index())?;
+    trig.copy_data_from(&default);
+
+    let Some(step) = step else {
+        return Ok(());
+    };
+    step.kind.apply(trig);
+
+    if let Some(velocity) = step.velocity {
+        trig.set_raw_velocity(velocity);
+    }
+    if let Some(note) = step.note {
+        trig.set_raw_note(note);
+    }
+    if let Some(note_length) = step.note_length {
+        trig.set_note_length(note_length);
+    }
+    if let Some(trig_condition) = step.trig_condition {
+        trig.set_trig_condition(trig_condition);
+    }
+    if let Some(micro_timing) = step.micro_timing {
+        trig.set_micro_timing_by_value(micro_timing)?;
+    }
+    if let Some(retrig_rate) = step.retrig_rate {
+        trig.set_retrig_rate(retrig_rate);
+    }
+    if let Some(retrig_length) = step.retrig_length {
+        trig.set_retrig_length(retrig_length);
+    }
+    if let Some(retrig_velocity_offset) = step.retrig_velocity_offset {
+        trig.set_retrig_velocity_offset(retrig_velocity_offset)?;
+    }
+    if let Some(sound_lock) = step.sound_lock {
+        trig.set_sound_lock(sound_lock)?;
+    }
+    for &(flag, enabled) in &step.flags {
+        if enabled {
+            *trig.raw_trig_flags_mut() |= flag;
+        } else {
+            *trig.raw_trig_flags_mut() &= !flag;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::object::sound::types::MachineType;
+
+    /// Returns the trigs and the parameter locks of a pattern in a comparable form.
+    fn contents(pattern: &Pattern) -> (serde_json::Value, Vec<(u8, usize, u8, RawPlockValue)>) {
+        let mut tracks = pattern.tracks.clone();
+        tracks.push(pattern.fx_track.lock().clone());
+        let mut plocks = pattern.parameter_lock_pool.lock().values();
+        plocks.sort_unstable_by_key(|&(track_index, trig_index, plock_type, _)| {
+            (track_index, trig_index, plock_type)
+        });
+        (serde_json::to_value(&tracks).unwrap(), plocks)
+    }
+
+    #[test]
+    fn rendered_notation_parses_back_to_the_same_pattern() {
+        let mut pattern = Pattern::try_default(0).unwrap();
+        {
+            let tracks = pattern.tracks_mut();
+            tracks[1].set_number_of_steps(12).unwrap();
+
+            let trig = &mut tracks[0].trigs_mut()[4];
+            trig.set_trig_enable(true);
+            trig.set_accent(true);
+            trig.set_velocity(110).unwrap();
+            trig.set_note(48).unwrap();
+            trig.set_note_length(Length::Infinite);
+            trig.set_trig_condition(TrigCondition::Fill);
+            trig.set_micro_timing_by_value(-3).unwrap();
+            trig.set_retrig(true);
+            trig.set_retrig_rate(RetrigRate::_1B8);
+            trig.set_retrig_length(Length::_32nd);
+            trig.set_retrig_velocity_offset(-20).unwrap();
+            trig.set_sound_lock(7).unwrap();
+            trig.set_slide(true);
+            trig.set_swing(true);
+            trig.plock_set_parameter(PlockParameter::FilterCutoff, 40.0)
+                .unwrap();
+            trig.plock_set_machine_parameter(MachineType::BdHard.parameter(1).unwrap(), 80.0)
+                .unwrap();
+            trig.plock_set_parameter(PlockParameter::FxDelayTime, 64.0)
+                .unwrap();
+
+            let trig = &mut tracks[1].trigs_mut()[11];
+            trig.set_lock_trig(true);
+            trig.set_mute(true);
+            trig.plock_set_parameter(PlockParameter::AmplitudePan, -10.0)
+                .unwrap();
+
+            // A disabled trig which still holds values.
+            tracks[2].trigs_mut()[3].set_note(72).unwrap();
+        }
+
+        let notation = pattern.to_grid_notation();
+        let parsed = Pattern::try_from_grid_notation(0, &notation).unwrap();
+
+        assert_eq!(parsed.to_grid_notation(), notation);
+        assert_eq!(contents(&parsed), contents(&pattern));
+    }
+
+    #[test]
+    fn compound_synth_locks_keep_their_raw_value() {
+        let notation = "BD x... | 0:#1=64,#2=0x4080\nFX ....\n";
+        let pattern = Pattern::try_from_grid_notation(0, notation).unwrap();
+
+        assert!(pattern.to_grid_notation().contains("0:#1=64,#2=0x4080"));
+    }
+
+    #[test]
+    fn errors_point_to_the_line_and_column() {
+        let at = |notation: &str| match Pattern::try_from_grid_notation(0, notation).err() {
+            Some(RytmError::GridNotation { line, column, .. }) => (line, column),
+            other => panic!("expected a grid notation error, got {other:?}"),
+        };
+
+        assert_eq!(at("XX x..."), (1, 1));
+        assert_eq!(at("BD x..?"), (1, 7));
+        assert_eq!(at("# comment\n\nBD x... | 0:v=300"), (3, 15));
+        assert_eq!(at("BD x... | 0:v=100,v=90"), (1, 19));
+        assert_eq!(at("BD x... | 0:filter_cutoff=40,fx_delay_time=3"), (1, 30));
+        assert_eq!(at("BD x... | 4:v=100"), (1, 11));
+        assert_eq!(at("BD x... | 0:#9=1"), (1, 13));
+        assert_eq!(at("BD x...\nSD x...\nbd x..."), (3, 1));
+    }
+
+    #[test]
+    fn failed_notation_leaves_the_pattern_unchanged() {
+        let mut pattern =
+            Pattern::try_from_grid_notation(0, "BD x.x. | 2:v=90,filter_cutoff=10\n").unwrap();
+        let before = contents(&pattern);
+
+        // The second row fails to parse.
+        let result = pattern.apply_grid_notation("BD xxxx\nSD x... | 0:n=200\n");
+        assert!(matches!(
+            result,
+            Err(RytmError::GridNotation { line: 2, .. })
+        ));
+        assert_eq!(contents(&pattern), before);
+
+        // Every row parses but the parameter locks do not fit.
+        let full = (0..12)
+            .map(|track_index| {
+                let annotations = (0..64)
+                    .map(|step| format!("{step}:#1=1,#2=2,#3=3,#4=4,#5=5,#6=6,#7=7"))
+                    .collect::<Vec<_>>()
+                    .join(" ");
+                format!(
+                    "{} {} | {annotations}",
+                    TRACK_NAMES[track_index],
+                    "x".repeat(64)
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        let result = pattern.apply_grid_notation(&full);
+        assert!(matches!(result, Err(RytmError::ParameterLockMemoryFull)));
+        assert_eq!(contents(&pattern), before);
+    }
+}
@@ -1,8 +1,137 @@
-use crate::{error::RytmError, util::stable_partition, RytmError::ParameterLockMemoryFull};
+use super::{budget::PlockSlot, track::trig::plock_impl::fx_plock_types::*};
+use crate::{
+    error::RytmError, util::stable_partition, RytmError::ParameterLockMemoryFull,
+    RytmError::ParameterLockMemoryFullWith,
+};
 use derivative::Derivative;
 use serde::{Deserialize, Serialize};
 use serde_big_array::BigArray;
 
+/// Track number byte of the slot which holds the least significant bytes of a compound plock.
+const ADJACENT_PLOCK_SLOT_TRACK_NUMBER_BYTE: u8 = 128;
+/// Plock type byte of the slot which holds the least significant bytes of a compound plock.
+const ADJACENT_PLOCK_SLOT_TYPE_BYTE: u8 = 128;
+
+/// The value of a parameter lock as it is stored in the parameter lock pool.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RawPlockValue {
+    /// A value which occupies a single slot.
+    Basic(u8),
+    /// A value which occupies a slot and its adjacent slot.
+    Compound(u16),
+}
+
+/// Returns a human readable description of a parameter lock, used in error messages.
+///
+/// The parameter names follow the naming of the plock setters.
+pub fn describe_plock(track_index: u8, plock_type: u8) -> String {
+    let owner = if track_index == 12 {
+        "fx track".to_string()
+    } else {
+        format!("track {track_index}")
+    };
+
+    plock_name(track_index, plock_type).map_or_else(
+        || format!("unknown plock type {plock_type} ({owner})"),
+        |name| format!("{name} ({owner})"),
+    )
+}
+
+/// Returns the name of a parameter lock type following the naming of the plock setters.
+///
+/// Returns `None` for unknown types.
+pub const fn plock_name(track_index: u8, plock_type: u8) -> Option<&'static str> {
+    let plock_type = plock_type as u32;
+
+    if track_index == 12 {
+        let name = match plock_type {
+            AR_FX_PLOCK_TYPE_DELAY_TIME => "fx_delay_time",
+            AR_FX_PLOCK_TYPE_DELAY_PING_PONG => "fx_delay_ping_pong",
+            AR_FX_PLOCK_TYPE_DELAY_WIDTH => "fx_delay_stereo_width",
+            AR_FX_PLOCK_TYPE_DELAY_FEEDBACK => "fx_delay_feedback",
+            AR_FX_PLOCK_TYPE_DELAY_HPF => "fx_delay_hpf",
+            AR_FX_PLOCK_TYPE_DELAY_LPF => "fx_delay_lpf",
+            AR_FX_PLOCK_TYPE_DELAY_REV => "fx_delay_reverb_send",
+            AR_FX_PLOCK_TYPE_DELAY_VOL => "fx_delay_volume",
+            AR_FX_PLOCK_TYPE_DIST_DOV => "fx_distortion_delay_overdrive",
+            AR_FX_PLOCK_TYPE_DIST_DELAY => "fx_distortion_delay_post",
+            AR_FX_PLOCK_TYPE_REVERB_PRE => "fx_reverb_pre_delay",
+            AR_FX_PLOCK_TYPE_REVERB_DECAY => "fx_reverb_decay",
+            AR_FX_PLOCK_TYPE_REVERB_FREQ => "fx_reverb_freq",
+            AR_FX_PLOCK_TYPE_REVERB_GAIN => "fx_reverb_gain",
+            AR_FX_PLOCK_TYPE_REVERB_HPF => "fx_reverb_hpf",
+            AR_FX_PLOCK_TYPE_REVERB_LPF => "fx_reverb_lpf",
+            AR_FX_PLOCK_TYPE_REVERB_VOL => "fx_reverb_volume",
+            AR_FX_PLOCK_TYPE_DIST_REV => "fx_distortion_reverb_post",
+            AR_FX_PLOCK_TYPE_DIST_AMOUNT => "fx_distortion_amount",
+            AR_FX_PLOCK_TYPE_DIST_SYM => "fx_distortion_symmetry",
+            AR_FX_PLOCK_TYPE_COMP_THRESHOLD => "fx_compressor_threshold",
+            AR_FX_PLOCK_TYPE_COMP_ATTACK => "fx_compressor_attack",
+            AR_FX_PLOCK_TYPE_COMP_RELEASE => "fx_compressor_release",
+            AR_FX_PLOCK_TYPE_COMP_RATIO => "fx_compressor_ratio",
+            AR_FX_PLOCK_TYPE_COMP_SEQ => "fx_compressor_side_chain_eq",
+            AR_FX_PLOCK_TYPE_COMP_MAKEUP => "fx_compressor_gain",
+            AR_FX_PLOCK_TYPE_COMP_MIX => "fx_compressor_mix",
+            AR_FX_PLOCK_TYPE_COMP_VOL => "fx_compressor_volume",
+            AR_FX_PLOCK_TYPE_LFO_SPEED => "fx_lfo_speed",
+            AR_FX_PLOCK_TYPE_LFO_MULTIPLY => "fx_lfo_multiplier",
+            AR_FX_PLOCK_TYPE_LFO_FADE => "fx_lfo_fade",
+            AR_FX_PLOCK_TYPE_LFO_DEST => "fx_lfo_destination",
+            AR_FX_PLOCK_TYPE_LFO_WAVEFORM => "fx_lfo_waveform",
+            AR_FX_PLOCK_TYPE_LFO_PHASE => "fx_lfo_start_phase",
+            AR_FX_PLOCK_TYPE_LFO_MOD => "fx_lfo_mode",
+            AR_FX_PLOCK_TYPE_LFO_DEPTH => "fx_lfo_depth",
+            _ => return None,
+        };
+        return Some(name);
+    }
+
+    let name = match plock_type {
+        rytm_sys::AR_PLOCK_TYPE_MP0 => "machine_parameter_1",
+        rytm_sys::AR_PLOCK_TYPE_MP1 => "machine_parameter_2",
+        rytm_sys::AR_PLOCK_TYPE_MP2 => "machine_parameter_3",
+        rytm_sys::AR_PLOCK_TYPE_MP3 => "machine_parameter_4",
+        rytm_sys::AR_PLOCK_TYPE_MP4 => "machine_parameter_5",
+        rytm_sys::AR_PLOCK_TYPE_MP5 => "machine_parameter_6",
+        rytm_sys::AR_PLOCK_TYPE_MP6 => "machine_parameter_7",
+        rytm_sys::AR_PLOCK_TYPE_MP7 => "machine_parameter_8",
+        rytm_sys::AR_PLOCK_TYPE_SMP_TUNE => "sample_tune",
+        rytm_sys::AR_PLOCK_TYPE_SMP_FINE => "sample_fine_tune",
+        rytm_sys::AR_PLOCK_TYPE_SMP_NR => "sample_number",
+        rytm_sys::AR_PLOCK_TYPE_SMP_BITRDC => "sample_bit_reduction",
+        rytm_sys::AR_PLOCK_TYPE_SMP_START => "sample_start",
+        rytm_sys::AR_PLOCK_TYPE_SMP_END => "sample_end",
+        rytm_sys::AR_PLOCK_TYPE_SMP_LOOPSW => "sample_loop_flag",
+        rytm_sys::AR_PLOCK_TYPE_SMP_LEVEL => "sample_volume",
+        rytm_sys::AR_PLOCK_TYPE_FLT_ATTACK => "filter_attack",
+        rytm_sys::AR_PLOCK_TYPE_FLT_SUSTAIN => "filter_sustain",
+        rytm_sys::AR_PLOCK_TYPE_FLT_DECAY => "filter_decay",
+        rytm_sys::AR_PLOCK_TYPE_FLT_RELEASE => "filter_release",
+        rytm_sys::AR_PLOCK_TYPE_FLT_FREQ => "filter_cutoff",
+        rytm_sys::AR_PLOCK_TYPE_FLT_RESO => "filter_resonance",
+        rytm_sys::AR_PLOCK_TYPE_FLT_TYPE => "filter_type",
+        rytm_sys::AR_PLOCK_TYPE_FLT_ENV => "filter_envelope_amount",
+        rytm_sys::AR_PLOCK_TYPE_AMP_ATTACK => "amplitude_attack",
+        rytm_sys::AR_PLOCK_TYPE_AMP_HOLD => "amplitude_hold",
+        rytm_sys::AR_PLOCK_TYPE_AMP_DECAY => "amplitude_decay",
+        rytm_sys::AR_PLOCK_TYPE_AMP_DRIVE => "amplitude_overdrive",
+        rytm_sys::AR_PLOCK_TYPE_AMP_DELAY => "amplitude_delay_send",
+        rytm_sys::AR_PLOCK_TYPE_AMP_REVERB => "amplitude_reverb_send",
+        rytm_sys::AR_PLOCK_TYPE_AMP_PAN => "amplitude_pan",
+        rytm_sys::AR_PLOCK_TYPE_AMP_VOLUME => "amplitude_volume",
+        rytm_sys::AR_PLOCK_TYPE_LFO_SPEED => "lfo_speed",
+        rytm_sys::AR_PLOCK_TYPE_LFO_MULTIPLY => "lfo_multiplier",
+        rytm_sys::AR_PLOCK_TYPE_LFO_FADE => "lfo_fade",
+        rytm_sys::AR_PLOCK_TYPE_LFO_DEST => "lfo_destination",
+        rytm_sys::AR_PLOCK_TYPE_LFO_WAVEFORM => "lfo_waveform",
+        rytm_sys::AR_PLOCK_TYPE_LFO_PHASE => "lfo_start_phase",
+        rytm_sys::AR_PLOCK_TYPE_LFO_TRIGMODE => "lfo_mode",
+        rytm_sys::AR_PLOCK_TYPE_LFO_DEPTH => "lfo_depth",
+        _ => return None,
+    };
+    Some(name)
+}
+
 #[derive(Derivative, Clone, Copy, Serialize, Deserialize)]
 #[derivative(Debug)]
 pub struct PlockSeq {
@@ -144,9 +273,6 @@ impl ParameterLockPool {
         plock_type: u8,
         value: u16,
     ) -> Result<(), RytmError> {
-        const ADJACENT_PLOCK_SLOT_TRACK_NUMBER_BYTE: u8 = 128;
-        const ADJACENT_PLOCK_SLOT_TYPE_BYTE: u8 = 128;
-
         let value_msb = (value >> 8) as u8;
         let value_lsb = value as u8;
 
@@ -213,6 +339,10 @@ impl ParameterLockPool {
         if let Some(plock) = self.inner.iter().find(|plock_seq| {
             plock_seq.track_nr == track_index && plock_seq.plock_type == plock_type
         }) {
+            // Other trigs may hold a value in this slot while this one is unset.
+            if plock.data[trig_index] == 0xFF {
+                return None;
+            }
             return Some(plock.data[trig_index]);
         }
         None
@@ -233,6 +363,10 @@ impl ParameterLockPool {
                 plock_seq.track_nr == track_index && plock_seq.plock_type == plock_type
             })
         {
+            // Other trigs may hold a value in these slots while this one is unset.
+            if plock.data[trig_index] == 0xFF {
+                return None;
+            }
             return Some(
                 ((plock.data[trig_index] as u16) << 8)
                     | (self.inner[i + 1].data[trig_index] as u16),
@@ -335,15 +469,282 @@ impl ParameterLockPool {
     }
 
     pub fn clear_all_plocks_for_track(&mut self, track_index: u8) {
-        for plock_seq in &mut self.inner {
-            if plock_seq.track_nr == track_index {
-                plock_seq.track_nr = 0xFF;
-                plock_seq.plock_type = 0xFF;
-                for byte in &mut plock_seq.data {
-                    // Like the default.
-                    *byte = 0xFF;
+        for i in 0..self.inner.len() {
+            if self.inner[i].track_nr == track_index {
+                // Compound plocks own the adjacent slot also, release it together.
+                if self.is_companion_slot(i + 1) {
+                    self.inner[i + 1] = PlockSeq::default();
                 }
+                // Like the default.
+                self.inner[i] = PlockSeq::default();
             }
         }
     }
+
+    /// Returns the number of slots which hold parameter locks of a track including the companion slots of compound parameter locks.
+    pub fn number_of_slots_used_by_track(&self, track_index: u8) -> usize {
+        (0..self.inner.len())
+            .filter(|&i| self.inner[i].track_nr == track_index && self.inner[i].plock_type != 0xFF)
+            .map(|i| 1 + usize::from(self.is_companion_slot(i + 1)))
+            .sum()
+    }
+
+    /// Checks if the slot at the given index holds the least significant bytes of a compound plock.
+    fn is_companion_slot(&self, slot_index: usize) -> bool {
+        self.inner.get(slot_index).is_some_and(|plock_seq| {
+            plock_seq.track_nr == ADJACENT_PLOCK_SLOT_TRACK_NUMBER_BYTE
+                && plock_seq.plock_type == ADJACENT_PLOCK_SLOT_TYPE_BYTE
+        })
+    }
+
+    /// Copies all the parameter locks of a track in the source pool to a track in this pool.
+    ///
+    /// Parameter locks which the destination track had before are replaced.
+    ///
+    /// The pool is only modified if every parameter lock fits.
+    /// Otherwise [`RytmError::ParameterLockMemoryFullWith`] is returned listing the parameter locks which did not fit.
+    pub fn copy_plocks_of_track_from(
+        &mut self,
+        source: &Self,
+        source_track_index: u8,
+        destination_track_index: u8,
+    ) -> Result<(), RytmError> {
+        let mut staged = self.clone();
+        staged.clear_all_plocks_for_track(destination_track_index);
+
+        // Partition the pool preserving the order so empty slots are stacked at the end.
+        // This way every compound plock finds its adjacent slot.
+        stable_partition(&mut staged.inner[..], |plock_seq| {
+            plock_seq.track_nr != 0xFF || plock_seq.plock_type != 0xFF
+        });
+
+        let mut next_empty_slot = staged
+            .inner
+            .iter()
+            .position(|plock_seq| plock_seq.track_nr == 0xFF || plock_seq.plock_type == 0xFF)
+            .unwrap_or(staged.inner.len());
+
+        let mut did_not_fit = Vec::new();
+
+        for (i, plock_seq) in source.inner.iter().enumerate() {
+            if plock_seq.track_nr != source_track_index || plock_seq.plock_type == 0xFF {
+                continue;
+            }
+
+            let slots = if source.is_companion_slot(i + 1) {
+                &source.inner[i..=i + 1]
+            } else {
+                &source.inner[i..=i]
+            };
+
+            if next_empty_slot + slots.len() > staged.inner.len() {
+                did_not_fit.push(describe_plock(
+                    destination_track_index,
+                    plock_seq.plock_type,
+                ));
+                continue;
+            }
+
+            for (offset, slot) in slots.iter().enumerate() {
+                staged.inner[next_empty_slot + offset] = *slot;
+            }
+            staged.inner[next_empty_slot].track_nr = destination_track_index;
+            next_empty_slot += slots.len();
+        }
+
+        if !did_not_fit.is_empty() {
+            return Err(ParameterLockMemoryFullWith { did_not_fit });
+        }
+
+        self.inner = staged.inner;
+        Ok(())
+    }
+
+    /// Clears all the parameter locks of a trig.
+    pub fn clear_plocks_of_trig(&mut self, trig_index: usize, track_index: u8) {
+        let plocks = (0..self.inner.len())
+            .filter(|&i| {
+                self.inner[i].track_nr == track_index
+                    && self.inner[i].plock_type != 0xFF
+                    && self.inner[i].data[trig_index] != 0xFF
+            })
+            .map(|i| (self.inner[i].plock_type, self.is_companion_slot(i + 1)))
+            .collect::<Vec<_>>();
+
+        for (plock_type, is_compound) in plocks {
+            if is_compound {
+                self.clear_compound_plock(trig_index, track_index, plock_type);
+            } else {
+                self.clear_basic_plock(trig_index, track_index, plock_type);
+            }
+        }
+    }
+
+    /// Copies the parameter locks of a trig in the source pool to a trig of the same track in this pool.
+    ///
+    /// Parameter locks which the destination trig had before are replaced.
+    ///
+    /// The pool is only modified if every parameter lock fits.
+    /// Otherwise [`RytmError::ParameterLockMemoryFullWith`] is returned listing the parameter locks which did not fit.
+    pub fn copy_plocks_of_trig_from(
+        &mut self,
+        source: &Self,
+        source_trig_index: usize,
+        destination_trig_index: usize,
+        track_index: u8,
+    ) -> Result<(), RytmError> {
+        let mut staged = self.clone();
+        staged.clear_plocks_of_trig(destination_trig_index, track_index);
+
+        let mut did_not_fit = Vec::new();
+
+        for (i, plock_seq) in source.inner.iter().enumerate() {
+            if plock_seq.track_nr != track_index
+                || plock_seq.plock_type == 0xFF
+                || plock_seq.data[source_trig_index] == 0xFF
+            {
+                continue;
+            }
+
+            let result = if source.is_companion_slot(i + 1) {
+                let value = (u16::from(plock_seq.data[source_trig_index]) << 8)
+                    | u16::from(source.inner[i + 1].data[source_trig_index]);
+                staged.set_compound_plock(
+                    destination_trig_index,
+                    track_index,
+                    plock_seq.plock_type,
+                    value,
+                )
+            } else {
+                staged.set_basic_plock(
+                    destination_trig_index,
+                    track_index,
+                    plock_seq.plock_type,
+                    plock_seq.data[source_trig_index],
+                )
+            };
+
+            match result {
+                Ok(()) => {}
+                Err(ParameterLockMemoryFull) => {
+                    did_not_fit.push(describe_plock(track_index, plock_seq.plock_type));
+                }
+                Err(error) => return Err(error),
+            }
+        }
+
+        if !did_not_fit.is_empty() {
+            return Err(ParameterLockMemoryFullWith { did_not_fit });
+        }
+
+        self.inner = staged.inner;
+        Ok(())
+    }
+
+    /// Checks if the slot at the given index is not used by any parameter lock.
+    fn is_free_slot(&self, slot_index: usize) -> bool {
+        let plock_seq = &self.inner[slot_index];
+        plock_seq.track_nr == 0xFF || plock_seq.plock_type == 0xFF
+    }
+
+    /// Checks if the slot at the given index is owned by a parameter lock.
+    fn is_owned_slot(&self, slot_index: usize) -> bool {
+        !self.is_free_slot(slot_index) && !self.is_companion_slot(slot_index)
+    }
+
+    /// Returns every parameter lock value in the pool as the track index, the trig index, the plock type and the value.
+    ///
+    /// Compound values are combined with the value in their companion slot.
+    pub fn values(&self) -> Vec<(u8, usize, u8, RawPlockValue)> {
+        let mut values = Vec::new();
+        for i in (0..self.inner.len()).filter(|&i| self.is_owned_slot(i)) {
+            let plock_seq = &self.inner[i];
+            let is_compound = self.is_companion_slot(i + 1);
+            for (trig_index, &byte) in plock_seq.data.iter().enumerate() {
+                if byte == 0xFF {
+                    continue;
+                }
+                let value = if is_compound {
+                    RawPlockValue::Compound(
+                        (u16::from(byte) << 8) | u16::from(self.inner[i + 1].data[trig_index]),
+                    )
+                } else {
+                    RawPlockValue::Basic(byte)
+                };
+                values.push((plock_seq.track_nr, trig_index, plock_seq.plock_type, value));
+            }
+        }
+        values
+    }
+
+    /// Returns the number of slots which are not used by any parameter lock.
+    pub fn number_of_free_slots(&self) -> usize {
+        (0..self.inner.len())
+            .filter(|&i| self.is_free_slot(i))
+            .count()
+    }
+
+    /// Returns what each slot is used for.
+    pub fn slots(&self) -> Vec<PlockSlot> {
+        (0..self.inner.len())
+            .map(|i| {
+                let plock_seq = &self.inner[i];
+                if self.is_free_slot(i) {
+                    PlockSlot::Free
+                } else if self.is_companion_slot(i) {
+                    PlockSlot::Companion
+                } else {
+                    PlockSlot::Owned {
+                        track_index: plock_seq.track_nr as usize,
+                        plock_type: plock_seq.plock_type,
+                        parameter: plock_name(plock_seq.track_nr, plock_seq.plock_type)
+                            .map(ToString::to_string),
+                        number_of_values: plock_seq
+                            .data
+                            .iter()
+                            .filter(|&&byte| byte != 0xFF)
+                            .count(),
+                    }
+                }
+            })
+            .collect()
+    }
+
+    /// Returns the indexes of the slots which are owned by a parameter lock but hold no values.
+    ///
+    /// Companion slots of compound parameter locks are not listed, they are released together with their slot.
+    pub fn empty_slots(&self) -> Vec<usize> {
+        (0..self.inner.len())
+            .filter(|&i| {
+                self.is_owned_slot(i) && self.inner[i].data.iter().all(|&byte| byte == 0xFF)
+            })
+            .collect()
+    }
+
+    /// Releases the slots which are owned by a parameter lock but hold no values.
+    ///
+    /// Companion slots of released compound parameter locks and companion slots which do not follow a parameter lock are released also.
+    ///
+    /// Returns the number of released slots.
+    pub fn compact(&mut self) -> usize {
+        let mut released = 0;
+
+        for i in self.empty_slots() {
+            if self.is_companion_slot(i + 1) {
+                self.inner[i + 1] = PlockSeq::default();
+                released += 1;
+            }
+            self.inner[i] = PlockSeq::default();
+            released += 1;
+        }
+
+        for i in 0..self.inner.len() {
+            if self.is_companion_slot(i) && (i == 0 || !self.is_owned_slot(i - 1)) {
+                self.inner[i] = PlockSeq::default();
+                released += 1;
+            }
+        }
+
+        released
+    }
 }
@@ -0,0 +1,1016 @@
+// All casts in this file are intended or safe within the context of this library.
+//
+// One can change `allow` to `warn` to review them if necessary.
+#![allow(clippy::cast_possible_truncation, clippy::cast_precision_loss)]
+#![allow(clippy::enum_glob_use)]
+
+use super::{
+    track::trig::{
+        parameter::{PlockParameter, PlockValueKind},
+        HoldsTrigFlags, Trig,
+    },
+    types::TimeMode,
+    Pattern,
+};
+use crate::{
+    error::{ParameterError, RytmError},
+    object::{
+        global::{menu::Channels, types::MidiChannel},
+        kit::Kit,
+        sound::machine::MachineParameter,
+    },
+};
+use rytm_rs_macro::parameter_range;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// A captured note on message.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct NoteEvent {
+    /// Time of the event in seconds from the start of the pattern.
+    pub time: f64,
+    /// Midi note of the event.
+    ///
+    /// Range `0..=127`
+    pub note: usize,
+    /// Velocity of the event.
+    ///
+    /// Range `0..=127`
+    ///
+    /// Following the midi convention a note on with `0` velocity is a note off and it is ignored.
+    pub velocity: usize,
+}
+
+/// Maps midi notes to tracks.
+///
+/// Defaults to consecutive notes starting from `36` for the 12 tracks.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NoteMap {
+    tracks: BTreeMap<usize, usize>,
+}
+
+impl Default for NoteMap {
+    fn default() -> Self {
+        Self::try_consecutive(36).unwrap()
+    }
+}
+
+impl NoteMap {
+    /// Makes a new note map which does not map any note.
+    pub const fn empty() -> Self {
+        Self {
+            tracks: BTreeMap::new(),
+        }
+    }
+
+    /// Makes a new note map which maps 12 consecutive notes starting from the base note to the 12 tracks.
+    ///
+    /// Range `0..=116`
+    #[parameter_range(range = "base_note:0..=116")]
+    pub fn try_consecutive(base_note: usize) -> Result<Self, RytmError> {
+        Ok(Self {
+            tracks: (0..12)
+                .map(|track_index| (base_note + track_index, track_index))
+                .collect(),
+        })
+    }
+
+    /// Maps a note to a track.
+    ///
+    /// Range `0..=127` for the note.
+    /// Range `0..=11` for the track.
+    #[parameter_range(range = "note:0..=127", range = "track_index:0..=11")]
+    pub fn set_track(&mut self, note: usize, track_index: usize) -> Result<(), RytmError> {
+        self.tracks.insert(note, track_index);
+        Ok(())
+    }
+
+    /// Removes the mapping of a note.
+    pub fn clear_note(&mut self, note: usize) {
+        self.tracks.remove(&note);
+    }
+
+    /// Returns the track which a note is mapped to.
+    pub fn track_index(&self, note: usize) -> Option<usize> {
+        self.tracks.get(&note).copied()
+    }
+}
+
+/// A midi controller which sends parameter changes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum Controller {
+    /// A control change with its number.
+    ///
+    /// Range `0..=127`
+    ControlChange(usize),
+    /// A non registered parameter number combining its msb and lsb as `msb * 128 + lsb`.
+    ///
+    /// Range `0..=16383`
+    Nrpn(usize),
+}
+
+impl Controller {
+    /// Returns the largest value which the controller sends.
+    const fn max_value(self) -> usize {
+        match self {
+            Self::ControlChange(_) => 127,
+            Self::Nrpn(_) => 16383,
+        }
+    }
+
+    /// Checks if the controller number is in range.
+    const fn is_valid(self) -> bool {
+        match self {
+            Self::ControlChange(number) => number <= 127,
+            Self::Nrpn(number) => number <= 16383,
+        }
+    }
+}
+
+/// A captured parameter change from a midi controller.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ControllerEvent {
+    /// Time of the event in seconds from the start of the pattern.
+    pub time: f64,
+    /// Midi channel of the event.
+    ///
+    /// Range `0..=15`
+    pub channel: usize,
+    /// The controller which sent the event.
+    pub controller: Controller,
+    /// Value of the event.
+    ///
+    /// Range `0..=127` for control changes.
+    /// Range `0..=16383` for non registered parameter numbers.
+    pub value: usize,
+}
+
+/// A parameter of a track which a controller moves.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum MotionTarget {
+    /// A parameter which is locked through [`Trig::plock_set_parameter`].
+    Parameter(PlockParameter),
+    /// A synth parameter of the machine of the track with its number.
+    ///
+    /// Range `1..=8`
+    MachineParameter(usize),
+}
+
+/// Maps midi channels to tracks and controllers to the parameters they move.
+///
+/// Defaults to the channels `0..=11` for the 12 tracks and `12` for the FX track,
+/// together with the control change and non registered parameter numbers of the sound parameters in the midi implementation chart of the device.
+///
+/// FX parameters are not mapped by default.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ControllerMap {
+    tracks: BTreeMap<usize, usize>,
+    track_controllers: Vec<(Controller, MotionTarget)>,
+    fx_controllers: Vec<(Controller, PlockParameter)>,
+}
+
+impl Default for ControllerMap {
+    fn default() -> Self {
+        use MotionTarget::*;
+        use PlockParameter::*;
+
+        let mut map = Self::empty();
+        map.tracks = (0..=12).map(|channel| (channel, channel)).collect();
+
+        let pages: [(usize, usize, [MotionTarget; 8]); 5] = [
+            (
+                16,
+                0,
+                [
+                    MachineParameter(1),
+                    MachineParameter(2),
+                    MachineParameter(3),
+                    MachineParameter(4),
+                    MachineParameter(5),
+                    MachineParameter(6),
+                    MachineParameter(7),
+                    MachineParameter(8),
+                ],
+            ),
+            (
+                24,
+                8,
+                [
+                    Parameter(SampleTune),
+                    Parameter(SampleFineTune),
+                    Parameter(SampleNumber),
+                    Parameter(SampleBitReduction),
+                    Parameter(SampleStart),
+                    Parameter(SampleEnd),
+                    Parameter(SampleLoopFlag),
+                    Parameter(SampleVolume),
+                ],
+            ),
+            (
+                70,
+                16,
+                [
+                    Parameter(FilterAttack),
+                    Parameter(FilterSustain),
+                    Parameter(FilterDecay),
+                    Parameter(FilterRelease),
+                    Parameter(FilterCutoff),
+                    Parameter(FilterResonance),
+                    Parameter(FilterType),
+                    Parameter(FilterEnvelopeAmount),
+                ],
+            ),
+            (
+                78,
+                24,
+                [
+                    Parameter(AmplitudeAttack),
+                    Parameter(AmplitudeHold),
+                    Parameter(AmplitudeDecay),
+                    Parameter(AmplitudeOverdrive),
+                    Parameter(AmplitudeDelaySend),
+                    Parameter(AmplitudeReverbSend),
+                    Parameter(AmplitudePan),
+                    Parameter(AmplitudeVolume),
+                ],
+            ),
+            (
+                102,
+                32,
+                [
+                    Parameter(LfoSpeed),
+                    Parameter(LfoMultiplier),
+                    Parameter(LfoFade),
+                    Parameter(LfoDestination),
+                    Parameter(LfoWaveform),
+                    Parameter(LfoStartPhase),
+                    Parameter(LfoMode),
+                    Parameter(LfoDepth),
+                ],
+            ),
+        ];
+
+        for (first_control_change, first_nrpn_lsb, targets) in pages {
+            for (i, target) in targets.into_iter().enumerate() {
+                // Pan and volume of the amp page use the standard control changes.
+                let control_change = match target {
+                    Parameter(AmplitudePan) => 10,
+                    Parameter(AmplitudeVolume) => 7,
+                    _ => first_control_change + i,
+                };
+                map.track_controllers
+                    .push((Controller::ControlChange(control_change), target));
+                map.track_controllers
+                    .push((Controller::Nrpn(128 + first_nrpn_lsb + i), target));
+            }
+        }
+
+        map
+    }
+}
+
+impl ControllerMap {
+    /// Makes a new controller map which does not map any channel or controller.
+    pub const fn empty() -> Self {
+        Self {
+            tracks: BTreeMap::new(),
+            track_controllers: Vec::new(),
+            fx_controllers: Vec::new(),
+        }
+    }
+
+    /// Makes the default controller map with the track channels of the midi settings.
+    ///
+    /// Tracks which are set to auto or off are not mapped.
+    pub fn from_channels(channels: &Channels) -> Self {
+        let mut map = Self {
+            tracks: BTreeMap::new(),
+            ..Self::default()
+        };
+        let track_channels = channels
+            .track_channels()
+            .iter()
+            .copied()
+            .chain(std::iter::once(channels.track_fx_channel()));
+        for (track_index, channel) in track_channels.enumerate() {
+            if let MidiChannel::Channel(channel) = channel {
+                map.tracks.insert(channel, track_index);
+            }
+        }
+        map
+    }
+
+    /// Maps a midi channel to a track.
+    ///
+    /// Range `0..=15` for the channel.
+    /// Range `0..=12` for the track, `12` is the FX track.
+    #[parameter_range(range = "channel:0..=15", range = "track_index:0..=12")]
+    pub fn set_track(&mut self, channel: usize, track_index: usize) -> Result<(), RytmError> {
+        self.tracks.insert(channel, track_index);
+        Ok(())
+    }
+
+    /// Removes the mapping of a midi channel.
+    pub fn clear_channel(&mut self, channel: usize) {
+        self.tracks.remove(&channel);
+    }
+
+    /// Returns the track which a midi channel is mapped to.
+    pub fn track_index(&self, channel: usize) -> Option<usize> {
+        self.tracks.get(&channel).copied()
+    }
+
+    /// Maps a controller to a parameter of the tracks.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the controller number is out of range, the target is an FX parameter or a synth parameter number out of range.
+    pub fn set_track_controller(
+        &mut self,
+        controller: Controller,
+        target: MotionTarget,
+    ) -> Result<(), RytmError> {
+        check_controller(controller)?;
+        match target {
+            MotionTarget::Parameter(parameter) if parameter.is_fx() => {
+                return Err(ParameterError::Compatibility {
+                    value: format!("{parameter:?}"),
+                    parameter_name: "target".into(),
+                    reason: Some("FX parameters are mapped with set_fx_controller.".into()),
+                }
+                .into());
+            }
+            MotionTarget::MachineParameter(number) if !(1..=8).contains(&number) => {
+                return Err(ParameterError::Range {
+                    value: number.to_string(),
+                    parameter_name: "target".into(),
+                }
+                .into());
+            }
+            _ => {}
+        }
+        self.track_controllers.retain(|(c, _)| *c != controller);
+        self.track_controllers.push((controller, target));
+        Ok(())
+    }
+
+    /// Maps a controller to a parameter of the FX track.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the controller number is out of range or the parameter is not an FX parameter.
+    pub fn set_fx_controller(
+        &mut self,
+        controller: Controller,
+        parameter: PlockParameter,
+    ) -> Result<(), RytmError> {
+        check_controller(controller)?;
+        if !parameter.is_fx() {
+            return Err(ParameterError::Compatibility {
+                value: format!("{parameter:?}"),
+                parameter_name: "parameter".into(),
+                reason: Some("Only FX parameters can be mapped for the FX track.".into()),
+            }
+            .into());
+        }
+        self.fx_controllers.retain(|(c, _)| *c != controller);
+        self.fx_controllers.push((controller, parameter));
+        Ok(())
+    }
+
+    /// Removes the mapping of a controller for the tracks and the FX track.
+    pub fn clear_controller(&mut self, controller: Controller) {
+        self.track_controllers.retain(|(c, _)| *c != controller);
+        self.fx_controllers.retain(|(c, _)| *c != controller);
+    }
+
+    /// Returns the parameter which a controller moves on a track.
+    ///
+    /// Range `0..=12` for the track, `12` is the FX track.
+    pub fn target(&self, track_index: usize, controller: Controller) -> Option<MotionTarget> {
+        if track_index == 12 {
+            self.fx_controllers
+                .iter()
+                .find(|(c, _)| *c == controller)
+                .map(|&(_, parameter)| MotionTarget::Parameter(parameter))
+        } else {
+            self.track_controllers
+                .iter()
+                .find(|(c, _)| *c == controller)
+                .map(|&(_, target)| target)
+        }
+    }
+}
+
+/// Returns an error if the number of a controller is out of range.
+fn check_controller(controller: Controller) -> Result<(), RytmError> {
+    if controller.is_valid() {
+        return Ok(());
+    }
+    let (Controller::ControlChange(number) | Controller::Nrpn(number)) = controller;
+    Err(ParameterError::Range {
+        value: number.to_string(),
+        parameter_name: "controller".into(),
+    }
+    .into())
+}
+
+/// Converts the value of a controller to the numeric value of a parameter.
+///
+/// Continuous parameters spread the range of the controller over their range.
+/// Choices and toggles take the value as it is, using the msb of non registered parameter numbers.
+fn controller_value(
+    controller: Controller,
+    value: usize,
+    kind: PlockValueKind,
+    min: f32,
+    max: f32,
+) -> f32 {
+    let coarse = match controller {
+        Controller::ControlChange(_) => value,
+        Controller::Nrpn(_) => value >> 7,
+    };
+    match kind {
+        PlockValueKind::Choice => coarse as f32,
+        PlockValueKind::Toggle => {
+            if coarse == 0 {
+                0.0
+            } else {
+                1.0
+            }
+        }
+        PlockValueKind::Integer | PlockValueKind::Float => {
+            (max - min).mul_add(value as f32 / controller.max_value() as f32, min)
+        }
+    }
+}
+
+/// A resolved parameter which a recorded change is locked for.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum MotionLock {
+    Parameter(PlockParameter),
+    MachineParameter(MachineParameter),
+}
+
+/// How recorded notes are written to a pattern.
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+pub enum RecordMode {
+    /// Adds the recorded trigs to the existing ones, a recorded trig replaces the trig on its step.
+    #[default]
+    Overdub,
+    /// Clears the trigs and the parameter locks of the tracks which receive notes before writing the recorded trigs.
+    ///
+    /// For parameter changes only the locks of the recorded parameters are cleared on the tracks which receive them.
+    Replace,
+}
+
+/// Settings of a live recording.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LiveRecording {
+    bpm: f32,
+    mode: RecordMode,
+    note_map: NoteMap,
+    controller_map: ControllerMap,
+    quantize_amount: Option<usize>,
+}
+
+impl LiveRecording {
+    /// Makes new settings for a recording played at the given tempo.
+    ///
+    /// Defaults to [`RecordMode::Overdub`], the default [`NoteMap`], the default [`ControllerMap`] and the quantize amounts of the tracks.
+    ///
+    /// Range `30.0..=300.0`
+    #[parameter_range(range = "bpm:30.0..=300.0")]
+    pub fn try_new(bpm: f32) -> Result<Self, RytmError> {
+        Ok(Self {
+            bpm,
+            mode: RecordMode::default(),
+            note_map: NoteMap::default(),
+            controller_map: ControllerMap::default(),
+            quantize_amount: None,
+        })
+    }
+
+    /// Sets the record mode.
+    pub const fn set_mode(&mut self, mode: RecordMode) {
+        self.mode = mode;
+    }
+
+    /// Sets the note map.
+    pub fn set_note_map(&mut self, note_map: NoteMap) {
+        self.note_map = note_map;
+    }
+
+    /// Sets the controller map.
+    pub fn set_controller_map(&mut self, controller_map: ControllerMap) {
+        self.controller_map = controller_map;
+    }
+
+    /// Sets a quantize amount which is used instead of the quantize amounts of the tracks.
+    ///
+    /// Range `0..=127`
+    ///
+    /// - `0` keeps the timing as played.
+    /// - `127` moves every trig to the grid.
+    #[parameter_range(range = "quantize_amount:0..=127")]
+    pub fn set_quantize_amount(&mut self, quantize_amount: usize) -> Result<(), RytmError> {
+        self.quantize_amount = Some(quantize_amount);
+        Ok(())
+    }
+
+    /// Uses the quantize amounts of the tracks again.
+    pub const fn clear_quantize_amount(&mut self) {
+        self.quantize_amount = None;
+    }
+
+    /// Returns the tempo of the recording.
+    pub const fn bpm(&self) -> f32 {
+        self.bpm
+    }
+
+    /// Returns the record mode.
+    pub const fn mode(&self) -> RecordMode {
+        self.mode
+    }
+
+    /// Returns the note map.
+    pub const fn note_map(&self) -> &NoteMap {
+        &self.note_map
+    }
+
+    /// Returns the controller map.
+    pub const fn controller_map(&self) -> &ControllerMap {
+        &self.controller_map
+    }
+
+    /// Returns the quantize amount which is used instead of the quantize amounts of the tracks.
+    pub const fn quantize_amount(&self) -> Option<usize> {
+        self.quantize_amount
+    }
+}
+
+/// The outcome of a live recording.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecordingReport {
+    /// Number of trigs written.
+    pub recorded: usize,
+    /// Indexes of the events which were ignored.
+    ///
+    /// Notes which are note offs or not mapped to a track.
+    /// Parameter changes which are not mapped to a track or a parameter, or which target a synth parameter the machine of the track does not use or a choice which does not exist.
+    pub ignored: Vec<usize>,
+}
+
+impl Pattern {
+    /// Returns the position of a time in seconds in the steps of a track, wrapped to the number of steps of the track.
+    ///
+    /// A step is a sixteenth note at `1x` speed.
+    /// Tracks follow the pattern speed unless the time mode is advanced.
+    ///
+    /// Range `0..=12` for the track, `12` is the FX track.
+    pub(crate) fn step_position(&self, track_index: usize, bpm: f32, time: f64) -> f64 {
+        let (track_speed, number_of_steps) = if track_index == 12 {
+            let fx_track = self.fx_track.lock();
+            (fx_track.speed, fx_track.number_of_steps)
+        } else {
+            let track = &self.tracks[track_index];
+            (track.speed, track.number_of_steps)
+        };
+        let speed = if self.time_mode == TimeMode::Advanced {
+            track_speed
+        } else {
+            self.speed
+        };
+        let steps_per_second = f64::from(bpm) / 60.0 * 4.0 * f64::from(speed.factor());
+        (time * steps_per_second).rem_euclid(f64::from(number_of_steps))
+    }
+
+    /// Writes captured note on events as trigs.
+    ///
+    /// Notes are mapped to tracks with the [`NoteMap`] and placed on the nearest step of the track, wrapping around the number of steps of the track.
+    /// The distance to the step is scaled down by the quantize amount and kept as micro timing.
+    /// Trigs play with the velocity of the event and their notes are left as they are.
+    ///
+    /// When more than one event lands on the same step, the last one wins.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the time of an event is negative or not finite, or its note or velocity is out of range.
+    /// In that case the pattern is not modified.
+    pub fn record_notes(
+        &mut self,
+        events: &[NoteEvent],
+        recording: &LiveRecording,
+    ) -> Result<RecordingReport, RytmError> {
+        let mut report = RecordingReport::default();
+        let mut recorded = Vec::new();
+
+        for (i, event) in events.iter().enumerate() {
+            if !event.time.is_finite() || event.time < 0.0 {
+                return Err(ParameterError::Range {
+                    value: event.time.to_string(),
+                    parameter_name: format!("time of event {i}"),
+                }
+                .into());
+            }
+            if event.note > 127 {
+                return Err(ParameterError::Range {
+                    value: event.note.to_string(),
+                    parameter_name: format!("note of event {i}"),
+                }
+                .into());
+            }
+            if event.velocity > 127 {
+                return Err(ParameterError::Range {
+                    value: event.velocity.to_string(),
+                    parameter_name: format!("velocity of event {i}"),
+                }
+                .into());
+            }
+
+            let track_index = recording.note_map.track_index(event.note);
+            let (Some(track_index), true) = (track_index, event.velocity > 0) else {
+                report.ignored.push(i);
+                continue;
+            };
+
+            let track = &self.tracks[track_index];
+            let quantize_amount = recording
+                .quantize_amount
+                .unwrap_or(track.quantize_amount as usize)
+                .min(127);
+
+            let position = self.step_position(track_index, recording.bpm, event.time);
+            let step = position.round();
+            // A step is 24 micro timing units of `1/384`.
+            let micro_timing = ((position - step) * 24.0 * (1.0 - quantize_amount as f64 / 127.0))
+                .round() as isize;
+            let step = step as usize % track.number_of_steps as usize;
+
+            recorded.push((
+                track_index,
+                step,
+                micro_timing.clamp(-23, 23),
+                event.velocity,
+            ));
+        }
+
+        if recording.mode == RecordMode::Replace {
+            let track_indexes = recorded
+                .iter()
+                .map(|&(track_index, ..)| track_index)
+                .collect::<BTreeSet<_>>();
+            for track_index in track_indexes {
+                self.clear_all_plocks_for_track(track_index as u8)?;
+                for trig in &mut self.tracks[track_index].trigs {
+                    let default = Trig::try_default(trig.index(), trig.track_index())?;
+                    trig.copy_data_from(&default);
+                }
+            }
+        }
+
+        for &(track_index, step, micro_timing, velocity) in &recorded {
+            let trig = &mut self.tracks[track_index].trigs[step];
+            if trig.enabled_lock_trig() {
+                trig.set_lock_trig(false);
+            }
+            trig.set_trig_enable(true);
+            trig.set_velocity(velocity)?;
+            trig.set_micro_timing_by_value(micro_timing)?;
+        }
+
+        report.recorded = recorded.len();
+        Ok(report)
+    }
+
+    /// Writes captured parameter changes as parameter locks.
+    ///
+    /// Channels are mapped to tracks and controllers to parameters with the [`ControllerMap`] of the recording.
+    /// Like the live recording of the device a change is locked on the step under the playhead, wrapping around the number of steps of the track.
+    /// Steps without a trig get a lock trig, FX parameters are locked on the FX track.
+    ///
+    /// When more than one change of the same parameter lands on the same step, the last one wins.
+    ///
+    /// The kit is used to find the machines of the tracks which receive synth parameter changes.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the time of an event is negative or not finite, its channel, controller or value is out of range,
+    /// a synth parameter change is mapped without a kit or the parameter locks do not fit in the parameter lock memory.
+    /// In that case the pattern is not modified.
+    pub fn record_motion(
+        &mut self,
+        events: &[ControllerEvent],
+        recording: &LiveRecording,
+        kit: Option<&Kit>,
+    ) -> Result<RecordingReport, RytmError> {
+        let mut report = RecordingReport::default();
+        let mut recorded = Vec::new();
+
+        for (i, event) in events.iter().enumerate() {
+            if !event.time.is_finite() || event.time < 0.0 {
+                return Err(ParameterError::Range {
+                    value: event.time.to_string(),
+                    parameter_name: format!("time of event {i}"),
+                }
+                .into());
+            }
+            if event.channel > 15 {
+                return Err(ParameterError::Range {
+                    value: event.channel.to_string(),
+                    parameter_name: format!("channel of event {i}"),
+                }
+                .into());
+            }
+            check_controller(event.controller)?;
+            if event.value > event.controller.max_value() {
+                return Err(ParameterError::Range {
+                    value: event.value.to_string(),
+                    parameter_name: format!("value of event {i}"),
+                }
+                .into());
+            }
+
+            let map = &recording.controller_map;
+            let Some((track_index, target)) =
+                map.track_index(event.channel).and_then(|track_index| {
+                    Some((track_index, map.target(track_index, event.controller)?))
+                })
+            else {
+                report.ignored.push(i);
+                continue;
+            };
+
+            let lock = match target {
+                MotionTarget::Parameter(parameter) => {
+                    let range = parameter.range();
+                    let value = controller_value(
+                        event.controller,
+                        event.value,
+                        parameter.kind(),
+                        *range.start(),
+                        *range.end(),
+                    );
+                    parameter
+                        .accepts(value)
+                        .then_some((MotionLock::Parameter(parameter), value))
+                }
+                MotionTarget::MachineParameter(number) => {
+                    let Some(kit) = kit else {
+                        return Err(ParameterError::Compatibility {
+                            value: format!("event {i}"),
+                            parameter_name: "kit".into(),
+                            reason: Some(
+                                "Synth parameter changes can not be recorded without the kit of the pattern.".into(),
+                            ),
+                        }
+                        .into());
+                    };
+                    kit.sounds()[track_index]
+                        .machine_type()
+                        .parameter(number)
+                        .and_then(|parameter| {
+                            let range = parameter.range();
+                            let value = controller_value(
+                                event.controller,
+                                event.value,
+                                parameter.kind(),
+                                *range.start(),
+                                *range.end(),
+                            );
+                            parameter
+                                .accepts(value)
+                                .then_some((MotionLock::MachineParameter(parameter), value))
+                        })
+                }
+            };
+            let Some((lock, value)) = lock else {
+                report.ignored.push(i);
+                continue;
+            };
+
+            let step = self
+                .step_position(track_index, recording.bpm, event.time)
+                .floor() as usize;
+            recorded.push((track_index, step, lock, value));
+        }
+
+        let backup = self.backup();
+        if let Err(err) = self.write_motion(&recorded, recording.mode) {
+            self.restore(&backup);
+            return Err(err);
+        }
+
+        report.recorded = recorded.len();
+        Ok(report)
+    }
+
+    /// Writes recorded parameter changes as `(track index, step, lock, value)`.
+    fn write_motion(
+        &mut self,
+        recorded: &[(usize, usize, MotionLock, f32)],
+        mode: RecordMode,
+    ) -> Result<(), RytmError> {
+        if mode == RecordMode::Replace {
+            let mut locks = Vec::new();
+            for &(track_index, _, lock, _) in recorded {
+                if !locks.contains(&(track_index, lock)) {
+                    locks.push((track_index, lock));
+                }
+            }
+            for (track_index, lock) in locks {
+                // FX parameters are cleared for the FX track through any track.
+                for trig in &self.tracks[track_index % 12].trigs {
+                    match lock {
+                        MotionLock::Parameter(parameter) => {
+                            trig.plock_clear_parameter(parameter)?;
+                        }
+                        MotionLock::MachineParameter(parameter) => {
+                            trig.plock_clear_machine_parameter(parameter)?;
+                        }
+                    }
+                }
+            }
+        }
+
+        for &(track_index, step, lock, value) in recorded {
+            // FX parameters are locked for the FX track through any track.
+            let trig = &mut self.tracks[track_index % 12].trigs[step];
+            match lock {
+                MotionLock::Parameter(parameter) => trig.plock_set_parameter(parameter, value)?,
+                MotionLock::MachineParameter(parameter) => {
+                    trig.plock_set_machine_parameter(parameter, value)?;
+                }
+            }
+            if track_index != 12 && !trig.enabled_trig() && !trig.enabled_lock_trig() {
+                trig.set_lock_trig(true);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Steps last `1/8` of a second at `120` bpm and `1x` speed.
+    const BPM: f32 = 120.0;
+
+    /// Returns a note event for track `0` at a position in steps.
+    fn note_at(position: f64) -> NoteEvent {
+        NoteEvent {
+            time: position / 8.0,
+            note: 36,
+            velocity: 100,
+        }
+    }
+
+    #[test]
+    fn quantize_amount_scales_the_micro_timing() {
+        let events = [note_at(4.25), note_at(8.75)];
+        let mut recording = LiveRecording::try_new(BPM).unwrap();
+
+        recording.set_quantize_amount(0).unwrap();
+        let mut pattern = Pattern::try_default(0).unwrap();
+        pattern.record_notes(&events, &recording).unwrap();
+        let trigs = pattern.tracks()[0].trigs();
+        assert!(trigs[4].enabled_trig());
+        assert_eq!(trigs[4].micro_timing_value(), 6);
+        assert!(trigs[9].enabled_trig());
+        assert_eq!(trigs[9].micro_timing_value(), -6);
+        assert_eq!(trigs[9].velocity(), 100);
+
+        recording.set_quantize_amount(127).unwrap();
+        let mut pattern = Pattern::try_default(0).unwrap();
+        pattern.record_notes(&events, &recording).unwrap();
+        let trigs = pattern.tracks()[0].trigs();
+        assert_eq!(trigs[4].micro_timing_value(), 0);
+        assert_eq!(trigs[9].micro_timing_value(), 0);
+    }
+
+    /// Returns a pattern with a trig locking the filter cutoff on step `0` of the first two tracks.
+    fn pattern_with_locked_trigs() -> Pattern {
+        let mut pattern = Pattern::try_default(0).unwrap();
+        for track in &mut pattern.tracks_mut()[..2] {
+            let trig = &mut track.trigs_mut()[0];
+            trig.set_trig_enable(true);
+            trig.plock_set_parameter(PlockParameter::FilterCutoff, 40.0)
+                .unwrap();
+        }
+        pattern
+    }
+
+    #[test]
+    fn replace_clears_the_receiving_tracks_and_overdub_keeps_them() {
+        let events = [note_at(4.0)];
+        let mut recording = LiveRecording::try_new(BPM).unwrap();
+
+        let mut overdub = pattern_with_locked_trigs();
+        let report = overdub.record_notes(&events, &recording).unwrap();
+        assert_eq!(report.recorded, 1);
+        let trigs = overdub.tracks()[0].trigs();
+        assert!(trigs[0].enabled_trig());
+        assert!(trigs[0]
+            .plock_get_parameter(PlockParameter::FilterCutoff)
+            .unwrap()
+            .is_some());
+        assert!(trigs[4].enabled_trig());
+
+        recording.set_mode(RecordMode::Replace);
+        let mut pattern = pattern_with_locked_trigs();
+        pattern.record_notes(&events, &recording).unwrap();
+        let trigs = pattern.tracks()[0].trigs();
+        assert!(!trigs[0].enabled_trig());
+        assert_eq!(
+            trigs[0]
+                .plock_get_parameter(PlockParameter::FilterCutoff)
+                .unwrap(),
+            None
+        );
+        assert!(trigs[4].enabled_trig());
+        // Tracks which do not receive notes are kept.
+        assert!(pattern.tracks()[1].trigs()[0].enabled_trig());
+    }
+
+    #[test]
+    fn note_offs_and_unmapped_notes_are_ignored() {
+        let events = [
+            NoteEvent {
+                velocity: 0,
+                ..note_at(0.0)
+            },
+            NoteEvent {
+                note: 20,
+                ..note_at(1.0)
+            },
+            note_at(2.0),
+        ];
+        let recording = LiveRecording::try_new(BPM).unwrap();
+        let mut pattern = Pattern::try_default(0).unwrap();
+
+        let report = pattern.record_notes(&events, &recording).unwrap();
+
+        assert_eq!(report.recorded, 1);
+        assert_eq!(report.ignored, vec![0, 1]);
+    }
+
+    #[test]
+    fn control_changes_lock_a_synth_parameter_of_the_machine() {
+        let kit = Kit::try_default(0).unwrap();
+        let parameter = kit.sounds()[0].machine_type().parameter(1).unwrap();
+        // The first synth parameter is on control change 16 of channel 0.
+        let events = [ControllerEvent {
+            time: 0.5,
+            channel: 0,
+            controller: Controller::ControlChange(16),
+            value: 127,
+        }];
+        let recording = LiveRecording::try_new(BPM).unwrap();
+        let mut pattern = Pattern::try_default(0).unwrap();
+
+        assert!(pattern.record_motion(&events, &recording, None).is_err());
+        let report = pattern
+            .record_motion(&events, &recording, Some(&kit))
+            .unwrap();
+
+        assert_eq!(report.recorded, 1);
+        let trig = &pattern.tracks()[0].trigs()[4];
+        assert!(trig.enabled_lock_trig());
+        assert_eq!(
+            trig.plock_get_machine_parameter(parameter).unwrap(),
+            Some(*parameter.range().end())
+        );
+    }
+
+    #[test]
+    fn control_changes_of_the_fx_channel_lock_an_fx_parameter() {
+        let mut controller_map = ControllerMap::default();
+        controller_map
+            .set_fx_controller(Controller::ControlChange(20), PlockParameter::FxDelayTime)
+            .unwrap();
+        let mut recording = LiveRecording::try_new(BPM).unwrap();
+        recording.set_controller_map(controller_map);
+        let events = [ControllerEvent {
+            time: 0.25,
+            channel: 12,
+            controller: Controller::ControlChange(20),
+            value: 0,
+        }];
+        let mut pattern = Pattern::try_default(0).unwrap();
+
+        pattern.record_motion(&events, &recording, None).unwrap();
+
+        let trig = &pattern.tracks()[0].trigs()[2];
+        assert_eq!(
+            trig.plock_get_parameter(PlockParameter::FxDelayTime)
+                .unwrap(),
+            Some(*PlockParameter::FxDelayTime.range().start())
+        );
+        // FX parameters do not place a lock trig on the track they are locked through.
+        assert!(!trig.enabled_lock_trig());
+    }
+}
@@ -0,0 +1,265 @@
+use super::{
+    track::trig::{
+        parameter::PlockParameter,
+        types::{Length, TrigCondition},
+        HoldsTrigFlags, Trig,
+    },
+    Pattern,
+};
+use crate::error::{ParameterError, RytmError};
+use rytm_rs_macro::parameter_range;
+use serde::{Deserialize, Serialize};
+
+/// A selection of steps across the tracks of a pattern.
+///
+/// Selects the same steps in every selected track and can be narrowed down by trig flags.
+/// Use the constants in [`flags`](super::track::trig::flags) to filter by flags.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct StepSelection {
+    tracks: u16,
+    steps: u64,
+    required_flags: u16,
+    excluded_flags: u16,
+}
+
+impl StepSelection {
+    /// Makes a new selection of a step range in the given tracks.
+    ///
+    /// Range `0..=11` for the tracks.
+    /// Range `0..=63` for the steps, the end step is inclusive.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a track or a step is out of range or the step range is reversed.
+    #[parameter_range(range = "start_step:0..=63", range = "end_step:0..=63")]
+    pub fn try_new(
+        track_indexes: &[usize],
+        start_step: usize,
+        end_step: usize,
+    ) -> Result<Self, RytmError> {
+        if start_step > end_step {
+            return Err(ParameterError::Range {
+                value: format!("{start_step}..={end_step}"),
+                parameter_name: "steps".into(),
+            }
+            .into());
+        }
+
+        let steps = (start_step..=end_step).fold(0, |mask, step| mask | 1 << step);
+        Self::try_new_with_step_mask(track_indexes, steps)
+    }
+
+    /// Makes a new selection of the steps in a mask in the given tracks.
+    ///
+    /// Bit `n` of the mask selects step `n`.
+    ///
+    /// Range `0..=11` for the tracks.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a track is out of range.
+    pub fn try_new_with_step_mask(
+        track_indexes: &[usize],
+        step_mask: u64,
+    ) -> Result<Self, RytmError> {
+        let mut tracks = 0;
+        for &track_index in track_indexes {
+            if track_index > 11 {
+                return Err(ParameterError::Range {
+                    value: track_index.to_string(),
+                    parameter_name: "track_index".into(),
+                }
+                .into());
+            }
+            tracks |= 1 << track_index;
+        }
+
+        Ok(Self {
+            tracks,
+            steps: step_mask,
+            required_flags: 0,
+            excluded_flags: 0,
+        })
+    }
+
+    /// Narrows the selection down to the trigs which have all of the given flags set.
+    #[must_use]
+    pub const fn with_required_flags(mut self, flags: u16) -> Self {
+        self.required_flags |= flags;
+        self
+    }
+
+    /// Narrows the selection down to the trigs which have none of the given flags set.
+    #[must_use]
+    pub const fn with_excluded_flags(mut self, flags: u16) -> Self {
+        self.excluded_flags |= flags;
+        self
+    }
+
+    /// Returns the indexes of the selected tracks.
+    pub fn track_indexes(&self) -> Vec<usize> {
+        (0..12).filter(|i| self.tracks & 1 << i != 0).collect()
+    }
+
+    /// Returns the mask of the selected steps.
+    pub const fn step_mask(&self) -> u64 {
+        self.steps
+    }
+
+    /// Checks if the trig is in this selection.
+    pub fn contains(&self, trig: &Trig) -> bool {
+        let flags = trig.raw_trig_flags();
+        trig.track_index() < 12
+            && self.tracks & 1 << trig.track_index() != 0
+            && self.steps & 1 << trig.index() != 0
+            && flags & self.required_flags == self.required_flags
+            && flags & self.excluded_flags == 0
+    }
+}
+
+impl Pattern {
+    /// Returns the trigs in the selection ordered by track and step.
+    pub fn selected_trigs(&self, selection: &StepSelection) -> Vec<&Trig> {
+        self.tracks
+            .iter()
+            .flat_map(|track| track.trigs.iter())
+            .filter(|trig| selection.contains(trig))
+            .collect()
+    }
+
+    /// Applies a closure to every trig in the selection.
+    ///
+    /// Membership is decided before any edit, changing flags in the closure does not change the selection.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first error returned from the closure.
+    /// In that case none of the trigs and parameter locks are modified.
+    pub fn edit_selection(
+        &mut self,
+        selection: &StepSelection,
+        mut edit: impl FnMut(&mut Trig) -> Result<(), RytmError>,
+    ) -> Result<(), RytmError> {
+        let mut edited = self
+            .selected_trigs(selection)
+            .into_iter()
+            .cloned()
+            .collect::<Vec<_>>();
+
+        let pool_backup = self.parameter_lock_pool.lock().clone();
+        let fx_trigs_backup = self.fx_track.lock().trigs.clone();
+
+        if let Err(err) = edited.iter_mut().try_for_each(&mut edit) {
+            *self.parameter_lock_pool.lock() = pool_backup;
+            for (trig, backup) in self.fx_track.lock().trigs.iter_mut().zip(&fx_trigs_backup) {
+                trig.copy_data_from(backup);
+            }
+            return Err(err);
+        }
+
+        for trig in &edited {
+            self.tracks[trig.track_index()].trigs[trig.index()].copy_data_from(trig);
+        }
+
+        Ok(())
+    }
+
+    /// Sets the note of every trig in the selection.
+    ///
+    /// Range `36..=84`
+    #[parameter_range(range = "note:36..=84")]
+    pub fn selection_set_note(
+        &mut self,
+        selection: &StepSelection,
+        note: usize,
+    ) -> Result<(), RytmError> {
+        self.edit_selection(selection, |trig| trig.set_note(note))
+    }
+
+    /// Sets the velocity of every trig in the selection.
+    ///
+    /// Range `1..=127`
+    #[parameter_range(range = "velocity:1..=127")]
+    pub fn selection_set_velocity(
+        &mut self,
+        selection: &StepSelection,
+        velocity: usize,
+    ) -> Result<(), RytmError> {
+        self.edit_selection(selection, |trig| trig.set_velocity(velocity))
+    }
+
+    /// Sets the note length of every trig in the selection.
+    pub fn selection_set_note_length(&mut self, selection: &StepSelection, note_length: Length) {
+        // Infallible, no need to roll back.
+        for track in &mut self.tracks {
+            for trig in &mut track.trigs {
+                if selection.contains(trig) {
+                    trig.set_note_length(note_length);
+                }
+            }
+        }
+    }
+
+    /// Sets the trig condition of every trig in the selection.
+    pub fn selection_set_trig_condition(
+        &mut self,
+        selection: &StepSelection,
+        trig_condition: TrigCondition,
+    ) {
+        // Infallible, no need to roll back.
+        for track in &mut self.tracks {
+            for trig in &mut track.trigs {
+                if selection.contains(trig) {
+                    trig.set_trig_condition(trig_condition);
+                }
+            }
+        }
+    }
+
+    /// Sets or unsets the given flags of every trig in the selection.
+    ///
+    /// Use the constants in [`flags`](super::track::trig::flags) to build the flags.
+    pub fn selection_set_flags(&mut self, selection: &StepSelection, flags: u16, enable: bool) {
+        for track in &mut self.tracks {
+            for trig in &mut track.trigs {
+                if !selection.contains(trig) {
+                    continue;
+                }
+                if enable {
+                    *trig.raw_trig_flags_mut() |= flags;
+                } else {
+                    *trig.raw_trig_flags_mut() &= !flags;
+                }
+            }
+        }
+    }
+
+    /// Sets a parameter lock for every trig in the selection.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the value is not accepted by the parameter or the parameter lock memory is full.
+    /// In that case none of the parameter locks are modified.
+    pub fn selection_plock_set(
+        &mut self,
+        selection: &StepSelection,
+        parameter: PlockParameter,
+        value: f32,
+    ) -> Result<(), RytmError> {
+        self.edit_selection(selection, |trig| trig.plock_set_parameter(parameter, value))
+    }
+
+    /// Clears a parameter lock for every trig in the selection.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if clearing the parameter lock of a trig fails.
+    /// In that case none of the parameter locks are modified.
+    pub fn selection_plock_clear(
+        &mut self,
+        selection: &StepSelection,
+        parameter: PlockParameter,
+    ) -> Result<(), RytmError> {
+        self.edit_selection(selection, |trig| trig.plock_clear_parameter(parameter))
+    }
+}
@@ -0,0 +1,206 @@
+// All casts in this file are intended or safe within the context of this library.
+//
+// One can change `allow` to `warn` to review them if necessary.
+#![allow(clippy::cast_precision_loss)]
+
+use super::{
+    track::{trig::HoldsTrigFlags, Track},
+    types::{Speed, TimeMode},
+    Pattern,
+};
+use serde::{Deserialize, Serialize};
+
+/// A pattern which is ranked by its rhythmic similarity to a pattern or a track.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SimilarityMatch {
+    /// Position of the pattern in the ranked patterns.
+    pub position: usize,
+    /// Index of the pattern.
+    pub pattern_index: usize,
+    /// The track of the pattern which matches the track best, only set when ranking by a track.
+    pub track_index: Option<usize>,
+    /// Similarity in the range `0.0..=1.0`, `1.0` meaning the same rhythm.
+    pub similarity: f32,
+}
+
+const fn gcd(a: usize, b: usize) -> usize {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+/// The onsets of a track placed in time.
+struct OnsetGrid {
+    /// Positions of the onsets in micro timing units.
+    onsets: Vec<usize>,
+    /// Length of a loop of the track in micro timing units.
+    length: usize,
+}
+
+impl OnsetGrid {
+    fn new(track: &Track, speed: Speed) -> Self {
+        let step_length = speed.step_length();
+        let number_of_steps = track.number_of_steps as usize;
+        Self {
+            onsets: track.trigs[..number_of_steps]
+                .iter()
+                .filter(|trig| trig.enabled_trig() && !trig.enabled_lock_trig())
+                .map(|trig| trig.index() * step_length)
+                .collect(),
+            length: number_of_steps * step_length,
+        }
+    }
+
+    /// Returns the number of differing slots and the number of slots where either grid plays,
+    /// comparing both grids looped over the least common multiple of their lengths.
+    fn compare(&self, other: &Self) -> (usize, usize) {
+        let divisor = gcd(self.length, other.length);
+        let span = self.length / divisor * other.length;
+        let own = self.onsets.len() * (span / self.length);
+        let others = other.onsets.len() * (span / other.length);
+
+        // Two onsets meet once in the span when their distance is a multiple of the greatest common divisor of the lengths.
+        let common = self
+            .onsets
+            .iter()
+            .map(|&a| {
+                other
+                    .onsets
+                    .iter()
+                    .filter(|&&b| a.abs_diff(b) % divisor == 0)
+                    .count()
+            })
+            .sum::<usize>();
+
+        (own + others - 2 * common, own + others - common)
+    }
+}
+
+/// Returns the speed which a track plays at.
+const fn track_speed(pattern: &Pattern, track: &Track) -> Speed {
+    match pattern.time_mode {
+        TimeMode::Advanced => track.speed,
+        TimeMode::Normal => pattern.speed,
+    }
+}
+
+/// Turns the number of differing slots and the number of slots where either grid plays into a similarity.
+fn similarity((difference, union): (usize, usize)) -> f32 {
+    if union == 0 {
+        1.0
+    } else {
+        1.0 - difference as f32 / union as f32
+    }
+}
+
+impl Track {
+    /// Returns the rhythmic similarity of this track to another track.
+    ///
+    /// Tracks are compared by the Hamming distance of their onsets, normalized by the number of steps which play in either track.
+    /// Each track is looped until both line up so a 16 step track matches the same 16 steps repeated in a 32 step track.
+    /// Speeds are taken into account so a 32 step track at `2x` matches the same rhythm in 16 steps at `1x`.
+    ///
+    /// Only trigs which play count, lock trigs, velocities and micro timings are ignored.
+    /// Tracks are compared at their own speed since a track does not know the time mode of its pattern,
+    /// use [`Pattern::rhythmic_similarity`] to compare tracks in patterns.
+    ///
+    /// Returns a value in the range `0.0..=1.0`, `1.0` meaning the same rhythm. Two silent tracks are the same.
+    pub fn rhythmic_similarity(&self, other: &Self) -> f32 {
+        similarity(OnsetGrid::new(self, self.speed).compare(&OnsetGrid::new(other, other.speed)))
+    }
+
+    /// Ranks patterns by how similar their most similar track is to this track, most similar first.
+    ///
+    /// Patterns can be the patterns of a project or the ones read from a `.syx` library with [`Pattern::try_many_from_sysex`].
+    pub fn rank_by_similarity<'a>(
+        &self,
+        patterns: impl IntoIterator<Item = &'a Pattern>,
+    ) -> Vec<SimilarityMatch> {
+        let grid = OnsetGrid::new(self, self.speed);
+        let mut matches = patterns
+            .into_iter()
+            .enumerate()
+            .map(|(position, pattern)| {
+                let (track_index, similarity) = pattern
+                    .tracks
+                    .iter()
+                    .map(|track| {
+                        let other = OnsetGrid::new(track, track_speed(pattern, track));
+                        similarity(grid.compare(&other))
+                    })
+                    .enumerate()
+                    .fold((0, f32::MIN), |best, current| {
+                        if current.1 > best.1 {
+                            current
+                        } else {
+                            best
+                        }
+                    });
+                SimilarityMatch {
+                    position,
+                    pattern_index: pattern.index,
+                    track_index: Some(track_index),
+                    similarity,
+                }
+            })
+            .collect::<Vec<_>>();
+        sort(&mut matches);
+        matches
+    }
+}
+
+impl Pattern {
+    /// Returns the rhythmic similarity of this pattern to another pattern.
+    ///
+    /// Tracks are compared one to one like [`Track::rhythmic_similarity`] at the speeds they play in their patterns.
+    /// The differences of all tracks are summed and normalized by the steps which play in either pattern,
+    /// so busier tracks weigh more and tracks which are silent in both patterns do not count.
+    ///
+    /// Returns a value in the range `0.0..=1.0`, `1.0` meaning the same rhythm. Two silent patterns are the same.
+    pub fn rhythmic_similarity(&self, other: &Self) -> f32 {
+        let (difference, union) = self
+            .tracks
+            .iter()
+            .zip(&other.tracks)
+            .map(|(track, other_track)| {
+                OnsetGrid::new(track, track_speed(self, track)).compare(&OnsetGrid::new(
+                    other_track,
+                    track_speed(other, other_track),
+                ))
+            })
+            .fold((0, 0), |(difference, union), (d, u)| {
+                (difference + d, union + u)
+            });
+        similarity((difference, union))
+    }
+
+    /// Ranks other patterns by their rhythmic similarity to this pattern, most similar first.
+    ///
+    /// Patterns can be the patterns of a project or the ones read from a `.syx` library with [`Pattern::try_many_from_sysex`].
+    /// This pattern is skipped if it is among them, duplicates of it rank first with a similarity of `1.0`.
+    pub fn rank_by_similarity<'a>(
+        &self,
+        patterns: impl IntoIterator<Item = &'a Self>,
+    ) -> Vec<SimilarityMatch> {
+        let mut matches = patterns
+            .into_iter()
+            .enumerate()
+            .filter(|(_, pattern)| !std::ptr::eq(*pattern, self))
+            .map(|(position, pattern)| SimilarityMatch {
+                position,
+                pattern_index: pattern.index,
+                track_index: None,
+                similarity: self.rhythmic_similarity(pattern),
+            })
+            .collect::<Vec<_>>();
+        sort(&mut matches);
+        matches
+    }
+}
+
+/// Sorts matches by similarity, most similar first, keeping the order of equally similar patterns.
+fn sort(matches: &mut [SimilarityMatch]) {
+    matches.sort_by(|a, b| b.similarity.total_cmp(&a.similarity));
+}
@@ -1,3 +1,11 @@
+/// Parameter lock automation curves for tracks.
+pub mod automation;
+/// Groove templates and humanization for tracks.
+pub mod groove;
+/// Scale aware note operations for tracks.
+pub mod scale;
+/// Conversion of tracks between speeds and numbers of steps.
+pub mod time_scale;
 /// Holds the structures to represent a trig in a track.
 pub mod trig;
 /// Types related to the track.
@@ -7,7 +15,10 @@ use self::{
     trig::{HoldsTrigFlags, TrigFlags},
     types::{PadScale, RootNote},
 };
-use super::{plock::ParameterLockPool, Length};
+use super::{
+    plock::{describe_plock, ParameterLockPool, RawPlockValue},
+    Length,
+};
 use crate::{
     defaults::default_trig_array,
     error::{ParameterError, RytmError},
@@ -280,6 +291,41 @@ impl Track {
             fx_track_ref,
         })
     }
+    /// Copies the trigs and the settings of the given track to this track.
+    ///
+    /// Indexes and the links to the owner pattern are kept, parameter locks are not copied.
+    pub(crate) fn copy_data_from(&mut self, track: &Self) {
+        for (trig, source_trig) in self.trigs.iter_mut().zip(track.trigs.iter()) {
+            trig.copy_data_from(source_trig);
+        }
+
+        self.default_trig_flags = track.default_trig_flags;
+        self.default_trig_note = track.default_trig_note;
+        self.default_trig_velocity = track.default_trig_velocity;
+        self.default_trig_note_length = track.default_trig_note_length;
+        self.default_trig_probability = track.default_trig_probability;
+
+        self.number_of_steps = track.number_of_steps;
+        self.quantize_amount = track.quantize_amount;
+        self.sends_midi = track.sends_midi;
+        self.speed = track.speed;
+
+        self.euclidean_mode = track.euclidean_mode;
+        self.euclidean_pl1 = track.euclidean_pl1;
+        self.euclidean_pl2 = track.euclidean_pl2;
+        self.euclidean_ro1 = track.euclidean_ro1;
+        self.euclidean_ro2 = track.euclidean_ro2;
+        self.euclidean_tro = track.euclidean_tro;
+
+        self.pad_scale = track.pad_scale;
+        self.root_note = track.root_note;
+
+        self.__maybe_useful_flag_from_default_trig_note =
+            track.__maybe_useful_flag_from_default_trig_note;
+        self.__maybe_useful_flags_from_flags_and_speed =
+            track.__maybe_useful_flags_from_flags_and_speed;
+    }
+
     /// Returns a mutable reference to the trigs in this track.
     ///
     /// 64 trigs in total.
@@ -440,6 +486,100 @@ impl Track {
         &self.trigs
     }
 
+    /// Places a trig into this track at the given step, replacing the trig there.
+    ///
+    /// The parameter locks of the placed trig are merged into the parameter lock pool of this track.
+    /// These are the pending parameter locks of a detached trig, see [`Trig::try_detached`],
+    /// or the parameter locks of a trig which belongs to another track or pattern.
+    ///
+    /// Parameter locks of the replaced trig are cleared.
+    /// FX parameter locks of the step are kept unless the placed trig locks the same FX parameter.
+    ///
+    /// Range `0..=63` for trig index.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RytmError::OrphanTrig`] if this track is not part of a pattern.
+    ///
+    /// Sound parameter locks can not be placed into the FX track.
+    ///
+    /// If the parameter locks do not fit [`RytmError::ParameterLockMemoryFullWith`] is returned listing the parameter locks which did not fit.
+    /// In that case this track and the parameter locks are left untouched.
+    #[parameter_range(range = "trig_index:0..=63")]
+    pub fn place_trig(&mut self, trig_index: usize, trig: &Trig) -> Result<(), RytmError> {
+        let pool = self
+            .parameter_lock_pool
+            .as_ref()
+            .ok_or(RytmError::OrphanTrig)?;
+
+        // The placed trig may share the pool with this track, read its values before staging.
+        let plocks = trig
+            .parameter_lock_pool
+            .as_ref()
+            .map(|source| source.lock().values())
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|&(track_index, source_trig_index, _, _)| {
+                source_trig_index == trig.index()
+                    && (usize::from(track_index) == trig.track_index() || track_index == 12)
+            })
+            .collect::<Vec<_>>();
+
+        let mut staged = pool.lock().clone();
+        staged.clear_plocks_of_trig(trig_index, self.index as u8);
+
+        let mut did_not_fit = Vec::new();
+        let mut has_fx_plocks = false;
+        for (track_index, _, plock_type, value) in plocks {
+            let destination_track_index = if track_index == 12 {
+                has_fx_plocks = true;
+                12
+            } else if self.index == 12 {
+                return Err(ParameterError::Compatibility {
+                    value: describe_plock(track_index, plock_type),
+                    parameter_name: "trig".into(),
+                    reason: Some(
+                        "Sound parameter locks can not be placed into the FX track.".into(),
+                    ),
+                }
+                .into());
+            } else {
+                self.index as u8
+            };
+
+            let result = match value {
+                RawPlockValue::Basic(value) => {
+                    staged.set_basic_plock(trig_index, destination_track_index, plock_type, value)
+                }
+                RawPlockValue::Compound(value) => staged.set_compound_plock(
+                    trig_index,
+                    destination_track_index,
+                    plock_type,
+                    value,
+                ),
+            };
+            match result {
+                Ok(()) => {}
+                Err(RytmError::ParameterLockMemoryFull) => {
+                    did_not_fit.push(describe_plock(destination_track_index, plock_type));
+                }
+                Err(error) => return Err(error),
+            }
+        }
+        if !did_not_fit.is_empty() {
+            return Err(RytmError::ParameterLockMemoryFullWith { did_not_fit });
+        }
+
+        *pool.lock() = staged;
+        let destination = &mut self.trigs[trig_index];
+        destination.copy_data_from(trig);
+        if has_fx_plocks {
+            destination.enable_fx_trig_if_necessary();
+        }
+
+        Ok(())
+    }
+
     /// Returns the default note for any trig in this track.
     ///
     /// Range `0..=127`
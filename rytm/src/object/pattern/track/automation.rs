@@ -0,0 +1,190 @@
+// All casts in this file are intended or safe within the context of this library.
+//
+// One can change `allow` to `warn` to review them if necessary.
+#![allow(clippy::cast_possible_truncation, clippy::cast_precision_loss)]
+
+use super::{
+    trig::{parameter::PlockParameter, HoldsTrigFlags},
+    Track,
+};
+use crate::{
+    error::{ParameterError, RytmError},
+    object::sound::types::LfoWaveform,
+    util::SeededRng,
+};
+use rytm_rs_macro::parameter_range;
+use serde::{Deserialize, Serialize};
+use std::f32::consts::TAU;
+
+/// The shape of an automation over a range of steps.
+///
+/// Values are in the numeric representation of the automated parameter, see [`PlockParameter::range`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum AutomationCurve {
+    /// Moves from `start` to `end` in a straight line.
+    Linear { start: f32, end: f32 },
+    /// Moves from `start` to `end` following a power curve.
+    ///
+    /// An exponent greater than `1.0` starts slow and ends fast, less than `1.0` starts fast and ends slow.
+    Exponential { start: f32, end: f32, exponent: f32 },
+    /// Oscillates around `center` with the shape of an LFO waveform.
+    ///
+    /// Bipolar waveforms swing between `center - depth` and `center + depth`.
+    /// [`LfoWaveform::Exp`] and [`LfoWaveform::Rmp`] are unipolar and move between `center` and `center + depth`.
+    Lfo {
+        waveform: LfoWaveform,
+        center: f32,
+        depth: f32,
+        /// Length of a cycle in steps.
+        period: f32,
+        /// Start phase of the cycle, `0.0..1.0`.
+        phase: f32,
+    },
+    /// Uses the values in order, repeating them if the range is longer.
+    Steps(Vec<f32>),
+}
+
+impl AutomationCurve {
+    /// Returns the value of the curve for the step at `position` of a range of `length` steps.
+    fn value_at(&self, position: usize, length: usize) -> f32 {
+        let progress = if length > 1 {
+            position as f32 / (length - 1) as f32
+        } else {
+            0.0
+        };
+
+        match self {
+            Self::Linear { start, end } => (end - start).mul_add(progress, *start),
+            Self::Exponential {
+                start,
+                end,
+                exponent,
+            } => (end - start).mul_add(progress.powf(*exponent), *start),
+            Self::Lfo {
+                waveform,
+                center,
+                depth,
+                period,
+                phase,
+            } => {
+                let cycles = position as f32 / period + phase;
+                let phase = cycles.rem_euclid(1.0);
+                let shape = match waveform {
+                    LfoWaveform::Tri => {
+                        if phase < 0.25 {
+                            4.0 * phase
+                        } else if phase < 0.75 {
+                            4.0f32.mul_add(-phase, 2.0)
+                        } else {
+                            4.0f32.mul_add(phase, -4.0)
+                        }
+                    }
+                    LfoWaveform::Sin => (TAU * phase).sin(),
+                    LfoWaveform::Sqr => {
+                        if phase < 0.5 {
+                            1.0
+                        } else {
+                            -1.0
+                        }
+                    }
+                    LfoWaveform::Saw => 2.0f32.mul_add(-phase, 1.0),
+                    LfoWaveform::Exp => (-5.0 * phase).exp(),
+                    LfoWaveform::Rmp => phase,
+                    LfoWaveform::Rnd => {
+                        // Holds a random value for each cycle, the same cycle always gets the same value.
+                        let mut rng = SeededRng::new(cycles.floor() as i64 as u64);
+                        rng.range_f32(-1.0, 1.0)
+                    }
+                };
+                depth.mul_add(shape, *center)
+            }
+            Self::Steps(values) => values[position % values.len()],
+        }
+    }
+}
+
+impl Track {
+    /// Writes parameter locks following a curve to the steps in the given range.
+    ///
+    /// Trigs in the range which are not enabled become lock trigs so they only apply the locks.
+    /// FX parameters are locked in the FX track so the trigs of this track are left as they are.
+    ///
+    /// Range `0..=63` for the steps, the end step is inclusive.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the range is reversed, the step list is empty, the LFO period is not positive or a value of the curve is not accepted by the parameter.
+    /// In that case none of the trigs are modified.
+    ///
+    /// Returns an error if the track is not part of a pattern or the parameter lock memory is full.
+    #[parameter_range(range = "start_step:0..=63", range = "end_step:0..=63")]
+    pub fn automate(
+        &mut self,
+        parameter: PlockParameter,
+        curve: &AutomationCurve,
+        start_step: usize,
+        end_step: usize,
+    ) -> Result<(), RytmError> {
+        if start_step > end_step {
+            return Err(ParameterError::Range {
+                value: format!("{start_step}..={end_step}"),
+                parameter_name: "steps".into(),
+            }
+            .into());
+        }
+
+        if matches!(curve, AutomationCurve::Steps(values) if values.is_empty()) {
+            return Err(ParameterError::Range {
+                value: "0".into(),
+                parameter_name: "number of values in the step list".into(),
+            }
+            .into());
+        }
+
+        if let AutomationCurve::Lfo { period, .. } = curve {
+            if *period <= 0.0 {
+                return Err(ParameterError::Range {
+                    value: period.to_string(),
+                    parameter_name: "period".into(),
+                }
+                .into());
+            }
+        }
+
+        let length = end_step - start_step + 1;
+        let values = (0..length)
+            .map(|position| {
+                let value = curve.value_at(position, length);
+                if parameter.accepts(value) {
+                    Ok(value)
+                } else {
+                    Err(ParameterError::Range {
+                        value: value.to_string(),
+                        parameter_name: format!(
+                            "{} of trig {}",
+                            <&str>::from(parameter),
+                            start_step + position
+                        ),
+                    }
+                    .into())
+                }
+            })
+            .collect::<Result<Vec<_>, RytmError>>()?;
+
+        // All locks of a parameter on a track share the same slots in the pool.
+        // If there is no room it fails on the first one before anything is written.
+        for (trig, value) in self.trigs[start_step..=end_step].iter().zip(values) {
+            trig.plock_set_parameter(parameter, value)?;
+        }
+
+        if !parameter.is_fx() {
+            for trig in &mut self.trigs[start_step..=end_step] {
+                if !trig.enabled_trig() {
+                    trig.set_lock_trig(true);
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,295 @@
+// All casts in this file are intended or safe within the context of this library.
+//
+// One can change `allow` to `warn` to review them if necessary.
+#![allow(
+    clippy::cast_lossless,
+    clippy::cast_possible_truncation,
+    clippy::cast_sign_loss,
+    clippy::cast_possible_wrap,
+    clippy::cast_precision_loss
+)]
+
+use super::{trig::HoldsTrigFlags, Track};
+use crate::{
+    error::{ParameterError, RytmError},
+    util::SeededRng,
+};
+use rytm_rs_macro::parameter_range;
+use serde::{Deserialize, Serialize};
+
+/// A single step of a [`Groove`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GrooveStep {
+    /// Micro timing offset of the step.
+    ///
+    /// Range `-23..=23`
+    pub micro_timing: isize,
+    /// Velocity accent of the step relative to the average velocity of the groove.
+    ///
+    /// Range `-126..=126`
+    pub velocity_offset: isize,
+}
+
+/// A groove template.
+///
+/// Holds micro timing offsets and velocity accents per step.
+///
+/// Steps without a trig are `None` and left untouched when the groove is applied.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Groove {
+    steps: Vec<Option<GrooveStep>>,
+}
+
+impl Groove {
+    /// Makes a new groove from the given steps.
+    ///
+    /// Range `1..=64` for the number of steps.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the number of steps or the values in a step are out of range.
+    pub fn try_new(steps: Vec<Option<GrooveStep>>) -> Result<Self, RytmError> {
+        if !(1..=64).contains(&steps.len()) {
+            return Err(ParameterError::Range {
+                value: steps.len().to_string(),
+                parameter_name: "steps".into(),
+            }
+            .into());
+        }
+
+        for step in steps.iter().flatten() {
+            if !(-23..=23).contains(&step.micro_timing) {
+                return Err(ParameterError::Range {
+                    value: step.micro_timing.to_string(),
+                    parameter_name: "micro_timing".into(),
+                }
+                .into());
+            }
+            if !(-126..=126).contains(&step.velocity_offset) {
+                return Err(ParameterError::Range {
+                    value: step.velocity_offset.to_string(),
+                    parameter_name: "velocity_offset".into(),
+                }
+                .into());
+            }
+        }
+
+        Ok(Self { steps })
+    }
+
+    /// Returns the steps of the groove.
+    pub fn steps(&self) -> &[Option<GrooveStep>] {
+        &self.steps
+    }
+}
+
+impl Track {
+    /// Returns the velocity which a trig in this track plays with.
+    ///
+    /// Trigs without a velocity of their own play with the default trig velocity of the track.
+    pub(crate) fn effective_velocity(&self, trig_index: usize) -> isize {
+        if self.has_own_velocity(trig_index) {
+            self.trigs[trig_index].velocity() as isize
+        } else {
+            (self.default_trig_velocity as isize).max(1)
+        }
+    }
+
+    /// Checks if a trig in this track has a velocity of its own.
+    fn has_own_velocity(&self, trig_index: usize) -> bool {
+        (1..=127).contains(&self.trigs[trig_index].velocity())
+    }
+
+    /// Extracts the groove of this track.
+    ///
+    /// The groove spans the number of steps of the track.
+    /// Enabled trigs contribute their micro timing and their velocity relative to the average velocity of the enabled trigs.
+    pub fn extract_groove(&self) -> Groove {
+        let steps = 0..self.number_of_steps as usize;
+
+        let velocities = steps
+            .clone()
+            .filter(|&i| self.trigs[i].enabled_trig())
+            .map(|i| self.effective_velocity(i))
+            .collect::<Vec<_>>();
+
+        let average_velocity = if velocities.is_empty() {
+            0
+        } else {
+            (velocities.iter().sum::<isize>() as f32 / velocities.len() as f32).round() as isize
+        };
+
+        Groove {
+            steps: steps
+                .map(|i| {
+                    let trig = &self.trigs[i];
+                    trig.enabled_trig().then(|| GrooveStep {
+                        micro_timing: trig.micro_timing_value(),
+                        velocity_offset: self.effective_velocity(i) - average_velocity,
+                    })
+                })
+                .collect(),
+        }
+    }
+
+    /// Applies a groove to the enabled trigs of this track.
+    ///
+    /// The groove repeats if it is shorter than the number of steps of the track.
+    ///
+    /// Strength range `0.0..=1.0`
+    ///
+    /// - `0.0` leaves the trigs untouched.
+    /// - `1.0` moves the micro timing of the trigs to the groove and applies the velocity accents fully.
+    ///
+    /// Velocities are clamped to `1..=127`.
+    /// Trigs without a velocity of their own keep playing with the default trig velocity of the track unless they are accented.
+    #[parameter_range(range = "strength:0.0..=1.0")]
+    pub fn apply_groove(&mut self, groove: &Groove, strength: f32) -> Result<(), RytmError> {
+        if groove.steps.is_empty() {
+            return Ok(());
+        }
+
+        for i in 0..self.number_of_steps as usize {
+            if !self.trigs[i].enabled_trig() {
+                continue;
+            }
+            let Some(step) = groove.steps[i % groove.steps.len()] else {
+                continue;
+            };
+
+            let micro_timing = self.trigs[i].micro_timing_value();
+            let micro_timing = micro_timing
+                + ((step.micro_timing - micro_timing) as f32 * strength).round() as isize;
+
+            let velocity_offset = (step.velocity_offset as f32 * strength).round() as isize;
+            let velocity = (self.effective_velocity(i) + velocity_offset).clamp(1, 127);
+            let write_velocity = velocity_offset != 0 || self.has_own_velocity(i);
+
+            let trig = &mut self.trigs[i];
+            trig.set_micro_timing_by_value(micro_timing)?;
+            if write_velocity {
+                trig.set_velocity(velocity as usize)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Humanizes the enabled trigs of this track with random micro timing and velocity offsets.
+    ///
+    /// The same seed always produces the same result.
+    ///
+    /// Micro timing amount range `0..=23`
+    ///
+    /// Velocity amount range `0..=126`
+    ///
+    /// Offsets are picked in `-amount..=amount` and the results are clamped to `-23..=23` for micro timing and `1..=127` for velocity.
+    /// Trigs without a velocity of their own keep playing with the default trig velocity of the track if their velocity offset is `0`.
+    #[parameter_range(
+        range = "micro_timing_amount:0..=23",
+        range = "velocity_amount:0..=126"
+    )]
+    pub fn humanize(
+        &mut self,
+        micro_timing_amount: usize,
+        velocity_amount: usize,
+        seed: u64,
+    ) -> Result<(), RytmError> {
+        let mut rng = SeededRng::new(seed);
+        let micro_timing_amount = micro_timing_amount as isize;
+        let velocity_amount = velocity_amount as isize;
+
+        for i in 0..self.number_of_steps as usize {
+            if !self.trigs[i].enabled_trig() {
+                continue;
+            }
+
+            let micro_timing = (self.trigs[i].micro_timing_value()
+                + rng.range_isize(-micro_timing_amount, micro_timing_amount))
+            .clamp(-23, 23);
+            let velocity_offset = rng.range_isize(-velocity_amount, velocity_amount);
+            let velocity = (self.effective_velocity(i) + velocity_offset).clamp(1, 127);
+            let write_velocity = velocity_offset != 0 || self.has_own_velocity(i);
+
+            let trig = &mut self.trigs[i];
+            trig.set_micro_timing_by_value(micro_timing)?;
+            if write_velocity {
+                trig.set_velocity(velocity as usize)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns a track with trigs on the beats where only the first trig has a velocity of its own.
+    fn track_with_beats() -> Track {
+        let mut track = flat_track_with_beats();
+        track.trigs[0].set_velocity(80).unwrap();
+        track
+    }
+
+    /// Returns a track with trigs on the beats which play with the default trig velocity.
+    fn flat_track_with_beats() -> Track {
+        let mut track = Track::try_default(0, 0, false, None).unwrap();
+        for trig in track.trigs.iter_mut().step_by(4).take(4) {
+            trig.set_trig_enable(true);
+        }
+        track
+    }
+
+    #[test]
+    fn groove_keeps_unset_velocities_without_an_accent() {
+        let groove = Groove::try_new(vec![Some(GrooveStep {
+            micro_timing: 5,
+            velocity_offset: 20,
+        })])
+        .unwrap();
+
+        let mut track = track_with_beats();
+        track.apply_groove(&groove, 0.0).unwrap();
+        assert_eq!(track.trigs[0].velocity(), 80);
+        assert_eq!(track.trigs[4].velocity(), 0xFF);
+        assert_eq!(track.trigs[4].micro_timing_value(), 0);
+
+        track.apply_groove(&groove, 1.0).unwrap();
+        assert_eq!(track.trigs[0].velocity(), 100);
+        assert_eq!(track.trigs[4].velocity(), 120);
+        assert_eq!(track.trigs[4].micro_timing_value(), 5);
+    }
+
+    #[test]
+    fn extracted_groove_carries_the_micro_timing_and_the_accents() {
+        let mut track = track_with_beats();
+        track.trigs[4].set_micro_timing_by_value(-7).unwrap();
+        let groove = track.extract_groove();
+
+        let mut flat = flat_track_with_beats();
+        flat.apply_groove(&groove, 1.0).unwrap();
+        for (a, b) in track.trigs.iter().zip(&flat.trigs) {
+            assert_eq!(a.micro_timing_value(), b.micro_timing_value());
+        }
+        assert_eq!(flat.effective_velocity(4) - flat.effective_velocity(0), 20);
+    }
+
+    #[test]
+    fn humanize_keeps_unset_velocities_without_a_velocity_amount() {
+        let mut track = track_with_beats();
+        track.humanize(10, 0, 7).unwrap();
+        assert_eq!(track.trigs[0].velocity(), 80);
+        assert_eq!(track.trigs[4].velocity(), 0xFF);
+
+        let mut a = track_with_beats();
+        let mut b = track_with_beats();
+        a.humanize(10, 20, 7).unwrap();
+        b.humanize(10, 20, 7).unwrap();
+        for (a, b) in a.trigs.iter().zip(&b.trigs) {
+            assert_eq!(a.micro_timing_value(), b.micro_timing_value());
+            assert_eq!(a.velocity(), b.velocity());
+        }
+    }
+}
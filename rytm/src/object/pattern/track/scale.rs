@@ -0,0 +1,246 @@
+// All casts in this file are intended or safe within the context of this library.
+//
+// One can change `allow` to `warn` to review them if necessary.
+#![allow(
+    clippy::cast_lossless,
+    clippy::cast_possible_truncation,
+    clippy::cast_sign_loss,
+    clippy::cast_possible_wrap
+)]
+
+use super::{trig::HoldsTrigFlags, Track};
+use crate::error::{ParameterError, RytmError};
+use rytm_rs_macro::parameter_range;
+use serde::{Deserialize, Serialize};
+
+/// The lowest note a trig accepts.
+const NOTE_MIN: isize = 36;
+/// The highest note a trig accepts.
+const NOTE_MAX: isize = 84;
+/// C-4, the octave which scale degrees are counted from.
+const NOTE_C4: isize = 60;
+
+/// Order of the notes in an arpeggio.
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+pub enum ArpeggioDirection {
+    /// From the lowest note to the highest.
+    #[default]
+    Up,
+    /// From the highest note to the lowest.
+    Down,
+    /// From the lowest note to the highest and back without repeating the ends.
+    UpDown,
+}
+
+fn note_out_of_range(note: isize, trig_index: Option<usize>) -> RytmError {
+    ParameterError::Range {
+        value: note.to_string(),
+        parameter_name: trig_index.map_or_else(
+            || "note".to_owned(),
+            |trig_index| format!("note of trig {trig_index}"),
+        ),
+    }
+    .into()
+}
+
+impl Track {
+    /// Returns the absolute scale degree of a note and whether the note is in the scale.
+    ///
+    /// Notes out of the scale return the degree of the closest scale note below them.
+    fn scale_degree_of_note(&self, note: isize) -> (isize, bool) {
+        let intervals = self.pad_scale.intervals();
+        let relative = note - u8::from(self.root_note) as isize;
+        let octave = relative.div_euclid(12);
+        let pitch_class = relative.rem_euclid(12) as u8;
+
+        // The first interval is always 0 so there is always a match.
+        let position = intervals
+            .iter()
+            .rposition(|&interval| interval <= pitch_class)
+            .unwrap_or(0);
+
+        (
+            octave * intervals.len() as isize + position as isize,
+            intervals[position] == pitch_class,
+        )
+    }
+
+    /// Returns the note of an absolute scale degree.
+    fn note_of_absolute_scale_degree(&self, degree: isize) -> isize {
+        let intervals = self.pad_scale.intervals();
+        let length = intervals.len() as isize;
+        u8::from(self.root_note) as isize
+            + degree.div_euclid(length) * 12
+            + intervals[degree.rem_euclid(length) as usize] as isize
+    }
+
+    /// Returns the note of a scale degree using the pad scale and the root note of this track.
+    ///
+    /// Degree `0` is the root note in the octave of C-4, negative degrees go down.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the note is out of the `36..=84` range.
+    pub fn note_of_scale_degree(&self, degree: isize) -> Result<usize, RytmError> {
+        let (root_degree, _) =
+            self.scale_degree_of_note(NOTE_C4 + u8::from(self.root_note) as isize);
+        let note = self.note_of_absolute_scale_degree(root_degree + degree);
+
+        if !(NOTE_MIN..=NOTE_MAX).contains(&note) {
+            return Err(note_out_of_range(note, None));
+        }
+
+        Ok(note as usize)
+    }
+
+    /// Checks if the note is in the pad scale of this track considering the root note.
+    pub fn is_note_in_scale(&self, note: usize) -> bool {
+        self.scale_degree_of_note(note as isize).1
+    }
+
+    /// Returns the notes of a chord built by stacking thirds of the scale on the given scale degree.
+    ///
+    /// Degree `0` is the root note in the octave of C-4, negative degrees go down.
+    ///
+    /// Size range `1..=7`
+    ///
+    /// - `3` for a triad.
+    /// - `4` for a seventh chord.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if any note of the chord is out of the `36..=84` range.
+    #[parameter_range(range = "size:1..=7")]
+    pub fn chord_of_scale_degree(
+        &self,
+        degree: isize,
+        size: usize,
+    ) -> Result<Vec<usize>, RytmError> {
+        (0..size as isize)
+            .map(|i| self.note_of_scale_degree(degree + i * 2))
+            .collect()
+    }
+
+    /// Transposes the notes of the enabled trigs in this track by scale degrees.
+    ///
+    /// Notes which are out of the scale are moved from the closest scale note below them.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a transposed note is out of the `36..=84` range.
+    /// In that case none of the trigs are modified.
+    pub fn transpose_by_scale_degrees(&mut self, degrees: isize) -> Result<(), RytmError> {
+        let notes = self.map_enabled_trig_notes(|track, note| {
+            let (degree, _) = track.scale_degree_of_note(note);
+            track.note_of_absolute_scale_degree(degree + degrees)
+        })?;
+
+        self.apply_trig_notes(&notes)
+    }
+
+    /// Moves the notes of the enabled trigs in this track to the closest note in the scale.
+    ///
+    /// When a note is in the middle of two scale notes, the lower one is chosen.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a snapped note is out of the `36..=84` range.
+    /// In that case none of the trigs are modified.
+    pub fn snap_notes_to_scale(&mut self) -> Result<(), RytmError> {
+        let notes = self.map_enabled_trig_notes(|track, note| {
+            let (degree, in_scale) = track.scale_degree_of_note(note);
+            if in_scale {
+                return note;
+            }
+            let below = track.note_of_absolute_scale_degree(degree);
+            let above = track.note_of_absolute_scale_degree(degree + 1);
+            if above - note < note - below {
+                above
+            } else {
+                below
+            }
+        })?;
+
+        self.apply_trig_notes(&notes)
+    }
+
+    /// Fills the notes of the enabled trigs in this track with an arpeggio of the given notes.
+    ///
+    /// The arpeggio repeats until the last step of the track.
+    ///
+    /// Use [`Track::chord_of_scale_degree`] to get notes of a chord in the scale of this track.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if any of the given notes is out of the `36..=84` range.
+    /// In that case none of the trigs are modified.
+    pub fn fill_arpeggio(
+        &mut self,
+        notes: &[usize],
+        direction: ArpeggioDirection,
+    ) -> Result<(), RytmError> {
+        if let Some(&note) = notes
+            .iter()
+            .find(|&&note| !(NOTE_MIN..=NOTE_MAX).contains(&(note as isize)))
+        {
+            return Err(note_out_of_range(note as isize, None));
+        }
+
+        let mut sequence = notes.to_vec();
+        sequence.sort_unstable();
+        match direction {
+            ArpeggioDirection::Up => {}
+            ArpeggioDirection::Down => sequence.reverse(),
+            ArpeggioDirection::UpDown => {
+                let descending = sequence
+                    .iter()
+                    .rev()
+                    .skip(1)
+                    .take(sequence.len().saturating_sub(2))
+                    .copied()
+                    .collect::<Vec<_>>();
+                sequence.extend(descending);
+            }
+        }
+
+        if sequence.is_empty() {
+            return Ok(());
+        }
+
+        let notes = (0..self.number_of_steps as usize)
+            .filter(|&i| self.trigs[i].enabled_trig())
+            .zip(sequence.iter().cycle())
+            .map(|(i, &note)| (i, note))
+            .collect::<Vec<_>>();
+
+        self.apply_trig_notes(&notes)
+    }
+
+    /// Maps the notes of the enabled trigs of this track and validates the results.
+    fn map_enabled_trig_notes(
+        &self,
+        f: impl Fn(&Self, isize) -> isize,
+    ) -> Result<Vec<(usize, usize)>, RytmError> {
+        (0..self.number_of_steps as usize)
+            .filter(|&i| self.trigs[i].enabled_trig())
+            .map(|i| {
+                let note = f(self, self.trigs[i].note() as isize);
+                if (NOTE_MIN..=NOTE_MAX).contains(&note) {
+                    Ok((i, note as usize))
+                } else {
+                    Err(note_out_of_range(note, Some(i)))
+                }
+            })
+            .collect()
+    }
+
+    /// Sets the notes of the trigs, expects validated notes.
+    fn apply_trig_notes(&mut self, notes: &[(usize, usize)]) -> Result<(), RytmError> {
+        for &(i, note) in notes {
+            self.trigs[i].set_note(note)?;
+        }
+        Ok(())
+    }
+}
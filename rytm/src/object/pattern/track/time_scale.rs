@@ -0,0 +1,453 @@
+// All casts in this file are intended or safe within the context of this library.
+//
+// One can change `allow` to `warn` to review them if necessary.
+#![allow(
+    clippy::cast_possible_truncation,
+    clippy::cast_possible_wrap,
+    clippy::cast_precision_loss
+)]
+#![allow(clippy::enum_glob_use)]
+
+use super::{
+    trig::{
+        types::{Length, RetrigRate, TrigCondition},
+        HoldsTrigFlags, Trig,
+    },
+    Track,
+};
+use crate::{
+    error::{ParameterError, RytmError},
+    object::pattern::types::Speed,
+};
+use rytm_rs_macro::parameter_range;
+use serde::{Deserialize, Serialize};
+
+/// Number of micro timing positions in a step.
+const MICRO_TIMING_POSITIONS: isize = 24;
+
+/// The furthest a trig can be moved from its step by micro timing.
+const MICRO_TIMING_LIMIT: isize = 23;
+
+/// The highest length value which is not infinite or unset.
+const LONGEST_LENGTH_VALUE: usize = 126;
+
+/// Divisions of the retrig rates, ordered like the values of [`RetrigRate`].
+const RETRIG_DIVISIONS: [usize; 17] = [1, 2, 3, 4, 5, 6, 8, 10, 12, 16, 20, 24, 32, 40, 48, 64, 80];
+
+/// Why a trig could not be converted exactly.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum TimeScaleIssue {
+    /// The trig falls between two micro timing positions and is moved to the nearest one.
+    Position,
+    /// The note length has no exact equivalent and the nearest one is used.
+    NoteLength,
+    /// The retrig length has no exact equivalent and the nearest one is used.
+    RetrigLength,
+    /// The retrig rate has no exact equivalent and the nearest one is used.
+    RetrigRate,
+    /// The trig has a condition which counts the loops of the track while the length of the loop changes.
+    Condition,
+    /// Another trig already lands on the same step, the trig is dropped.
+    Collision,
+    /// The trig falls after the end of the converted track, the trig is dropped.
+    OutOfRange,
+}
+
+/// A trig which could not be converted exactly.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InexactTrig {
+    /// Index of the trig before the conversion.
+    pub source_index: usize,
+    /// Index of the trig after the conversion, `None` if the trig is dropped.
+    pub destination_index: Option<usize>,
+    /// What could not be converted exactly.
+    pub issues: Vec<TimeScaleIssue>,
+}
+
+/// The outcome of a time scale conversion.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TimeScaleReport {
+    /// Trigs which could not be converted exactly.
+    ///
+    /// A trig which repeats in the converted track is listed once for each repetition.
+    pub inexact: Vec<InexactTrig>,
+    /// `true` if the converted track loops at the same time as before or repeats the track a whole number of times.
+    pub loop_preserved: bool,
+    /// `true` if the default note length of the track is converted exactly.
+    pub default_note_length_exact: bool,
+}
+
+impl TimeScaleReport {
+    /// Checks if the converted track sounds the same as before.
+    pub const fn is_exact(&self) -> bool {
+        self.inexact.is_empty() && self.loop_preserved && self.default_note_length_exact
+    }
+}
+
+/// Returns the length in sixteenths of a step for a length value in the range `0..=126`.
+const fn length_in_sixteenths(value: usize) -> usize {
+    match value {
+        0..=29 => value + 2,
+        30..=45 => 32 + (value - 30) * 2,
+        46..=61 => 64 + (value - 46) * 4,
+        62..=77 => 128 + (value - 62) * 8,
+        78..=93 => 256 + (value - 78) * 16,
+        94..=109 => 512 + (value - 94) * 32,
+        _ => 1024 + (value - 110) * 64,
+    }
+}
+
+/// Converts a length between step lengths, returns the nearest length and whether it is exact.
+///
+/// Infinite and unset lengths do not depend on the step length.
+fn convert_length(length: Length, from: usize, to: usize) -> (Length, bool) {
+    let value = u8::from(length) as usize;
+    if value > LONGEST_LENGTH_VALUE {
+        return (length, true);
+    }
+
+    let target = length_in_sixteenths(value) * from;
+    let nearest = (0..=LONGEST_LENGTH_VALUE)
+        .min_by_key(|&candidate| (length_in_sixteenths(candidate) * to).abs_diff(target))
+        .unwrap_or(value);
+
+    (
+        Length::try_from(nearest as u8).expect("Length values up to 126 are valid."),
+        length_in_sixteenths(nearest) * to == target,
+    )
+}
+
+/// Converts a retrig rate between step lengths, returns the nearest rate and whether it is exact.
+fn convert_retrig_rate(rate: RetrigRate, from: usize, to: usize) -> (RetrigRate, bool) {
+    let division = RETRIG_DIVISIONS[u8::from(rate) as usize];
+    let target = from as f64 / division as f64;
+    let nearest = (0..RETRIG_DIVISIONS.len())
+        .min_by(|&a, &b| {
+            let a = (to as f64 / RETRIG_DIVISIONS[a] as f64 - target).abs();
+            let b = (to as f64 / RETRIG_DIVISIONS[b] as f64 - target).abs();
+            a.total_cmp(&b)
+        })
+        .unwrap_or(0);
+
+    (
+        RetrigRate::try_from(nearest as u8).expect("Retrig rate values up to 16 are valid."),
+        from * RETRIG_DIVISIONS[nearest] == to * division,
+    )
+}
+
+/// Checks if a condition depends on how many times the track looped.
+const fn counts_loops(condition: TrigCondition) -> bool {
+    use TrigCondition::*;
+    !matches!(
+        condition,
+        P1 | P3
+            | P4
+            | P6
+            | P9
+            | P13
+            | P19
+            | P25
+            | P33
+            | P41
+            | P50
+            | P59
+            | P67
+            | P75
+            | P81
+            | P87
+            | P91
+            | P94
+            | P96
+            | P98
+            | P99
+            | P100
+            | Fill
+            | FillNot
+            | Pre
+            | PreNot
+            | Nei
+            | NeiNot
+            | Unset
+    )
+}
+
+impl Track {
+    /// Moves this track to another speed and number of steps without changing what it sounds like.
+    ///
+    /// Trigs are placed at the same time in the converted track, using micro timing for positions between steps.
+    /// Note lengths, retrig lengths and retrig rates of the trigs and the default note length of the track are rescaled.
+    /// Trigs bring their parameter locks along.
+    ///
+    /// A longer track repeats the trigs of this track until it ends,
+    /// so `16` steps at `1x` become `32` steps at `2x` or `64` steps at `2x` playing the same bar twice.
+    /// A shorter track drops the trigs which fall after its end.
+    ///
+    /// The speed of a track is only effective when the time mode of its pattern is advanced.
+    /// Parameter locks of the FX track which are placed on the steps of this track are not moved.
+    ///
+    /// Range `1..=64` for the number of steps.
+    ///
+    /// # Errors
+    ///
+    /// If the parameter locks do not fit [`RytmError::ParameterLockMemoryFullWith`] is returned listing the parameter locks which did not fit.
+    /// In that case this track is left untouched.
+    #[parameter_range(range = "number_of_steps:1..=64")]
+    pub fn convert_time_scale(
+        &mut self,
+        speed: Speed,
+        number_of_steps: usize,
+    ) -> Result<TimeScaleReport, RytmError> {
+        let from = self.speed.step_length();
+        let to = speed.step_length();
+
+        // Positions are counted in micro timing positions of the step length in micro timing units, so both step lengths fit.
+        let old_step = from as isize * MICRO_TIMING_POSITIONS;
+        let new_step = to as isize * MICRO_TIMING_POSITIONS;
+        let old_loop = self.number_of_steps as isize * old_step;
+        let new_loop = number_of_steps as isize * new_step;
+
+        let (default_trig_note_length, default_note_length_exact) =
+            convert_length(self.default_trig_note_length, from, to);
+        let mut report = TimeScaleReport {
+            inexact: Vec::new(),
+            loop_preserved: new_loop % old_loop == 0,
+            default_note_length_exact,
+        };
+
+        let mut trigs = (0..64)
+            .map(|trig_index| Trig::try_default(trig_index, self.index))
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut sources = [None; 64];
+
+        for trig in self.trigs[..self.number_of_steps as usize]
+            .iter()
+            .filter(|trig| trig.enabled_trig())
+        {
+            let mut converted = Trig::try_default(trig.index(), self.index)?;
+            converted.copy_data_from(trig);
+
+            let mut issues = Vec::new();
+            let (note_length, exact) = convert_length(trig.note_length(), from, to);
+            converted.set_note_length(note_length);
+            if !exact {
+                issues.push(TimeScaleIssue::NoteLength);
+            }
+            let (retrig_length, retrig_length_exact) =
+                convert_length(trig.retrig_length(), from, to);
+            let (retrig_rate, retrig_rate_exact) =
+                convert_retrig_rate(trig.retrig_rate(), from, to);
+            converted.set_retrig_length(retrig_length);
+            converted.set_retrig_rate(retrig_rate);
+            if trig.enabled_retrig() && !retrig_length_exact {
+                issues.push(TimeScaleIssue::RetrigLength);
+            }
+            if trig.enabled_retrig() && !retrig_rate_exact {
+                issues.push(TimeScaleIssue::RetrigRate);
+            }
+            if old_loop != new_loop && counts_loops(trig.trig_condition()) {
+                issues.push(TimeScaleIssue::Condition);
+            }
+
+            // Trigs which are pulled before the first step play at the end of the loop.
+            let position = ((trig.index() as isize * MICRO_TIMING_POSITIONS
+                + trig.micro_timing_value())
+                * from as isize)
+                .rem_euclid(old_loop);
+
+            if position >= new_loop {
+                report.inexact.push(InexactTrig {
+                    source_index: trig.index(),
+                    destination_index: None,
+                    issues: vec![TimeScaleIssue::OutOfRange],
+                });
+            }
+
+            for position in (position..new_loop).step_by(old_loop as usize) {
+                // Place the trig on the nearest step, ties go to the earlier step.
+                // If another trig took that step the trig is pulled from the neighbouring step with micro timing.
+                let earlier = (position / new_step, position % new_step);
+                let later = (earlier.0 + 1, earlier.1 - new_step);
+                let candidates = if earlier.1 * 2 > new_step {
+                    [later, earlier]
+                } else {
+                    [earlier, later]
+                };
+                let Some((step, offset)) = candidates
+                    .into_iter()
+                    .map(|(step, offset)| (step as usize % number_of_steps, offset))
+                    .find(|&(step, _)| sources[step].is_none())
+                else {
+                    report.inexact.push(InexactTrig {
+                        source_index: trig.index(),
+                        destination_index: None,
+                        issues: vec![TimeScaleIssue::Collision],
+                    });
+                    continue;
+                };
+
+                let mut issues = issues.clone();
+                if offset % to as isize != 0 {
+                    issues.insert(0, TimeScaleIssue::Position);
+                }
+                let micro_timing = (offset * 2 + to as isize)
+                    .div_euclid(to as isize * 2)
+                    .clamp(-MICRO_TIMING_LIMIT, MICRO_TIMING_LIMIT);
+
+                trigs[step].copy_data_from(&converted);
+                trigs[step].set_micro_timing_by_value(micro_timing)?;
+                sources[step] = Some(trig.index());
+
+                if !issues.is_empty() {
+                    report.inexact.push(InexactTrig {
+                        source_index: trig.index(),
+                        destination_index: Some(step),
+                        issues,
+                    });
+                }
+            }
+        }
+
+        if let Some(ref pool) = self.parameter_lock_pool {
+            let source = pool.lock().clone();
+            let mut staged = source.clone();
+            staged.clear_all_plocks_for_track(self.index as u8);
+
+            let mut did_not_fit = Vec::new();
+            for (destination, source_index) in sources.iter().enumerate() {
+                let Some(source_index) = *source_index else {
+                    continue;
+                };
+                match staged.copy_plocks_of_trig_from(
+                    &source,
+                    source_index,
+                    destination,
+                    self.index as u8,
+                ) {
+                    Ok(()) => {}
+                    Err(RytmError::ParameterLockMemoryFullWith {
+                        did_not_fit: mut descriptions,
+                    }) => {
+                        did_not_fit.append(&mut descriptions);
+                    }
+                    Err(error) => return Err(error),
+                }
+            }
+            if !did_not_fit.is_empty() {
+                did_not_fit.dedup();
+                return Err(RytmError::ParameterLockMemoryFullWith { did_not_fit });
+            }
+
+            *pool.lock() = staged;
+        }
+
+        for (trig, converted) in self.trigs.iter_mut().zip(&trigs) {
+            trig.copy_data_from(converted);
+        }
+        self.default_trig_note_length = default_trig_note_length;
+        self.speed = speed;
+        self.number_of_steps = number_of_steps as u8;
+
+        Ok(report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::object::pattern::{track::trig::parameter::PlockParameter, Pattern};
+
+    /// Returns a track of `16` steps at the given speed with trigs on the given steps.
+    fn track_with_trigs(speed: Speed, steps: &[usize]) -> Track {
+        let mut track = Track::try_default(0, 0, false, None).unwrap();
+        track.set_speed(speed);
+        for &step in steps {
+            track.trigs_mut()[step].set_trig_enable(true);
+        }
+        track
+    }
+
+    #[test]
+    fn doubling_the_speed_and_the_steps_is_exact() {
+        let mut pattern = Pattern::try_default(0).unwrap();
+        let track = &mut pattern.tracks_mut()[0];
+        for step in [0, 4, 8, 12] {
+            track.trigs_mut()[step].set_trig_enable(true);
+        }
+        track.trigs()[4]
+            .plock_set_parameter(PlockParameter::FilterCutoff, 40.0)
+            .unwrap();
+
+        let report = track.convert_time_scale(Speed::X2, 32).unwrap();
+
+        assert!(report.is_exact());
+        let trigs = track.trigs();
+        for (step, trig) in trigs[..32].iter().enumerate() {
+            assert_eq!(trig.enabled_trig(), step % 8 == 0);
+        }
+        assert!(trigs[8]
+            .plock_get_parameter(PlockParameter::FilterCutoff)
+            .unwrap()
+            .is_some());
+        assert_eq!(
+            trigs[4]
+                .plock_get_parameter(PlockParameter::FilterCutoff)
+                .unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn trigs_between_micro_timing_positions_are_reported() {
+        let mut track = track_with_trigs(Speed::X3B4, &[1]);
+        track.trigs_mut()[1].set_micro_timing_by_value(1).unwrap();
+
+        let report = track.convert_time_scale(Speed::X1, 16).unwrap();
+
+        assert_eq!(
+            report.inexact,
+            vec![InexactTrig {
+                source_index: 1,
+                destination_index: Some(1),
+                issues: vec![TimeScaleIssue::Position],
+            }]
+        );
+    }
+
+    #[test]
+    fn colliding_and_out_of_range_trigs_are_dropped() {
+        let mut track = track_with_trigs(Speed::X1, &[0, 1, 2, 3, 12]);
+
+        let report = track.convert_time_scale(Speed::X1B2, 4).unwrap();
+
+        assert!(report.inexact.contains(&InexactTrig {
+            source_index: 3,
+            destination_index: None,
+            issues: vec![TimeScaleIssue::Collision],
+        }));
+        assert!(report.inexact.contains(&InexactTrig {
+            source_index: 12,
+            destination_index: None,
+            issues: vec![TimeScaleIssue::OutOfRange],
+        }));
+        let enabled = track
+            .trigs()
+            .iter()
+            .filter(|trig| trig.enabled_trig())
+            .count();
+        assert_eq!(enabled, 3);
+    }
+
+    #[test]
+    fn micro_timing_before_the_first_step_wraps_to_the_end_of_the_loop() {
+        let mut track = track_with_trigs(Speed::X1, &[0]);
+        track.trigs_mut()[0].set_micro_timing_by_value(-6).unwrap();
+
+        let report = track.convert_time_scale(Speed::X2, 32).unwrap();
+
+        assert!(report.is_exact());
+        let trigs = track.trigs();
+        assert!(!trigs[0].enabled_trig());
+        assert!(trigs[31].enabled_trig());
+        assert_eq!(trigs[31].micro_timing_value(), 12);
+    }
+}
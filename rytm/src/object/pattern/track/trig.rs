@@ -13,7 +13,9 @@
 
 /// Holds the raw trig flag types
 pub mod flags;
-mod plock_impl;
+/// Typed identifiers for the parameters which can be locked in a trig.
+pub mod parameter;
+pub(crate) mod plock_impl;
 /// Holds the trig types.
 pub mod types;
 
@@ -213,6 +215,32 @@ pub trait HoldsTrigFlags {
         self.raw_trig_flags() & ENV_PL_EN == ENV_PL_EN
     }
 
+    /// Makes the trig a lock trig or a regular trig.
+    ///
+    /// A lock trig applies its parameter locks without triggering the synth, sample, envelope and LFO.
+    /// It is enabled and has the states of those locked to off.
+    fn set_lock_trig(&mut self, enable: bool) {
+        let states = SYN_PL_SW | SMP_PL_SW | ENV_PL_SW | LFO_PL_SW;
+        let locks = SYN_PL_EN | SMP_PL_EN | ENV_PL_EN | LFO_PL_EN;
+        if enable {
+            *(self.raw_trig_flags_mut()) |= ENABLE | locks;
+            *(self.raw_trig_flags_mut()) &= !states;
+        } else {
+            // Like the default.
+            *(self.raw_trig_flags_mut()) &= !(locks | LFO_PL_SW);
+            *(self.raw_trig_flags_mut()) |= SYN_PL_SW | SMP_PL_SW | ENV_PL_SW;
+        }
+    }
+
+    /// Returns `true` if the trig is a lock trig.
+    fn enabled_lock_trig(&self) -> bool {
+        let states = SYN_PL_SW | SMP_PL_SW | ENV_PL_SW;
+        let locks = SYN_PL_EN | SMP_PL_EN | ENV_PL_EN;
+        self.enabled_trig()
+            && self.raw_trig_flags() & locks == locks
+            && self.raw_trig_flags() & states == 0
+    }
+
     /// Sets all flags to the given value.
     fn swap_all_flags(&mut self, flags: &impl HoldsTrigFlags) {
         *(self.raw_trig_flags_mut()) = flags.raw_trig_flags();
@@ -318,7 +346,7 @@ impl HoldsTrigFlags for TrigFlags {
     }
 }
 
-#[derive(Derivative, Clone, Serialize, Deserialize)]
+#[derive(Derivative, Serialize, Deserialize)]
 #[derivative(Debug)]
 pub struct Trig {
     track_index: usize,
@@ -360,6 +388,32 @@ pub struct Trig {
     #[derivative(Debug = "ignore")]
     #[serde(skip)]
     pub(crate) fx_track_ref: Option<Arc<Mutex<Track>>>,
+
+    /// `true` if the trig is not part of a pattern and its parameter lock pool only holds its pending parameter locks.
+    #[derivative(Debug = "ignore")]
+    #[serde(skip)]
+    pub(crate) is_detached: bool,
+}
+
+impl Clone for Trig {
+    /// Trigs of a pattern share the parameter lock pool of the pattern, so their clones read and write the same parameter locks.
+    ///
+    /// The pending parameter locks of a detached trig are copied instead, changing them on the clone does not change the original.
+    fn clone(&self) -> Self {
+        let parameter_lock_pool = if self.is_detached {
+            self.parameter_lock_pool
+                .as_ref()
+                .map(|pool| Arc::new(Mutex::new(pool.lock().clone())))
+        } else {
+            self.parameter_lock_pool.clone()
+        };
+
+        Self {
+            parameter_lock_pool,
+            fx_track_ref: self.fx_track_ref.clone(),
+            ..*self
+        }
+    }
 }
 
 impl Trig {
@@ -394,9 +448,26 @@ impl Trig {
             sound_lock: 0xFF,
             parameter_lock_pool: None,
             fx_track_ref: None,
+            is_detached: false,
         })
     }
 
+    /// Makes a new trig complying to project defaults which is not part of a pattern yet.
+    ///
+    /// Unlike a trig made with [`Trig::try_default`], parameter locks can be set on this trig.
+    /// They are held as pending parameter locks until the trig is placed into a track with [`Track::place_trig`].
+    /// Clones of the trig get their own copy of the pending parameter locks.
+    ///
+    /// Range `0..=63` for trig index.
+    /// Range `0..=12` for track index.
+    #[parameter_range(range = "trig_index:0..=63", range = "track_index:0..=12")]
+    pub fn try_detached(trig_index: usize, track_index: usize) -> Result<Self, RytmError> {
+        let mut trig = Self::try_default(trig_index, track_index)?;
+        trig.parameter_lock_pool = Some(Arc::new(Mutex::new(ParameterLockPool::default())));
+        trig.is_detached = true;
+        Ok(trig)
+    }
+
     /// Makes a new trig from raw values.
     #[allow(clippy::too_many_arguments)]
     pub(crate) fn new(
@@ -461,9 +532,26 @@ impl Trig {
             sound_lock,
             parameter_lock_pool: Some(parameter_lock_pool),
             fx_track_ref,
+            is_detached: false,
         })
     }
 
+    /// Copies the data of the given trig to this trig.
+    ///
+    /// Indexes and the links to the owner pattern are kept.
+    pub(crate) const fn copy_data_from(&mut self, trig: &Self) {
+        self.flags = trig.flags;
+        self.note = trig.note;
+        self.trig_condition = trig.trig_condition;
+        self.velocity = trig.velocity;
+        self.note_length = trig.note_length;
+        self.micro_timing = trig.micro_timing;
+        self.retrig_rate = trig.retrig_rate;
+        self.retrig_length = trig.retrig_length;
+        self.retrig_velocity_offset = trig.retrig_velocity_offset;
+        self.sound_lock = trig.sound_lock;
+    }
+
     pub(crate) const fn encode_note(&self) -> u8 {
         // Always use standard encoding, even for Unset
         // This preserves note value in lower 7 bits (0-127 range)
@@ -521,6 +609,13 @@ impl Trig {
         self.track_index
     }
 
+    /// Returns `true` if this trig is not part of a pattern and holds its parameter locks as pending.
+    ///
+    /// See [`Trig::try_detached`].
+    pub const fn is_detached(&self) -> bool {
+        self.is_detached
+    }
+
     // TODO: Can we try to exceed the range how does the device respond?
     /// Sets the note value.
     ///
@@ -533,6 +628,20 @@ impl Trig {
         Ok(())
     }
 
+    /// Sets the raw note byte without checking the range of the device.
+    ///
+    /// Used to write back notes which are read from the device as they are.
+    pub(crate) const fn set_raw_note(&mut self, note: u8) {
+        self.note = note & 0b0111_1111;
+    }
+
+    /// Sets the raw velocity byte without checking the range of the device.
+    ///
+    /// Used to write back velocities which are read from the device as they are.
+    pub(crate) const fn set_raw_velocity(&mut self, velocity: u8) {
+        self.velocity = velocity;
+    }
+
     /// Sets the velocity value.
     ///
     /// Range `0..=127`
@@ -642,7 +751,8 @@ impl Trig {
     ///
     /// Range `-23..=23`
     pub const fn micro_timing_value(&self) -> isize {
-        self.micro_timing as isize
+        // Variants are ordered from `-23` to `23`, `OnGrid` being the 23rd.
+        self.micro_timing as isize - 23
     }
 
     /// Returns the micro timing.
@@ -824,3 +934,58 @@ fn format_trig_flags(trig: &impl HoldsTrigFlags) -> Vec<&str> {
 
     flags
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::object::pattern::{track::trig::parameter::PlockParameter, Pattern};
+
+    #[test]
+    fn clones_of_detached_trigs_have_their_own_pending_plocks() {
+        let trig = Trig::try_detached(4, 0).unwrap();
+        trig.plock_set_parameter(PlockParameter::FilterCutoff, 40.0)
+            .unwrap();
+
+        let clone = trig.clone();
+        assert_eq!(
+            clone
+                .plock_get_parameter(PlockParameter::FilterCutoff)
+                .unwrap(),
+            Some(40.0)
+        );
+
+        clone
+            .plock_set_parameter(PlockParameter::FilterCutoff, 80.0)
+            .unwrap();
+        clone
+            .plock_set_parameter(PlockParameter::AmplitudeDecay, 10.0)
+            .unwrap();
+
+        assert_eq!(
+            trig.plock_get_parameter(PlockParameter::FilterCutoff)
+                .unwrap(),
+            Some(40.0)
+        );
+        assert_eq!(
+            trig.plock_get_parameter(PlockParameter::AmplitudeDecay)
+                .unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn clones_of_pattern_trigs_share_the_pool_of_the_pattern() {
+        let pattern = Pattern::try_default(0).unwrap();
+        let trig = &pattern.tracks()[0].trigs()[4];
+
+        trig.clone()
+            .plock_set_parameter(PlockParameter::FilterCutoff, 40.0)
+            .unwrap();
+
+        assert_eq!(
+            trig.plock_get_parameter(PlockParameter::FilterCutoff)
+                .unwrap(),
+            Some(40.0)
+        );
+    }
+}
@@ -0,0 +1,796 @@
+// All casts in this file are intended or safe within the context of this library.
+//
+// One can change `allow` to `warn` to review them if necessary.
+#![allow(
+    clippy::cast_lossless,
+    clippy::cast_possible_truncation,
+    clippy::cast_sign_loss,
+    clippy::cast_precision_loss
+)]
+#![allow(clippy::enum_glob_use, clippy::too_many_lines)]
+
+use super::Trig;
+use crate::{
+    error::{ConversionError, ParameterError, RytmError},
+    object::{
+        kit::types::{
+            FxCompAttack, FxCompRatio, FxCompRelease, FxCompSideChainEq, FxLfoDestination,
+        },
+        pattern::plock::RawPlockValue,
+        sound::{
+            machine::MachineParameter,
+            types::{FilterType, LfoDestination, LfoMode, LfoMultiplier, LfoWaveform},
+        },
+    },
+};
+use serde::{Deserialize, Serialize};
+use std::ops::RangeInclusive;
+
+/// Describes how the numeric value of a [`PlockParameter`] is interpreted.
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+pub enum PlockValueKind {
+    /// Whole numbers, fractions are rounded.
+    #[default]
+    Integer,
+    /// Fractional numbers.
+    Float,
+    /// `0.0` for off and `1.0` for on.
+    Toggle,
+    /// The raw `u8` value of the enum type of the parameter.
+    ///
+    /// Not every value in the range may be a valid choice, use [`PlockParameter::accepts`] to check.
+    Choice,
+}
+
+/// Identifies a parameter which can be locked in a trig.
+///
+/// Sound parameters are locked for the track of the trig, FX parameters are locked for the FX track.
+///
+/// Machine parameters are not included since their ranges depend on the machine of the sound.
+/// They can be locked through the machine parameter types of a sound.
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+pub enum PlockParameter {
+    #[default]
+    SampleTune,
+    SampleFineTune,
+    SampleNumber,
+    SampleBitReduction,
+    SampleStart,
+    SampleEnd,
+    SampleLoopFlag,
+    SampleVolume,
+    FilterAttack,
+    FilterSustain,
+    FilterDecay,
+    FilterRelease,
+    FilterCutoff,
+    FilterResonance,
+    FilterType,
+    FilterEnvelopeAmount,
+    AmplitudeAttack,
+    AmplitudeHold,
+    AmplitudeDecay,
+    AmplitudeOverdrive,
+    AmplitudeDelaySend,
+    AmplitudeReverbSend,
+    AmplitudePan,
+    AmplitudeVolume,
+    LfoSpeed,
+    LfoMultiplier,
+    LfoFade,
+    LfoDestination,
+    LfoWaveform,
+    LfoStartPhase,
+    LfoMode,
+    LfoDepth,
+    FxDelayTime,
+    FxDelayPingPong,
+    FxDelayStereoWidth,
+    FxDelayFeedback,
+    FxDelayHpf,
+    FxDelayLpf,
+    FxDelayReverbSend,
+    FxDelayVolume,
+    FxReverbPreDelay,
+    FxReverbDecay,
+    FxReverbFreq,
+    FxReverbGain,
+    FxReverbHpf,
+    FxReverbLpf,
+    FxReverbVolume,
+    FxDistortionReverbPost,
+    FxDistortionDelayOverdrive,
+    FxDistortionDelayPost,
+    FxDistortionAmount,
+    FxDistortionSymmetry,
+    FxCompressorThreshold,
+    FxCompressorAttack,
+    FxCompressorRelease,
+    FxCompressorRatio,
+    FxCompressorSideChainEq,
+    FxCompressorGain,
+    FxCompressorMix,
+    FxCompressorVolume,
+    FxLfoSpeed,
+    FxLfoMultiplier,
+    FxLfoFade,
+    FxLfoDestination,
+    FxLfoWaveform,
+    FxLfoStartPhase,
+    FxLfoMode,
+    FxLfoDepth,
+}
+
+impl PlockParameter {
+    /// All parameters which can be locked in a trig.
+    pub const ALL: [Self; 68] = {
+        use PlockParameter::*;
+        [
+            SampleTune,
+            SampleFineTune,
+            SampleNumber,
+            SampleBitReduction,
+            SampleStart,
+            SampleEnd,
+            SampleLoopFlag,
+            SampleVolume,
+            FilterAttack,
+            FilterSustain,
+            FilterDecay,
+            FilterRelease,
+            FilterCutoff,
+            FilterResonance,
+            FilterType,
+            FilterEnvelopeAmount,
+            AmplitudeAttack,
+            AmplitudeHold,
+            AmplitudeDecay,
+            AmplitudeOverdrive,
+            AmplitudeDelaySend,
+            AmplitudeReverbSend,
+            AmplitudePan,
+            AmplitudeVolume,
+            LfoSpeed,
+            LfoMultiplier,
+            LfoFade,
+            LfoDestination,
+            LfoWaveform,
+            LfoStartPhase,
+            LfoMode,
+            LfoDepth,
+            FxDelayTime,
+            FxDelayPingPong,
+            FxDelayStereoWidth,
+            FxDelayFeedback,
+            FxDelayHpf,
+            FxDelayLpf,
+            FxDelayReverbSend,
+            FxDelayVolume,
+            FxReverbPreDelay,
+            FxReverbDecay,
+            FxReverbFreq,
+            FxReverbGain,
+            FxReverbHpf,
+            FxReverbLpf,
+            FxReverbVolume,
+            FxDistortionReverbPost,
+            FxDistortionDelayOverdrive,
+            FxDistortionDelayPost,
+            FxDistortionAmount,
+            FxDistortionSymmetry,
+            FxCompressorThreshold,
+            FxCompressorAttack,
+            FxCompressorRelease,
+            FxCompressorRatio,
+            FxCompressorSideChainEq,
+            FxCompressorGain,
+            FxCompressorMix,
+            FxCompressorVolume,
+            FxLfoSpeed,
+            FxLfoMultiplier,
+            FxLfoFade,
+            FxLfoDestination,
+            FxLfoWaveform,
+            FxLfoStartPhase,
+            FxLfoMode,
+            FxLfoDepth,
+        ]
+    };
+
+    /// Checks if the parameter belongs to the FX track.
+    pub const fn is_fx(self) -> bool {
+        self as u8 >= Self::FxDelayTime as u8
+    }
+
+    /// Returns how the numeric value of the parameter is interpreted.
+    pub const fn kind(self) -> PlockValueKind {
+        use PlockParameter::*;
+        match self {
+            SampleStart | SampleEnd | LfoDepth | FxLfoDepth => PlockValueKind::Float,
+            SampleLoopFlag | FxDelayPingPong | FxDistortionReverbPost | FxDistortionDelayPost => {
+                PlockValueKind::Toggle
+            }
+            FilterType
+            | LfoMultiplier
+            | LfoDestination
+            | LfoWaveform
+            | LfoMode
+            | FxCompressorAttack
+            | FxCompressorRelease
+            | FxCompressorRatio
+            | FxCompressorSideChainEq
+            | FxLfoMultiplier
+            | FxLfoDestination
+            | FxLfoWaveform
+            | FxLfoMode => PlockValueKind::Choice,
+            _ => PlockValueKind::Integer,
+        }
+    }
+
+    /// Returns the range of the numeric value of the parameter.
+    ///
+    /// The range matches the range of the corresponding `plock_set_*` method of [`Trig`].
+    pub const fn range(self) -> RangeInclusive<f32> {
+        use PlockParameter::*;
+        match self {
+            SampleTune => -24.0..=24.0,
+            SampleFineTune | FilterEnvelopeAmount | AmplitudePan | LfoSpeed | LfoFade
+            | FxDelayStereoWidth | FxDistortionSymmetry | FxLfoSpeed | FxLfoFade => -64.0..=63.0,
+            SampleStart | SampleEnd => 0.0..=120.0,
+            LfoDepth | FxLfoDepth => -128.0..=127.99,
+            SampleLoopFlag | FxDelayPingPong | FxDistortionReverbPost | FxDistortionDelayPost => {
+                0.0..=1.0
+            }
+            FilterType | LfoWaveform | FxLfoWaveform | FxCompressorAttack => 0.0..=6.0,
+            LfoMultiplier | FxLfoMultiplier => 0.0..=23.0,
+            LfoDestination => 0.0..=41.0,
+            LfoMode | FxLfoMode => 0.0..=4.0,
+            FxCompressorRelease => 0.0..=7.0,
+            FxCompressorRatio | FxCompressorSideChainEq => 0.0..=3.0,
+            FxLfoDestination => 0.0..=37.0,
+            _ => 0.0..=127.0,
+        }
+    }
+
+    /// Checks if the value would be accepted by [`Trig::plock_set_parameter`] for this parameter.
+    pub fn accepts(self, value: f32) -> bool {
+        self.validate(value).is_ok()
+    }
+
+    /// Validates the value and returns it normalized to the kind of the parameter.
+    pub(crate) fn validate(self, value: f32) -> Result<f32, RytmError> {
+        let kind = self.kind();
+        let value = if kind == PlockValueKind::Float {
+            value
+        } else {
+            value.round()
+        };
+
+        if !self.range().contains(&value) {
+            return Err(ParameterError::Range {
+                value: value.to_string(),
+                parameter_name: <&str>::from(self).to_owned(),
+            }
+            .into());
+        }
+
+        if kind == PlockValueKind::Choice {
+            use PlockParameter::*;
+            let code = value as u8;
+            match self {
+                FilterType => drop(self::FilterType::try_from(code)?),
+                LfoMultiplier | FxLfoMultiplier => drop(self::LfoMultiplier::try_from(code)?),
+                LfoDestination => drop(self::LfoDestination::try_from(code)?),
+                LfoWaveform | FxLfoWaveform => drop(self::LfoWaveform::try_from(code)?),
+                LfoMode | FxLfoMode => drop(self::LfoMode::try_from(code)?),
+                FxCompressorAttack => drop(FxCompAttack::try_from(code)?),
+                FxCompressorRelease => drop(FxCompRelease::try_from(code)?),
+                FxCompressorRatio => drop(FxCompRatio::try_from(code)?),
+                FxCompressorSideChainEq => drop(FxCompSideChainEq::try_from(code)?),
+                FxLfoDestination => drop(self::FxLfoDestination::try_from(code)?),
+                _ => unreachable!("Only choice parameters are matched here."),
+            }
+        }
+
+        Ok(value)
+    }
+}
+
+impl TryFrom<&str> for PlockParameter {
+    type Error = ConversionError;
+    fn try_from(parameter: &str) -> Result<Self, Self::Error> {
+        Self::ALL
+            .into_iter()
+            .find(|&candidate| <&str>::from(candidate) == parameter)
+            .ok_or_else(|| ConversionError::Range {
+                value: parameter.to_string(),
+                type_name: "PlockParameter".to_string(),
+            })
+    }
+}
+
+impl From<PlockParameter> for &str {
+    fn from(parameter: PlockParameter) -> Self {
+        use PlockParameter::*;
+        match parameter {
+            SampleTune => "sample_tune",
+            SampleFineTune => "sample_fine_tune",
+            SampleNumber => "sample_number",
+            SampleBitReduction => "sample_bit_reduction",
+            SampleStart => "sample_start",
+            SampleEnd => "sample_end",
+            SampleLoopFlag => "sample_loop_flag",
+            SampleVolume => "sample_volume",
+            FilterAttack => "filter_attack",
+            FilterSustain => "filter_sustain",
+            FilterDecay => "filter_decay",
+            FilterRelease => "filter_release",
+            FilterCutoff => "filter_cutoff",
+            FilterResonance => "filter_resonance",
+            FilterType => "filter_type",
+            FilterEnvelopeAmount => "filter_envelope_amount",
+            AmplitudeAttack => "amplitude_attack",
+            AmplitudeHold => "amplitude_hold",
+            AmplitudeDecay => "amplitude_decay",
+            AmplitudeOverdrive => "amplitude_overdrive",
+            AmplitudeDelaySend => "amplitude_delay_send",
+            AmplitudeReverbSend => "amplitude_reverb_send",
+            AmplitudePan => "amplitude_pan",
+            AmplitudeVolume => "amplitude_volume",
+            LfoSpeed => "lfo_speed",
+            LfoMultiplier => "lfo_multiplier",
+            LfoFade => "lfo_fade",
+            LfoDestination => "lfo_destination",
+            LfoWaveform => "lfo_waveform",
+            LfoStartPhase => "lfo_start_phase",
+            LfoMode => "lfo_mode",
+            LfoDepth => "lfo_depth",
+            FxDelayTime => "fx_delay_time",
+            FxDelayPingPong => "fx_delay_ping_pong",
+            FxDelayStereoWidth => "fx_delay_stereo_width",
+            FxDelayFeedback => "fx_delay_feedback",
+            FxDelayHpf => "fx_delay_hpf",
+            FxDelayLpf => "fx_delay_lpf",
+            FxDelayReverbSend => "fx_delay_reverb_send",
+            FxDelayVolume => "fx_delay_volume",
+            FxReverbPreDelay => "fx_reverb_pre_delay",
+            FxReverbDecay => "fx_reverb_decay",
+            FxReverbFreq => "fx_reverb_freq",
+            FxReverbGain => "fx_reverb_gain",
+            FxReverbHpf => "fx_reverb_hpf",
+            FxReverbLpf => "fx_reverb_lpf",
+            FxReverbVolume => "fx_reverb_volume",
+            FxDistortionReverbPost => "fx_distortion_reverb_post",
+            FxDistortionDelayOverdrive => "fx_distortion_delay_overdrive",
+            FxDistortionDelayPost => "fx_distortion_delay_post",
+            FxDistortionAmount => "fx_distortion_amount",
+            FxDistortionSymmetry => "fx_distortion_symmetry",
+            FxCompressorThreshold => "fx_compressor_threshold",
+            FxCompressorAttack => "fx_compressor_attack",
+            FxCompressorRelease => "fx_compressor_release",
+            FxCompressorRatio => "fx_compressor_ratio",
+            FxCompressorSideChainEq => "fx_compressor_side_chain_eq",
+            FxCompressorGain => "fx_compressor_gain",
+            FxCompressorMix => "fx_compressor_mix",
+            FxCompressorVolume => "fx_compressor_volume",
+            FxLfoSpeed => "fx_lfo_speed",
+            FxLfoMultiplier => "fx_lfo_multiplier",
+            FxLfoFade => "fx_lfo_fade",
+            FxLfoDestination => "fx_lfo_destination",
+            FxLfoWaveform => "fx_lfo_waveform",
+            FxLfoStartPhase => "fx_lfo_start_phase",
+            FxLfoMode => "fx_lfo_mode",
+            FxLfoDepth => "fx_lfo_depth",
+        }
+    }
+}
+
+impl Trig {
+    /// Sets a parameter lock by its identifier and numeric value.
+    ///
+    /// The value is interpreted according to [`PlockParameter::kind`] and validated against [`PlockParameter::range`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the value is not accepted by the parameter, the trig is orphan or the parameter lock memory is full.
+    pub fn plock_set_parameter(
+        &self,
+        parameter: PlockParameter,
+        value: f32,
+    ) -> Result<(), RytmError> {
+        use PlockParameter::*;
+        let value = parameter.validate(value)?;
+        let code = value as u8;
+        let whole = value as isize;
+        let toggle = value != 0.0;
+
+        match parameter {
+            SampleTune => self.plock_set_sample_tune(whole),
+            SampleFineTune => self.plock_set_sample_fine_tune(whole),
+            SampleNumber => self.plock_set_sample_number(whole as usize),
+            SampleBitReduction => self.plock_set_sample_bit_reduction(whole as usize),
+            SampleStart => self.plock_set_sample_start(value),
+            SampleEnd => self.plock_set_sample_end(value),
+            SampleLoopFlag => self.plock_set_sample_loop_flag(toggle),
+            SampleVolume => self.plock_set_sample_volume(whole as usize),
+            FilterAttack => self.plock_set_filter_attack(whole as usize),
+            FilterSustain => self.plock_set_filter_sustain(whole as usize),
+            FilterDecay => self.plock_set_filter_decay(whole as usize),
+            FilterRelease => self.plock_set_filter_release(whole as usize),
+            FilterCutoff => self.plock_set_filter_cutoff(whole as usize),
+            FilterResonance => self.plock_set_filter_resonance(whole as usize),
+            FilterType => self.plock_set_filter_type(code.try_into()?),
+            FilterEnvelopeAmount => self.plock_set_filter_envelope_amount(whole),
+            AmplitudeAttack => self.plock_set_amplitude_attack(whole as usize),
+            AmplitudeHold => self.plock_set_amplitude_hold(whole as usize),
+            AmplitudeDecay => self.plock_set_amplitude_decay(whole as usize),
+            AmplitudeOverdrive => self.plock_set_amplitude_overdrive(whole as usize),
+            AmplitudeDelaySend => self.plock_set_amplitude_delay_send(whole as usize),
+            AmplitudeReverbSend => self.plock_set_amplitude_reverb_send(whole as usize),
+            AmplitudePan => self.plock_set_amplitude_pan(whole),
+            AmplitudeVolume => self.plock_set_amplitude_volume(whole as usize),
+            LfoSpeed => self.plock_set_lfo_speed(whole),
+            LfoMultiplier => self.plock_set_lfo_multiplier(code.try_into()?),
+            LfoFade => self.plock_set_lfo_fade(whole),
+            LfoDestination => self.plock_set_lfo_destination(code.try_into()?),
+            LfoWaveform => self.plock_set_lfo_waveform(code.try_into()?),
+            LfoStartPhase => self.plock_set_lfo_start_phase(whole as usize),
+            LfoMode => self.plock_set_lfo_mode(code.try_into()?),
+            LfoDepth => self.plock_set_lfo_depth(value),
+            FxDelayTime => self.plock_set_fx_delay_time(whole as usize),
+            FxDelayPingPong => self.plock_set_fx_delay_ping_pong(toggle),
+            FxDelayStereoWidth => self.plock_set_fx_delay_stereo_width(whole),
+            FxDelayFeedback => self.plock_set_fx_delay_feedback(whole as usize),
+            FxDelayHpf => self.plock_set_fx_delay_hpf(whole as usize),
+            FxDelayLpf => self.plock_set_fx_delay_lpf(whole as usize),
+            FxDelayReverbSend => self.plock_set_fx_delay_reverb_send(whole as usize),
+            FxDelayVolume => self.plock_set_fx_delay_volume(whole as usize),
+            FxReverbPreDelay => self.plock_set_fx_reverb_pre_delay(whole as usize),
+            FxReverbDecay => self.plock_set_fx_reverb_decay(whole as usize),
+            FxReverbFreq => self.plock_set_fx_reverb_freq(whole as usize),
+            FxReverbGain => self.plock_set_fx_reverb_gain(whole as usize),
+            FxReverbHpf => self.plock_set_fx_reverb_hpf(whole as usize),
+            FxReverbLpf => self.plock_set_fx_reverb_lpf(whole as usize),
+            FxReverbVolume => self.plock_set_fx_reverb_volume(whole as usize),
+            FxDistortionReverbPost => self.plock_set_fx_distortion_reverb_post(toggle),
+            FxDistortionDelayOverdrive => {
+                self.plock_set_fx_distortion_delay_overdrive(whole as usize)
+            }
+            FxDistortionDelayPost => self.plock_set_fx_distortion_delay_post(toggle),
+            FxDistortionAmount => self.plock_set_fx_distortion_amount(whole as usize),
+            FxDistortionSymmetry => self.plock_set_fx_distortion_symmetry(whole),
+            FxCompressorThreshold => self.plock_set_fx_compressor_threshold(whole as usize),
+            FxCompressorAttack => self.plock_set_fx_compressor_attack(code.try_into()?),
+            FxCompressorRelease => self.plock_set_fx_compressor_release(code.try_into()?),
+            FxCompressorRatio => self.plock_set_fx_compressor_ratio(code.try_into()?),
+            FxCompressorSideChainEq => self.plock_set_fx_compressor_side_chain_eq(code.try_into()?),
+            FxCompressorGain => self.plock_set_fx_compressor_gain(whole as usize),
+            FxCompressorMix => self.plock_set_fx_compressor_mix(whole as usize),
+            FxCompressorVolume => self.plock_set_fx_compressor_volume(whole as usize),
+            FxLfoSpeed => self.plock_set_fx_lfo_speed(whole),
+            FxLfoMultiplier => self.plock_set_fx_lfo_multiplier(code.try_into()?),
+            FxLfoFade => self.plock_set_fx_lfo_fade(whole),
+            FxLfoDestination => self.plock_set_fx_lfo_destination(code.try_into()?),
+            FxLfoWaveform => self.plock_set_fx_lfo_waveform(code.try_into()?),
+            FxLfoStartPhase => self.plock_set_fx_lfo_start_phase(whole as usize),
+            FxLfoMode => self.plock_set_fx_lfo_mode(code.try_into()?),
+            FxLfoDepth => self.plock_set_fx_lfo_depth(value),
+        }
+    }
+
+    /// Gets the numeric value of a parameter lock by its identifier.
+    ///
+    /// The value is represented according to [`PlockParameter::kind`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the trig is orphan or the stored value can not be decoded.
+    pub fn plock_get_parameter(&self, parameter: PlockParameter) -> Result<Option<f32>, RytmError> {
+        use PlockParameter::*;
+        const fn toggle(value: bool) -> f32 {
+            if value {
+                1.0
+            } else {
+                0.0
+            }
+        }
+        fn choice<T: Into<u8>>(value: T) -> f32 {
+            value.into() as f32
+        }
+
+        Ok(match parameter {
+            SampleTune => self.plock_get_sample_tune()?.map(|value| value as f32),
+            SampleFineTune => self.plock_get_sample_fine_tune()?.map(|value| value as f32),
+            SampleNumber => self.plock_get_sample_number()?.map(|value| value as f32),
+            SampleBitReduction => self
+                .plock_get_sample_bit_reduction()?
+                .map(|value| value as f32),
+            SampleStart => self.plock_get_sample_start()?,
+            SampleEnd => self.plock_get_sample_end()?,
+            SampleLoopFlag => self.plock_get_sample_loop_flag()?.map(toggle),
+            SampleVolume => self.plock_get_sample_volume()?.map(|value| value as f32),
+            FilterAttack => self.plock_get_filter_attack()?.map(|value| value as f32),
+            FilterSustain => self.plock_get_filter_sustain()?.map(|value| value as f32),
+            FilterDecay => self.plock_get_filter_decay()?.map(|value| value as f32),
+            FilterRelease => self.plock_get_filter_release()?.map(|value| value as f32),
+            FilterCutoff => self.plock_get_filter_cutoff()?.map(|value| value as f32),
+            FilterResonance => self.plock_get_filter_resonance()?.map(|value| value as f32),
+            FilterType => self.plock_get_filter_type()?.map(choice),
+            FilterEnvelopeAmount => self
+                .plock_get_filter_envelope_amount()?
+                .map(|value| value as f32),
+            AmplitudeAttack => self.plock_get_amplitude_attack()?.map(|value| value as f32),
+            AmplitudeHold => self.plock_get_amplitude_hold()?.map(|value| value as f32),
+            AmplitudeDecay => self.plock_get_amplitude_decay()?.map(|value| value as f32),
+            AmplitudeOverdrive => self
+                .plock_get_amplitude_overdrive()?
+                .map(|value| value as f32),
+            AmplitudeDelaySend => self
+                .plock_get_amplitude_delay_send()?
+                .map(|value| value as f32),
+            AmplitudeReverbSend => self
+                .plock_get_amplitude_reverb_send()?
+                .map(|value| value as f32),
+            AmplitudePan => self.plock_get_amplitude_pan()?.map(|value| value as f32),
+            AmplitudeVolume => self.plock_get_amplitude_volume()?.map(|value| value as f32),
+            LfoSpeed => self.plock_get_lfo_speed()?.map(|value| value as f32),
+            LfoMultiplier => self.plock_get_lfo_multiplier()?.map(choice),
+            LfoFade => self.plock_get_lfo_fade()?.map(|value| value as f32),
+            LfoDestination => self.plock_get_lfo_destination()?.map(choice),
+            LfoWaveform => self.plock_get_lfo_waveform()?.map(choice),
+            LfoStartPhase => self.plock_get_lfo_start_phase()?.map(|value| value as f32),
+            LfoMode => self.plock_get_lfo_mode()?.map(choice),
+            LfoDepth => self.plock_get_lfo_depth()?,
+            FxDelayTime => self.plock_get_fx_delay_time()?.map(|value| value as f32),
+            FxDelayPingPong => self.plock_get_fx_delay_ping_pong()?.map(toggle),
+            FxDelayStereoWidth => self
+                .plock_get_fx_delay_stereo_width()?
+                .map(|value| value as f32),
+            FxDelayFeedback => self
+                .plock_get_fx_delay_feedback()?
+                .map(|value| value as f32),
+            FxDelayHpf => self.plock_get_fx_delay_hpf()?.map(|value| value as f32),
+            FxDelayLpf => self.plock_get_fx_delay_lpf()?.map(|value| value as f32),
+            FxDelayReverbSend => self
+                .plock_get_fx_delay_reverb_send()?
+                .map(|value| value as f32),
+            FxDelayVolume => self.plock_get_fx_delay_volume()?.map(|value| value as f32),
+            FxReverbPreDelay => self
+                .plock_get_fx_reverb_pre_delay()?
+                .map(|value| value as f32),
+            FxReverbDecay => self.plock_get_fx_reverb_decay()?.map(|value| value as f32),
+            FxReverbFreq => self.plock_get_fx_reverb_freq()?.map(|value| value as f32),
+            FxReverbGain => self.plock_get_fx_reverb_gain()?.map(|value| value as f32),
+            FxReverbHpf => self.plock_get_fx_reverb_hpf()?.map(|value| value as f32),
+            FxReverbLpf => self.plock_get_fx_reverb_lpf()?.map(|value| value as f32),
+            FxReverbVolume => self.plock_get_fx_reverb_volume()?.map(|value| value as f32),
+            FxDistortionReverbPost => self.plock_get_fx_distortion_reverb_post()?.map(toggle),
+            FxDistortionDelayOverdrive => self
+                .plock_get_fx_distortion_delay_overdrive()?
+                .map(|value| value as f32),
+            FxDistortionDelayPost => self.plock_get_fx_distortion_delay_post()?.map(toggle),
+            FxDistortionAmount => self
+                .plock_get_fx_distortion_amount()?
+                .map(|value| value as f32),
+            FxDistortionSymmetry => self
+                .plock_get_fx_distortion_symmetry()?
+                .map(|value| value as f32),
+            FxCompressorThreshold => self
+                .plock_get_fx_compressor_threshold()?
+                .map(|value| value as f32),
+            FxCompressorAttack => self.plock_get_fx_compressor_attack()?.map(choice),
+            FxCompressorRelease => self.plock_get_fx_compressor_release()?.map(choice),
+            FxCompressorRatio => self.plock_get_fx_compressor_ratio()?.map(choice),
+            FxCompressorSideChainEq => self.plock_get_fx_compressor_side_chain_eq()?.map(choice),
+            FxCompressorGain => self
+                .plock_get_fx_compressor_gain()?
+                .map(|value| value as f32),
+            FxCompressorMix => self
+                .plock_get_fx_compressor_mix()?
+                .map(|value| value as f32),
+            FxCompressorVolume => self
+                .plock_get_fx_compressor_volume()?
+                .map(|value| value as f32),
+            FxLfoSpeed => self.plock_get_fx_lfo_speed()?.map(|value| value as f32),
+            FxLfoMultiplier => self.plock_get_fx_lfo_multiplier()?.map(choice),
+            FxLfoFade => self.plock_get_fx_lfo_fade()?.map(|value| value as f32),
+            FxLfoDestination => self.plock_get_fx_lfo_destination()?.map(choice),
+            FxLfoWaveform => self.plock_get_fx_lfo_waveform()?.map(choice),
+            FxLfoStartPhase => self
+                .plock_get_fx_lfo_start_phase()?
+                .map(|value| value as f32),
+            FxLfoMode => self.plock_get_fx_lfo_mode()?.map(choice),
+            FxLfoDepth => self.plock_get_fx_lfo_depth()?,
+        })
+    }
+
+    /// Clears a parameter lock by its identifier.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the trig is orphan.
+    pub fn plock_clear_parameter(&self, parameter: PlockParameter) -> Result<(), RytmError> {
+        use PlockParameter::*;
+        match parameter {
+            SampleTune => self.plock_clear_sample_tune(),
+            SampleFineTune => self.plock_clear_sample_fine_tune(),
+            SampleNumber => self.plock_clear_sample_number(),
+            SampleBitReduction => self.plock_clear_sample_bit_reduction(),
+            SampleStart => self.plock_clear_sample_start(),
+            SampleEnd => self.plock_clear_sample_end(),
+            SampleLoopFlag => self.plock_clear_sample_loop_flag(),
+            SampleVolume => self.plock_clear_sample_volume(),
+            FilterAttack => self.plock_clear_filter_attack(),
+            FilterSustain => self.plock_clear_filter_sustain(),
+            FilterDecay => self.plock_clear_filter_decay(),
+            FilterRelease => self.plock_clear_filter_release(),
+            FilterCutoff => self.plock_clear_filter_cutoff(),
+            FilterResonance => self.plock_clear_filter_resonance(),
+            FilterType => self.plock_clear_filter_type(),
+            FilterEnvelopeAmount => self.plock_clear_filter_envelope_amount(),
+            AmplitudeAttack => self.plock_clear_amplitude_attack(),
+            AmplitudeHold => self.plock_clear_amplitude_hold(),
+            AmplitudeDecay => self.plock_clear_amplitude_decay(),
+            AmplitudeOverdrive => self.plock_clear_amplitude_overdrive(),
+            AmplitudeDelaySend => self.plock_clear_amplitude_delay_send(),
+            AmplitudeReverbSend => self.plock_clear_amplitude_reverb_send(),
+            AmplitudePan => self.plock_clear_amplitude_pan(),
+            AmplitudeVolume => self.plock_clear_amplitude_volume(),
+            LfoSpeed => self.plock_clear_lfo_speed(),
+            LfoMultiplier => self.plock_clear_lfo_multiplier(),
+            LfoFade => self.plock_clear_lfo_fade(),
+            LfoDestination => self.plock_clear_lfo_destination(),
+            LfoWaveform => self.plock_clear_lfo_waveform(),
+            LfoStartPhase => self.plock_clear_lfo_start_phase(),
+            LfoMode => self.plock_clear_lfo_mode(),
+            LfoDepth => self.plock_clear_lfo_depth(),
+            FxDelayTime => self.plock_clear_fx_delay_time(),
+            FxDelayPingPong => self.plock_clear_fx_delay_ping_pong(),
+            FxDelayStereoWidth => self.plock_clear_fx_delay_stereo_width(),
+            FxDelayFeedback => self.plock_clear_fx_delay_feedback(),
+            FxDelayHpf => self.plock_clear_fx_delay_hpf(),
+            FxDelayLpf => self.plock_clear_fx_delay_lpf(),
+            FxDelayReverbSend => self.plock_clear_fx_delay_reverb_send(),
+            FxDelayVolume => self.plock_clear_fx_delay_volume(),
+            FxReverbPreDelay => self.plock_clear_fx_reverb_pre_delay(),
+            FxReverbDecay => self.plock_clear_fx_reverb_decay(),
+            FxReverbFreq => self.plock_clear_fx_reverb_freq(),
+            FxReverbGain => self.plock_clear_fx_reverb_gain(),
+            FxReverbHpf => self.plock_clear_fx_reverb_hpf(),
+            FxReverbLpf => self.plock_clear_fx_reverb_lpf(),
+            FxReverbVolume => self.plock_clear_fx_reverb_volume(),
+            FxDistortionReverbPost => self.plock_clear_fx_distortion_reverb_post(),
+            FxDistortionDelayOverdrive => self.plock_clear_fx_distortion_delay_overdrive(),
+            FxDistortionDelayPost => self.plock_clear_fx_distortion_delay_post(),
+            FxDistortionAmount => self.plock_clear_fx_distortion_amount(),
+            FxDistortionSymmetry => self.plock_clear_fx_distortion_symmetry(),
+            FxCompressorThreshold => self.plock_clear_fx_compressor_threshold(),
+            FxCompressorAttack => self.plock_clear_fx_compressor_attack(),
+            FxCompressorRelease => self.plock_clear_fx_compressor_release(),
+            FxCompressorRatio => self.plock_clear_fx_compressor_ratio(),
+            FxCompressorSideChainEq => self.plock_clear_fx_compressor_side_chain_eq(),
+            FxCompressorGain => self.plock_clear_fx_compressor_gain(),
+            FxCompressorMix => self.plock_clear_fx_compressor_mix(),
+            FxCompressorVolume => self.plock_clear_fx_compressor_volume(),
+            FxLfoSpeed => self.plock_clear_fx_lfo_speed(),
+            FxLfoMultiplier => self.plock_clear_fx_lfo_multiplier(),
+            FxLfoFade => self.plock_clear_fx_lfo_fade(),
+            FxLfoDestination => self.plock_clear_fx_lfo_destination(),
+            FxLfoWaveform => self.plock_clear_fx_lfo_waveform(),
+            FxLfoStartPhase => self.plock_clear_fx_lfo_start_phase(),
+            FxLfoMode => self.plock_clear_fx_lfo_mode(),
+            FxLfoDepth => self.plock_clear_fx_lfo_depth(),
+        }
+    }
+
+    /// Returns an error if the synth parameters of a machine can not be locked on this trig.
+    fn check_machine_parameter_lock(&self) -> Result<(), RytmError> {
+        if self.track_index == 12 {
+            return Err(ParameterError::Compatibility {
+                value: self.track_index.to_string(),
+                parameter_name: "track_index".into(),
+                reason: Some("The FX track does not have synth parameters.".into()),
+            }
+            .into());
+        }
+        if self.parameter_lock_pool.is_none() {
+            return Err(RytmError::OrphanTrig);
+        }
+        Ok(())
+    }
+
+    /// Sets a parameter lock for a synth parameter of a machine.
+    ///
+    /// The value is interpreted according to [`MachineParameter::kind`] and validated against [`MachineParameter::range`].
+    ///
+    /// The trig does not know the machine of its track, make sure the parameter belongs to the machine which is used by the sound of the track.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the value is not accepted by the parameter, the trig is on the FX track or orphan or the parameter lock memory is full.
+    pub fn plock_set_machine_parameter(
+        &self,
+        parameter: MachineParameter,
+        value: f32,
+    ) -> Result<(), RytmError> {
+        self.check_machine_parameter_lock()?;
+        let value = parameter.validate(value)?;
+        let Some(ref pool) = self.parameter_lock_pool else {
+            return Err(RytmError::OrphanTrig);
+        };
+
+        let mut pool = pool.lock();
+        match parameter.encode(value) {
+            RawPlockValue::Basic(value) => pool.set_basic_plock(
+                self.index,
+                self.track_index as u8,
+                parameter.plock_type(),
+                value,
+            ),
+            RawPlockValue::Compound(value) => pool.set_compound_plock(
+                self.index,
+                self.track_index as u8,
+                parameter.plock_type(),
+                value,
+            ),
+        }
+    }
+
+    /// Gets the numeric value of a parameter lock for a synth parameter of a machine.
+    ///
+    /// The value is represented according to [`MachineParameter::kind`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the trig is on the FX track or orphan.
+    pub fn plock_get_machine_parameter(
+        &self,
+        parameter: MachineParameter,
+    ) -> Result<Option<f32>, RytmError> {
+        self.check_machine_parameter_lock()?;
+        let Some(ref pool) = self.parameter_lock_pool else {
+            return Err(RytmError::OrphanTrig);
+        };
+
+        let pool = pool.lock();
+        let value = if parameter.is_compound() {
+            pool.get_compound_plock(self.index, self.track_index as u8, parameter.plock_type())
+                .map(RawPlockValue::Compound)
+        } else {
+            pool.get_basic_plock(self.index, self.track_index as u8, parameter.plock_type())
+                .map(RawPlockValue::Basic)
+        };
+        drop(pool);
+
+        Ok(value.map(|value| parameter.decode(value)))
+    }
+
+    /// Clears a parameter lock for a synth parameter of a machine.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the trig is on the FX track or orphan.
+    pub fn plock_clear_machine_parameter(
+        &self,
+        parameter: MachineParameter,
+    ) -> Result<(), RytmError> {
+        self.check_machine_parameter_lock()?;
+        let Some(ref pool) = self.parameter_lock_pool else {
+            return Err(RytmError::OrphanTrig);
+        };
+
+        let mut pool = pool.lock();
+        if parameter.is_compound() {
+            pool.clear_compound_plock(self.index, self.track_index as u8, parameter.plock_type());
+        } else {
+            pool.clear_basic_plock(self.index, self.track_index as u8, parameter.plock_type());
+        }
+        drop(pool);
+
+        Ok(())
+    }
+}
@@ -357,3 +357,49 @@ impl TryFrom<u8> for PadScale {
         }
     }
 }
+
+impl PadScale {
+    /// Returns the intervals of the scale in semitones from the root note.
+    ///
+    /// The intervals are sorted and always start with `0`.
+    pub const fn intervals(self) -> &'static [u8] {
+        match self {
+            Self::Chromatic => &[0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11],
+            Self::IonianMajor => &[0, 2, 4, 5, 7, 9, 11],
+            Self::Dorian => &[0, 2, 3, 5, 7, 9, 10],
+            Self::Phrygian => &[0, 1, 3, 5, 7, 8, 10],
+            Self::Lydian => &[0, 2, 4, 6, 7, 9, 11],
+            Self::Mixolydian => &[0, 2, 4, 5, 7, 9, 10],
+            Self::AeolianMinor => &[0, 2, 3, 5, 7, 8, 10],
+            Self::Locrian => &[0, 1, 3, 5, 6, 8, 10],
+            Self::PentatonicMinor => &[0, 3, 5, 7, 10],
+            Self::PentatonicMajor => &[0, 2, 4, 7, 9],
+            Self::MelodicMinor => &[0, 2, 3, 5, 7, 9, 11],
+            Self::HarmonicMinor => &[0, 2, 3, 5, 7, 8, 11],
+            Self::WholeTone => &[0, 2, 4, 6, 8, 10],
+            Self::Blues => &[0, 3, 5, 6, 7, 10],
+            Self::ComboMinor => &[0, 2, 3, 5, 7, 8, 9, 10, 11],
+            Self::Persian => &[0, 1, 4, 5, 6, 8, 11],
+            Self::Iwato => &[0, 1, 5, 6, 10],
+            Self::InSen => &[0, 1, 5, 7, 10],
+            Self::Hirajoshi => &[0, 2, 3, 7, 8],
+            Self::Pelog => &[0, 1, 3, 7, 8],
+            Self::PhrygianDominant => &[0, 1, 4, 5, 7, 8, 10],
+            Self::WholeHalfDiminished => &[0, 2, 3, 5, 6, 8, 9, 11],
+            Self::HalfWholeDiminished => &[0, 1, 3, 4, 6, 7, 9, 10],
+            Self::Spanish => &[0, 1, 3, 4, 5, 6, 8, 10],
+            Self::MajorLocrian => &[0, 2, 4, 5, 6, 8, 10],
+            Self::SuperLocrian => &[0, 1, 3, 4, 6, 8, 10],
+            Self::DorianB2 => &[0, 1, 3, 5, 7, 9, 10],
+            Self::LydianAugmented => &[0, 2, 4, 6, 8, 9, 11],
+            Self::LydianDominant => &[0, 2, 4, 6, 7, 9, 10],
+            Self::DoubleHarmonicMajor => &[0, 1, 4, 5, 7, 8, 11],
+            Self::Lydian26 => &[0, 3, 4, 6, 7, 10, 11],
+            Self::Ultraphrygian => &[0, 1, 3, 4, 7, 8, 9],
+            Self::HungarianMinor => &[0, 2, 3, 6, 7, 8, 11],
+            Self::Oriental => &[0, 1, 4, 5, 6, 9, 10],
+            Self::Ionian25 => &[0, 3, 4, 5, 8, 9, 11],
+            Self::LocrianBb3Bb7 => &[0, 1, 2, 5, 6, 8, 9],
+        }
+    }
+}
@@ -27,6 +27,34 @@ pub enum Speed {
     X1B8,
 }
 
+impl Speed {
+    /// Returns how many times faster than `1x` the steps advance.
+    pub const fn factor(self) -> f32 {
+        match self {
+            Self::X1 => 1.0,
+            Self::X2 => 2.0,
+            Self::X3B2 => 1.5,
+            Self::X3B4 => 0.75,
+            Self::X1B2 => 0.5,
+            Self::X1B4 => 0.25,
+            Self::X1B8 => 0.125,
+        }
+    }
+
+    /// Returns the length of a step in micro timing units of `1/384` of a bar.
+    pub const fn step_length(self) -> usize {
+        match self {
+            Self::X2 => 12,
+            Self::X3B2 => 16,
+            Self::X1 => 24,
+            Self::X3B4 => 32,
+            Self::X1B2 => 48,
+            Self::X1B4 => 96,
+            Self::X1B8 => 192,
+        }
+    }
+}
+
 impl TryFrom<&str> for Speed {
     type Error = ConversionError;
     fn try_from(speed: &str) -> Result<Self, Self::Error> {
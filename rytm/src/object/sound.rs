@@ -1,8 +1,16 @@
 pub(crate) mod de;
 /// All structures related to machines and their parameters.
 pub mod machine;
+/// Holds the policies for the parameter locks of the synth parameters when the machine of a sound changes.
+pub mod machine_change;
+/// Interpolation between sounds which use the same machine.
+pub mod morph;
 /// Holds the page settings of the sound. Like `[AMP]`, `[FLT]`, `[LFO]`, `[SAMP]` on the device.
 pub mod page;
+/// Holds the identifiers of the parameters of a sound.
+pub mod parameter;
+/// Seeded randomization of the parameters of a sound within constraints.
+pub mod randomizer;
 /// Holds the structures which represent the settings of the sound.
 pub mod settings;
 /// Types which are relevant to sounds.
@@ -21,7 +29,9 @@ use crate::{
     error::{RytmError, SysexConversionError},
     impl_sysex_compatible,
     object::types::ObjectName,
-    sysex::{SysexCompatible, SysexMeta, SysexType, SOUND_SYSEX_SIZE},
+    sysex::{
+        decode_sysex_response_to_raw, SysexCompatible, SysexMeta, SysexType, SOUND_SYSEX_SIZE,
+    },
     util::{arc_mutex_owner, assemble_u32_from_u8_array_be},
     AnySysexType, ParameterError,
 };
@@ -172,6 +182,58 @@ impl Sound {
             .clone_from(&object.machine_parameters);
     }
 
+    /// Replaces the name and the data of this sound with the ones of another sound.
+    ///
+    /// The index, the track assignment and the link to the parameter lock pool of this sound are kept.
+    pub(crate) fn replace_with(&mut self, sound: &Self) -> Result<(), RytmError> {
+        let machine_type = sound.machine_type();
+        if let Some(assigned_track) = self.assigned_track() {
+            if !crate::util::is_machine_compatible_for_track(assigned_track, machine_type) {
+                return Err(ParameterError::Compatibility {
+                    value: machine_type.to_string(),
+                    parameter_name: "Machine".to_string(),
+                    reason: Some(format!(
+                        "Given machine {machine_type} is not compatible for track {assigned_track}"
+                    )),
+                }
+                .into());
+            }
+        }
+
+        self.copy_data_from(sound);
+        self.name = sound.name;
+        self.accent_level = sound.accent_level;
+        self.def_note = sound.def_note;
+        self.__unknown = sound.__unknown;
+
+        self.machine_parameters = machine_type.into();
+        if machine_type != MachineType::Unset {
+            self.set_machine_parameters_from_raw_sound(&ar_sound_t::from(sound))?;
+        }
+        Ok(())
+    }
+
+    /// Makes a sound from a sound sysex message, for example one read from a `.syx` file.
+    ///
+    /// Depending on the message the sound is a pool sound or a work buffer sound, it is not linked to any parameter lock pool.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the message is not a complete sound sysex message.
+    pub fn try_from_sysex(sysex: &[u8]) -> Result<Self, RytmError> {
+        if sysex.len() < 2 || sysex[0] != 0xF0 || sysex[sysex.len() - 1] != 0xF7 {
+            return Err(SysexConversionError::NotASysexMsg.into());
+        }
+
+        let (raw, meta) = decode_sysex_response_to_raw(sysex)?;
+        if meta.object_type()? != SysexType::Sound {
+            return Err(SysexConversionError::NotASound.into());
+        }
+
+        let raw_sound: &ar_sound_t = unsafe { &*raw.as_ptr().cast::<ar_sound_t>() };
+        Self::try_from_raw(meta, raw_sound, None)
+    }
+
     // The panics in this function should be basically unreachable when this function is used correctly.
     pub(crate) fn try_from_raw(
         sysex_meta: SysexMeta,
@@ -528,6 +590,8 @@ impl Sound {
 
     /// Sets the machine type of the sound.
     ///
+    /// Parameter locks of the synth parameters are left as they are, see [`Sound::set_machine_type_with_policy`] to clear or remap them.
+    ///
     /// # Errors
     ///
     /// Not every machine type could be set for every sound if they're assigned to a track.
@@ -552,6 +616,12 @@ impl Sound {
 
         self.settings_mut().machine_type = machine_type;
         self.machine_parameters = machine_type.into();
+
+        // Keep the assigned track and the link to the parameter lock pool for the new machine parameters.
+        if machine_type != MachineType::Unset {
+            let raw_sound = ar_sound_t::from(&*self);
+            self.set_machine_parameters_from_raw_sound(&raw_sound)?;
+        }
         Ok(())
     }
 
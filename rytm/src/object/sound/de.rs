@@ -44,8 +44,8 @@ impl<'de> Deserialize<'de> for Sound {
                 let mut __unknown = None;
                 let mut parameter_lock_pool: Option<Option<Arc<Mutex<ParameterLockPool>>>> = None;
 
-                while let Some(key) = map.next_key()? {
-                    match key {
+                while let Some(key) = map.next_key::<String>()? {
+                    match key.as_str() {
                         "sysex_meta" => sysex_meta = Some(map.next_value()?),
                         "version" => version = Some(map.next_value()?),
                         "index" => index = Some(map.next_value()?),
@@ -77,7 +77,7 @@ impl<'de> Deserialize<'de> for Sound {
                                 parameter_lock_pool = Some(None);
                             }
                         }
-                        _ => return Err(V::Error::unknown_field(key, FIELDS)),
+                        _ => return Err(V::Error::unknown_field(&key, FIELDS)),
                     }
                 }
 
@@ -130,3 +130,18 @@ impl<'de> Deserialize<'de> for Sound {
         deserializer.deserialize_struct("Sound", FIELDS, SoundVisitor)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::object::sound::{types::MachineType, Sound};
+
+    #[test]
+    fn sounds_round_trip_with_owned_keys() {
+        let mut sound = Sound::try_default(0).unwrap();
+        sound.set_machine_type(MachineType::BdHard).unwrap();
+        let value = serde_json::to_value(&sound).unwrap();
+
+        let copy: Sound = serde_json::from_value(value.clone()).unwrap();
+        assert_eq!(serde_json::to_value(&copy).unwrap(), value);
+    }
+}
@@ -49,6 +49,8 @@ mod hh_lab;
 mod oh_classic;
 /// Oh Metallic machine parameters.
 mod oh_metallic;
+/// Synth parameter descriptions of the machines.
+mod parameter;
 /// Rs Classic machine parameters.
 mod rs_classic;
 /// Rs Hard machine parameters.
@@ -101,6 +103,7 @@ pub use hh_basic::*;
 pub use hh_lab::*;
 pub use oh_classic::*;
 pub use oh_metallic::*;
+pub use parameter::*;
 use parking_lot::Mutex;
 pub use rs_classic::*;
 pub use rs_hard::*;
@@ -436,6 +439,92 @@ impl MachineParameters {
         }
     }
 
+    /// Gets the value of a synth parameter by its number, see [`MachineType::parameters`].
+    ///
+    /// Returns `None` if the machine does not use the synth parameter.
+    pub(crate) fn get_parameter(&self, number: usize) -> Option<f32> {
+        match self {
+            Self::BdHard(bd_hard) => bd_hard.get_parameter(number),
+            Self::BdClassic(bd_classic) => bd_classic.get_parameter(number),
+            Self::BdAcoustic(bd_acoustic) => bd_acoustic.get_parameter(number),
+            Self::BdFm(bd_fm) => bd_fm.get_parameter(number),
+            Self::BdPlastic(bd_plastic) => bd_plastic.get_parameter(number),
+            Self::BdSilky(bd_silky) => bd_silky.get_parameter(number),
+            Self::BdSharp(bd_sharp) => bd_sharp.get_parameter(number),
+            Self::BtClassic(bt_classic) => bt_classic.get_parameter(number),
+            Self::CbClassic(cb_classic) => cb_classic.get_parameter(number),
+            Self::CbMetallic(cb_metallic) => cb_metallic.get_parameter(number),
+            Self::ChClassic(ch_classic) => ch_classic.get_parameter(number),
+            Self::ChMetallic(ch_metallic) => ch_metallic.get_parameter(number),
+            Self::CpClassic(cp_classic) => cp_classic.get_parameter(number),
+            Self::CyClassic(cy_classic) => cy_classic.get_parameter(number),
+            Self::CyMetallic(cy_metallic) => cy_metallic.get_parameter(number),
+            Self::CyRide(cy_ride) => cy_ride.get_parameter(number),
+            Self::HhBasic(hh_basic) => hh_basic.get_parameter(number),
+            Self::HhLab(hh_lab) => hh_lab.get_parameter(number),
+            Self::OhClassic(oh_classic) => oh_classic.get_parameter(number),
+            Self::OhMetallic(oh_metallic) => oh_metallic.get_parameter(number),
+            Self::RsClassic(rs_classic) => rs_classic.get_parameter(number),
+            Self::RsHard(rs_hard) => rs_hard.get_parameter(number),
+            Self::SdAcoustic(sd_acoustic) => sd_acoustic.get_parameter(number),
+            Self::SdClassic(sd_classic) => sd_classic.get_parameter(number),
+            Self::SdFm(sd_fm) => sd_fm.get_parameter(number),
+            Self::SdHard(sd_hard) => sd_hard.get_parameter(number),
+            Self::SdNatural(sd_natural) => sd_natural.get_parameter(number),
+            Self::SyChip(sy_chip) => sy_chip.get_parameter(number),
+            Self::SyDualVco(sy_dual_vco) => sy_dual_vco.get_parameter(number),
+            Self::SyRaw(sy_raw) => sy_raw.get_parameter(number),
+            Self::UtImpulse(ut_impulse) => ut_impulse.get_parameter(number),
+            Self::UtNoise(ut_noise) => ut_noise.get_parameter(number),
+            Self::XtClassic(xt_classic) => xt_classic.get_parameter(number),
+            Self::Unset | Self::Disable => None,
+        }
+    }
+
+    /// Sets a validated value of a synth parameter by its number through the setter of the machine, see [`MachineType::parameters`].
+    pub(crate) fn set_parameter(&mut self, number: usize, value: f32) -> Result<(), RytmError> {
+        match self {
+            Self::BdHard(bd_hard) => bd_hard.set_parameter(number, value),
+            Self::BdClassic(bd_classic) => bd_classic.set_parameter(number, value),
+            Self::BdAcoustic(bd_acoustic) => bd_acoustic.set_parameter(number, value),
+            Self::BdFm(bd_fm) => bd_fm.set_parameter(number, value),
+            Self::BdPlastic(bd_plastic) => bd_plastic.set_parameter(number, value),
+            Self::BdSilky(bd_silky) => bd_silky.set_parameter(number, value),
+            Self::BdSharp(bd_sharp) => bd_sharp.set_parameter(number, value),
+            Self::BtClassic(bt_classic) => bt_classic.set_parameter(number, value),
+            Self::CbClassic(cb_classic) => cb_classic.set_parameter(number, value),
+            Self::CbMetallic(cb_metallic) => cb_metallic.set_parameter(number, value),
+            Self::ChClassic(ch_classic) => ch_classic.set_parameter(number, value),
+            Self::ChMetallic(ch_metallic) => ch_metallic.set_parameter(number, value),
+            Self::CpClassic(cp_classic) => cp_classic.set_parameter(number, value),
+            Self::CyClassic(cy_classic) => cy_classic.set_parameter(number, value),
+            Self::CyMetallic(cy_metallic) => cy_metallic.set_parameter(number, value),
+            Self::CyRide(cy_ride) => cy_ride.set_parameter(number, value),
+            Self::HhBasic(hh_basic) => hh_basic.set_parameter(number, value),
+            Self::HhLab(hh_lab) => hh_lab.set_parameter(number, value),
+            Self::OhClassic(oh_classic) => oh_classic.set_parameter(number, value),
+            Self::OhMetallic(oh_metallic) => oh_metallic.set_parameter(number, value),
+            Self::RsClassic(rs_classic) => rs_classic.set_parameter(number, value),
+            Self::RsHard(rs_hard) => rs_hard.set_parameter(number, value),
+            Self::SdAcoustic(sd_acoustic) => sd_acoustic.set_parameter(number, value),
+            Self::SdClassic(sd_classic) => sd_classic.set_parameter(number, value),
+            Self::SdFm(sd_fm) => sd_fm.set_parameter(number, value),
+            Self::SdHard(sd_hard) => sd_hard.set_parameter(number, value),
+            Self::SdNatural(sd_natural) => sd_natural.set_parameter(number, value),
+            Self::SyChip(sy_chip) => sy_chip.set_parameter(number, value),
+            Self::SyDualVco(sy_dual_vco) => sy_dual_vco.set_parameter(number, value),
+            Self::SyRaw(sy_raw) => sy_raw.set_parameter(number, value),
+            Self::UtImpulse(ut_impulse) => ut_impulse.set_parameter(number, value),
+            Self::UtNoise(ut_noise) => ut_noise.set_parameter(number, value),
+            Self::XtClassic(xt_classic) => xt_classic.set_parameter(number, value),
+            Self::Unset | Self::Disable => Err(ParameterError::Range {
+                value: number.to_string(),
+                parameter_name: "number".into(),
+            }
+            .into()),
+        }
+    }
+
     pub(crate) fn link_parameter_lock_pool(
         &mut self,
         parameter_lock_pool: Arc<Mutex<ParameterLockPool>>,
@@ -105,7 +105,7 @@ impl From<BdSharpWaveform> for u8 {
     hld: "0..=127" #4,
     swt: "0..=127" #5,
     swd: "0..=127" #6,
-    // wav #7
+    wav: "0..=11" #7 choice, // manual impl
     tic: "0..=127" #8,
 )]
 /// Parameters for the `BdSharp` machine.
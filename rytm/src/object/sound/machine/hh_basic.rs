@@ -20,7 +20,7 @@ use std::sync::Arc;
     dec: "0..=127" #3,
     ton:  "-64..=63" #4,
     trd: "0..=127" #5,
-    // rst #6 
+    rst: "0..=1" #6 toggle, // manual impl
     // Unavailable #7
     // Unavailable #8
 )]
@@ -83,7 +83,7 @@ impl HhBasicParameters {
             pool.lock().set_basic_plock(
                 trig_index,
                 assigned_track as u8,
-                rytm_sys::AR_PLOCK_TYPE_MP6 as u8,
+                rytm_sys::AR_PLOCK_TYPE_MP5 as u8,
                 rst as u8,
             )?;
             return Ok(());
@@ -98,7 +98,7 @@ impl HhBasicParameters {
             let rst = pool.lock().get_basic_plock(
                 trig_index,
                 assigned_track as u8,
-                rytm_sys::AR_PLOCK_TYPE_MP6 as u8,
+                rytm_sys::AR_PLOCK_TYPE_MP5 as u8,
             );
             if let Some(rst) = rst {
                 return Ok(Some(rst != 0));
@@ -115,7 +115,7 @@ impl HhBasicParameters {
             pool.lock().clear_basic_plock(
                 trig_index,
                 assigned_track as u8,
-                rytm_sys::AR_PLOCK_TYPE_MP6 as u8,
+                rytm_sys::AR_PLOCK_TYPE_MP5 as u8,
             );
             return Ok(());
         }
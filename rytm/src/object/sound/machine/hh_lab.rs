@@ -6,11 +6,21 @@ use crate::{
 };
 use derivative::Derivative;
 use parking_lot::Mutex;
-use rytm_rs_macro::parameter_range;
+use rytm_rs_macro::{machine_parameters, parameter_range};
 use rytm_sys::ar_sound_t;
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 
+#[machine_parameters(
+    lev: "0..=127" #1,
+    osc1: "0..=16256" #2 wide, // manual impl
+    dec: "0..=127" #3,
+    osc2: "0..=16256" #4 wide, // manual impl
+    osc3: "0..=16256" #5 wide, // manual impl
+    osc4: "0..=16256" #6 wide, // manual impl
+    osc5: "0..=16256" #7 wide, // manual impl
+    osc6: "0..=16256" #8 wide, // manual impl
+)]
 /// Parameters for the `HhLab` machine.
 #[derive(Derivative, Clone, Serialize, Deserialize)]
 #[derivative(Debug)]
@@ -53,9 +63,8 @@ impl HhLabParameters {
     }
 
     pub(crate) fn apply_to_raw_sound(&self, raw_sound: &mut ar_sound_t) {
-        raw_sound.synth_param_1 = to_s_u16_t_union_a((self.lev as u16) << 8);
+        self.apply_to_raw_sound_values(raw_sound);
         raw_sound.synth_param_2 = to_s_u16_t_union_a(self.osc1);
-        raw_sound.synth_param_3 = to_s_u16_t_union_a((self.dec as u16) << 8);
         raw_sound.synth_param_4 = to_s_u16_t_union_a(self.osc2);
         raw_sound.synth_param_5 = to_s_u16_t_union_a(self.osc3);
         raw_sound.synth_param_6 = to_s_u16_t_union_a(self.osc4);
@@ -63,15 +72,6 @@ impl HhLabParameters {
         raw_sound.synth_param_8 = to_s_u16_t_union_a(self.osc6);
     }
 
-    /// Sets the `lev` parameter.
-    ///
-    /// Range: `0..=127`
-    #[parameter_range(range = "lev:0..=127")]
-    pub fn set_lev(&mut self, lev: usize) -> Result<(), RytmError> {
-        self.lev = lev as u8;
-        Ok(())
-    }
-
     /// Sets the `osc1` parameter.
     ///
     /// Range: `0..=16256`
@@ -81,15 +81,6 @@ impl HhLabParameters {
         Ok(())
     }
 
-    /// Sets the `dec` parameter.
-    ///
-    /// Range: `0..=127`
-    #[parameter_range(range = "dec:0..=127")]
-    pub fn set_dec(&mut self, dec: usize) -> Result<(), RytmError> {
-        self.dec = dec as u8;
-        Ok(())
-    }
-
     /// Sets the `osc2` parameter.
     ///
     /// Range: `0..=16256`
@@ -135,13 +126,6 @@ impl HhLabParameters {
         Ok(())
     }
 
-    /// Returns the `lev` parameter.
-    ///
-    /// Range: `0..=127`
-    pub const fn get_lev(&self) -> usize {
-        self.lev as usize
-    }
-
     /// Returns the `osc1` parameter.
     ///
     /// Range: `0..=16256`
@@ -149,13 +133,6 @@ impl HhLabParameters {
         self.osc1 as usize
     }
 
-    /// Returns the `dec` parameter.
-    ///
-    /// Range: `0..=127`
-    pub const fn get_dec(&self) -> usize {
-        self.dec as usize
-    }
-
     /// Returns the `osc2` parameter.
     ///
     /// Range: `0..=16256`
@@ -214,62 +191,6 @@ impl HhLabParameters {
 }
 
 impl HhLabParameters {
-    /// Sets the parameter lock for the `lev` parameter.
-    ///
-    /// Range: `0..=127`
-    #[parameter_range(range = "lev:0..=127")]
-    pub fn plock_set_lev(&self, lev: usize, trig_index: usize) -> Result<(), RytmError> {
-        if let Some(ref pool) = self.parameter_lock_pool {
-            let assigned_track = self.assigned_track.ok_or(OrphanTrig)?;
-
-            pool.lock().set_basic_plock(
-                trig_index,
-                assigned_track as u8,
-                rytm_sys::AR_PLOCK_TYPE_MP0 as u8,
-                lev as u8,
-            )?;
-
-            return Ok(());
-        }
-        Err(OrphanTrig)
-    }
-
-    /// Gets the parameter lock for the `lev` parameter.
-    ///
-    /// Range: `0..=127`
-    pub fn plock_get_lev(&self, trig_index: usize) -> Result<Option<usize>, RytmError> {
-        if let Some(ref pool) = self.parameter_lock_pool {
-            let assigned_track = self.assigned_track.ok_or(OrphanTrig)?;
-
-            let lev = pool.lock().get_basic_plock(
-                trig_index,
-                assigned_track as u8,
-                rytm_sys::AR_PLOCK_TYPE_MP0 as u8,
-            );
-
-            if let Some(lev) = lev {
-                return Ok(Some(lev as usize));
-            }
-
-            return Ok(None);
-        }
-        Err(OrphanTrig)
-    }
-
-    /// Clears the parameter lock for the `lev` parameter if set.
-    pub fn plock_clear_lev(&self, trig_index: usize) -> Result<(), RytmError> {
-        if let Some(ref pool) = self.parameter_lock_pool {
-            let assigned_track = self.assigned_track.ok_or(OrphanTrig)?;
-            pool.lock().clear_basic_plock(
-                trig_index,
-                assigned_track as u8,
-                rytm_sys::AR_PLOCK_TYPE_MP0 as u8,
-            );
-            return Ok(());
-        }
-        Err(OrphanTrig)
-    }
-
     /// Sets the parameter lock for the `osc1` parameter.
     ///
     /// Range: `0..=16256`
@@ -326,62 +247,6 @@ impl HhLabParameters {
         Err(OrphanTrig)
     }
 
-    /// Sets the parameter lock for the `dec` parameter.
-    ///
-    /// Range: `0..=127`
-    #[parameter_range(range = "dec:0..=127")]
-    pub fn plock_set_dec(&self, dec: usize, trig_index: usize) -> Result<(), RytmError> {
-        if let Some(ref pool) = self.parameter_lock_pool {
-            let assigned_track = self.assigned_track.ok_or(OrphanTrig)?;
-
-            pool.lock().set_basic_plock(
-                trig_index,
-                assigned_track as u8,
-                rytm_sys::AR_PLOCK_TYPE_MP2 as u8,
-                dec as u8,
-            )?;
-
-            return Ok(());
-        }
-        Err(OrphanTrig)
-    }
-
-    /// Gets the parameter lock for the `dec` parameter.
-    ///
-    /// Range: `0..=127`
-    pub fn plock_get_dec(&self, trig_index: usize) -> Result<Option<usize>, RytmError> {
-        if let Some(ref pool) = self.parameter_lock_pool {
-            let assigned_track = self.assigned_track.ok_or(OrphanTrig)?;
-
-            let dec = pool.lock().get_basic_plock(
-                trig_index,
-                assigned_track as u8,
-                rytm_sys::AR_PLOCK_TYPE_MP2 as u8,
-            );
-
-            if let Some(dec) = dec {
-                return Ok(Some(dec as usize));
-            }
-
-            return Ok(None);
-        }
-        Err(OrphanTrig)
-    }
-
-    /// Clears the parameter lock for the `dec` parameter if set.
-    pub fn plock_clear_dec(&self, trig_index: usize) -> Result<(), RytmError> {
-        if let Some(ref pool) = self.parameter_lock_pool {
-            let assigned_track = self.assigned_track.ok_or(OrphanTrig)?;
-            pool.lock().clear_basic_plock(
-                trig_index,
-                assigned_track as u8,
-                rytm_sys::AR_PLOCK_TYPE_MP2 as u8,
-            );
-            return Ok(());
-        }
-        Err(OrphanTrig)
-    }
-
     /// Sets the parameter lock for the `osc2` parameter.
     ///
     /// Range: `0..=16256`
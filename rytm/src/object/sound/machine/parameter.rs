@@ -0,0 +1,397 @@
+// All casts in this file are intended or safe within the context of this library.
+//
+// One can change `allow` to `warn` to review them if necessary.
+#![allow(
+    clippy::cast_possible_truncation,
+    clippy::cast_sign_loss,
+    clippy::cast_possible_wrap,
+    clippy::cast_precision_loss
+)]
+#![allow(clippy::enum_glob_use, clippy::match_same_arms, clippy::too_many_lines)]
+
+use super::*;
+use crate::{
+    error::{ParameterError, RytmError},
+    object::{
+        pattern::{plock::RawPlockValue, track::trig::parameter::PlockValueKind},
+        sound::types::MachineType,
+    },
+    util::{
+        get_u16_min_max_from_float_range, i8_to_u8_midpoint_of_u8_input_range, scale_f32_to_u16,
+        scale_u16_to_f32, u8_to_i8_midpoint_of_u8_input_range,
+    },
+};
+use serde::{Deserialize, Serialize};
+use std::ops::RangeInclusive;
+
+/// How the value of a synth parameter is stored in a parameter lock.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Encoding {
+    /// A single byte holding the value.
+    Byte,
+    /// A single byte holding the value around the midpoint of `0..=127`.
+    Bipolar,
+    /// Two bytes holding the value scaled from the range of the parameter.
+    Scaled,
+    /// Two bytes holding the value as it is.
+    Wide,
+}
+
+/// The semantic role of a synth parameter, shared by the parameters of different machines which do the same thing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum ParameterRole {
+    Level,
+    Tune,
+    Decay,
+    Hold,
+    SweepTime,
+    SweepDepth,
+    Snap,
+    /// The click at the start of the sound, `tic`, `tra`, `clk` or `imp` depending on the machine.
+    Transient,
+    NoiseDecay,
+    NoiseLevel,
+    Detune,
+    Balance,
+    Tone,
+    Color,
+    Body,
+    Attack,
+    LowPassFrequency,
+    HighPassFrequency,
+    Resonance,
+    FmAmount,
+    FmTune,
+    FmDecay,
+    FmSweep,
+    /// A waveform selection, values are only carried over when the other machine accepts them.
+    Waveform,
+    /// A model selection, values are only carried over when the other machine accepts them.
+    Type,
+}
+
+impl ParameterRole {
+    /// Checks if the values of the role select from a list rather than set an amount.
+    pub const fn is_discrete(self) -> bool {
+        matches!(self, Self::Waveform | Self::Type)
+    }
+}
+
+/// A synth parameter of a machine.
+///
+/// Values are in the numeric representation of the parameter, see [`MachineParameter::kind`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MachineParameter {
+    name: &'static str,
+    number: usize,
+    kind: PlockValueKind,
+    min: f32,
+    max: f32,
+    encoding: Encoding,
+}
+
+impl MachineParameter {
+    /// A parameter stored in a single byte.
+    pub(crate) const fn unsigned(name: &'static str, number: usize, max: u8) -> Self {
+        Self {
+            name,
+            number,
+            kind: PlockValueKind::Integer,
+            min: 0.0,
+            max: max as f32,
+            encoding: Encoding::Byte,
+        }
+    }
+
+    /// A parameter stored in a single byte around the midpoint of `0..=127`.
+    pub(crate) const fn bipolar(name: &'static str, number: usize, min: i8, max: i8) -> Self {
+        Self {
+            name,
+            number,
+            kind: PlockValueKind::Integer,
+            min: min as f32,
+            max: max as f32,
+            encoding: Encoding::Bipolar,
+        }
+    }
+
+    /// A parameter stored in two bytes, scaled from its range.
+    pub(crate) const fn scaled(name: &'static str, number: usize, min: f32, max: f32) -> Self {
+        Self {
+            name,
+            number,
+            kind: PlockValueKind::Float,
+            min,
+            max,
+            encoding: Encoding::Scaled,
+        }
+    }
+
+    /// A parameter stored in two bytes as it is.
+    pub(crate) const fn wide(name: &'static str, number: usize, max: u16) -> Self {
+        Self {
+            name,
+            number,
+            kind: PlockValueKind::Integer,
+            min: 0.0,
+            max: max as f32,
+            encoding: Encoding::Wide,
+        }
+    }
+
+    /// A parameter which selects a variant of an enum with a `u8` representation.
+    pub(crate) const fn choice(name: &'static str, number: usize, max: u8) -> Self {
+        Self {
+            name,
+            number,
+            kind: PlockValueKind::Choice,
+            min: 0.0,
+            max: max as f32,
+            encoding: Encoding::Byte,
+        }
+    }
+
+    /// A parameter which is on or off.
+    pub(crate) const fn toggle(name: &'static str, number: usize) -> Self {
+        Self {
+            name,
+            number,
+            kind: PlockValueKind::Toggle,
+            min: 0.0,
+            max: 1.0,
+            encoding: Encoding::Byte,
+        }
+    }
+
+    /// Returns the name of the parameter.
+    ///
+    /// The names follow the setters of the machine parameter types, e.g. `tun` for [`BdHardParameters::set_tun`](super::BdHardParameters::set_tun).
+    pub const fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Returns the semantic role of the parameter, `None` if the parameter is particular to its machine.
+    pub fn role(&self) -> Option<ParameterRole> {
+        use ParameterRole::*;
+        Some(match self.name {
+            "lev" => Level,
+            "tun" | "t1" => Tune,
+            "dec" | "dec1" => Decay,
+            "hld" => Hold,
+            "swt" => SweepTime,
+            "swd" => SweepDepth,
+            "snp" => Snap,
+            "tic" | "tra" | "clk" | "imp" => Transient,
+            "nod" => NoiseDecay,
+            "nol" | "nlev" => NoiseLevel,
+            "det" => Detune,
+            "bal" => Balance,
+            "ton" => Tone,
+            "col" => Color,
+            "bdy" => Body,
+            "atk" => Attack,
+            "lpf" => LowPassFrequency,
+            "hpf" => HighPassFrequency,
+            "res" | "lpq" => Resonance,
+            "fma" => FmAmount,
+            "fmt" => FmTune,
+            "fmd" => FmDecay,
+            "fms" => FmSweep,
+            "wav" => Waveform,
+            "typ" => Type,
+            _ => return None,
+        })
+    }
+
+    /// Converts a value of this parameter to a value of an equivalent parameter of another machine.
+    ///
+    /// - Values of discrete parameters, choices and toggles are carried over as they are when the other parameter accepts them.
+    /// - Values of parameters which are both in semitones are carried over as they are and clamped to the other range.
+    /// - Other values are rescaled from the range of this parameter to the range of the other one.
+    ///
+    /// Returns the converted value and `true` if it is clamped, `None` if the value can not be carried over.
+    pub fn convert_value(&self, value: f32, to: &Self) -> Option<(f32, bool)> {
+        let discrete = self.role().is_some_and(ParameterRole::is_discrete)
+            || matches!(self.kind, PlockValueKind::Choice | PlockValueKind::Toggle)
+            || matches!(to.kind, PlockValueKind::Choice | PlockValueKind::Toggle);
+        if discrete {
+            return to.validate(value).ok().map(|value| (value, false));
+        }
+
+        if self.kind == PlockValueKind::Float && to.kind == PlockValueKind::Float {
+            let clamped = !to.range().contains(&value);
+            return to
+                .validate(value.clamp(to.min, to.max))
+                .ok()
+                .map(|value| (value, clamped));
+        }
+
+        let span = self.max - self.min;
+        let position = if span > 0.0 {
+            ((value - self.min) / span).clamp(0.0, 1.0)
+        } else {
+            0.0
+        };
+        to.validate(position.mul_add(to.max - to.min, to.min))
+            .ok()
+            .map(|value| (value, false))
+    }
+
+    /// Returns the number of the synth parameter which this parameter occupies.
+    ///
+    /// Range `1..=8`
+    pub const fn number(&self) -> usize {
+        self.number
+    }
+
+    /// Returns how the numeric value of the parameter is interpreted.
+    ///
+    /// Choices are the raw `u8` values of the enum types of the parameters.
+    pub const fn kind(&self) -> PlockValueKind {
+        self.kind
+    }
+
+    /// Returns the range of the numeric value of the parameter.
+    pub const fn range(&self) -> RangeInclusive<f32> {
+        RangeInclusive::new(self.min, self.max)
+    }
+
+    /// Checks if a value is accepted by this parameter.
+    ///
+    /// Values of parameters which are not [`PlockValueKind::Float`] are rounded before checking.
+    pub fn accepts(&self, value: f32) -> bool {
+        self.validate(value).is_ok()
+    }
+
+    /// Rounds the value if necessary and checks the range.
+    pub(crate) fn validate(&self, value: f32) -> Result<f32, RytmError> {
+        let value = if self.kind == PlockValueKind::Float {
+            value
+        } else {
+            value.round()
+        };
+
+        if value.is_finite() && self.range().contains(&value) {
+            Ok(value)
+        } else {
+            Err(ParameterError::Range {
+                value: value.to_string(),
+                parameter_name: self.name.into(),
+            }
+            .into())
+        }
+    }
+
+    /// Returns the parameter lock type of the synth parameter.
+    pub(crate) const fn plock_type(&self) -> u8 {
+        (rytm_sys::AR_PLOCK_TYPE_MP0 as usize + self.number - 1) as u8
+    }
+
+    /// Encodes a validated value the way the plock setters of the machines do.
+    pub(crate) fn encode(&self, value: f32) -> RawPlockValue {
+        match self.encoding {
+            Encoding::Byte => RawPlockValue::Basic(value as u8),
+            Encoding::Bipolar => {
+                RawPlockValue::Basic(i8_to_u8_midpoint_of_u8_input_range(value as i8, 0, 127))
+            }
+            Encoding::Scaled => {
+                let (min, max) = get_u16_min_max_from_float_range(self.min, self.max);
+                RawPlockValue::Compound(scale_f32_to_u16(value, self.min, self.max, min, max))
+            }
+            Encoding::Wide => RawPlockValue::Compound(value as u16),
+        }
+    }
+
+    /// Decodes a value stored in the parameter lock pool.
+    pub(crate) fn decode(&self, value: RawPlockValue) -> f32 {
+        match (self.encoding, value) {
+            (Encoding::Bipolar, RawPlockValue::Basic(value)) => {
+                f32::from(u8_to_i8_midpoint_of_u8_input_range(value, 0, 127))
+            }
+            (Encoding::Scaled, RawPlockValue::Compound(value)) => {
+                let (min, max) = get_u16_min_max_from_float_range(self.min, self.max);
+                scale_u16_to_f32(value, min, max, self.min, self.max)
+            }
+            (_, RawPlockValue::Basic(value)) => f32::from(value),
+            (_, RawPlockValue::Compound(value)) => f32::from(value),
+        }
+    }
+
+    /// Checks if the parameter is stored in two slots of the parameter lock pool.
+    pub(crate) const fn is_compound(&self) -> bool {
+        matches!(self.encoding, Encoding::Scaled | Encoding::Wide)
+    }
+}
+
+impl MachineType {
+    /// Returns the synth parameters of this machine in the order of their numbers.
+    ///
+    /// Synth parameters which the machine does not use are `None`.
+    pub const fn parameters(self) -> [Option<MachineParameter>; 8] {
+        use MachineType::*;
+        match self {
+            BdHard => BdHardParameters::PARAMETERS,
+            BdClassic => BdClassicParameters::PARAMETERS,
+            SdHard => SdHardParameters::PARAMETERS,
+            SdClassic => SdClassicParameters::PARAMETERS,
+            RsHard => RsHardParameters::PARAMETERS,
+            RsClassic => RsClassicParameters::PARAMETERS,
+            CpClassic => CpClassicParameters::PARAMETERS,
+            BtClassic => BtClassicParameters::PARAMETERS,
+            XtClassic => XtClassicParameters::PARAMETERS,
+            ChClassic => ChClassicParameters::PARAMETERS,
+            OhClassic => OhClassicParameters::PARAMETERS,
+            CyClassic => CyClassicParameters::PARAMETERS,
+            CbClassic => CbClassicParameters::PARAMETERS,
+            BdFm => BdFmParameters::PARAMETERS,
+            SdFm => SdFmParameters::PARAMETERS,
+            UtNoise => UtNoiseParameters::PARAMETERS,
+            UtImpulse => UtImpulseParameters::PARAMETERS,
+            ChMetallic => ChMetallicParameters::PARAMETERS,
+            OhMetallic => OhMetallicParameters::PARAMETERS,
+            CyMetallic => CyMetallicParameters::PARAMETERS,
+            CbMetallic => CbMetallicParameters::PARAMETERS,
+            BdPlastic => BdPlasticParameters::PARAMETERS,
+            BdSilky => BdSilkyParameters::PARAMETERS,
+            SdNatural => SdNaturalParameters::PARAMETERS,
+            HhBasic => HhBasicParameters::PARAMETERS,
+            CyRide => CyRideParameters::PARAMETERS,
+            BdSharp => BdSharpParameters::PARAMETERS,
+            SyDualVco => SyDualVcoParameters::PARAMETERS,
+            SyChip => SyChipParameters::PARAMETERS,
+            BdAcoustic => BdAcousticParameters::PARAMETERS,
+            SdAcoustic => SdAcousticParameters::PARAMETERS,
+            SyRaw => SyRawParameters::PARAMETERS,
+            HhLab => HhLabParameters::PARAMETERS,
+            Disable | Unset => [None; 8],
+        }
+    }
+
+    /// Returns the synth parameter of this machine with the given number.
+    ///
+    /// Range `1..=8`, `None` if the number is out of range or the machine does not use the synth parameter.
+    pub const fn parameter(self, number: usize) -> Option<MachineParameter> {
+        if number == 0 || number > 8 {
+            return None;
+        }
+        self.parameters()[number - 1]
+    }
+
+    /// Returns the synth parameter of this machine with the given name.
+    pub fn parameter_by_name(self, name: &str) -> Option<MachineParameter> {
+        self.parameters()
+            .into_iter()
+            .flatten()
+            .find(|parameter| parameter.name == name)
+    }
+
+    /// Returns the synth parameter of this machine which is equivalent to a synth parameter of another machine.
+    ///
+    /// Parameters are equivalent when they have the same [`ParameterRole`], parameters without a role when they have the same name.
+    pub fn equivalent_parameter(self, parameter: &MachineParameter) -> Option<MachineParameter> {
+        let role = parameter.role();
+        self.parameters().into_iter().flatten().find(|candidate| {
+            candidate.role() == role && (role.is_some() || candidate.name == parameter.name)
+        })
+    }
+}
@@ -81,7 +81,7 @@ impl SdHardParameters {
         unsafe {
             Ok(Self {
                 parameter_lock_pool: None,
-                assigned_track: None,
+                assigned_track: track_index,
                 lev: (from_s_u16_t(raw_sound.synth_param_1) >> 8) as u8,
                 tun: scale_u16_to_f32(
                     from_s_u16_t(raw_sound.synth_param_2),
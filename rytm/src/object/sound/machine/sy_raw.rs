@@ -138,7 +138,9 @@ impl From<SyRawWaveform2> for u8 {
  det: "-24.0..=24.0" #4,
  nlev: "0..=127" #5,
  // wav1 manual impl (0=sin,1=asin,2=tri,3=ssaw,4=asaw,5=saw,6=ring)
+ wav1: "0..=6" #6 choice,
  // wav2 manual impl (0=sineA,1=ssawA,2=sineB,3=ssawB)
+ wav2: "0..=3" #7 choice,
  bal: "-64..=63" #8,
 )]
 /// Parameters for the `SyRaw` machine.
@@ -218,7 +220,7 @@ impl SyRawParameters {
             pool.lock().set_basic_plock(
                 trig_index,
                 assigned_track as u8,
-                rytm_sys::AR_PLOCK_TYPE_MP6 as u8,
+                rytm_sys::AR_PLOCK_TYPE_MP5 as u8,
                 wav1.into(),
             )?;
             return Ok(());
@@ -233,7 +235,7 @@ impl SyRawParameters {
             let wav1 = pool.lock().get_basic_plock(
                 trig_index,
                 assigned_track as u8,
-                rytm_sys::AR_PLOCK_TYPE_MP6 as u8,
+                rytm_sys::AR_PLOCK_TYPE_MP5 as u8,
             );
             if let Some(wav1) = wav1 {
                 return Ok(Some(wav1.try_into()?));
@@ -250,7 +252,7 @@ impl SyRawParameters {
             pool.lock().clear_basic_plock(
                 trig_index,
                 assigned_track as u8,
-                rytm_sys::AR_PLOCK_TYPE_MP6 as u8,
+                rytm_sys::AR_PLOCK_TYPE_MP5 as u8,
             );
             return Ok(());
         }
@@ -0,0 +1,364 @@
+// All casts in this file are intended or safe within the context of this library.
+//
+// One can change `allow` to `warn` to review them if necessary.
+#![allow(clippy::cast_possible_truncation)]
+
+use super::{machine::MachineParameter, parameter::SoundParameter, types::MachineType, Sound};
+use crate::{
+    error::RytmError,
+    object::pattern::plock::{describe_plock, ParameterLockPool, RawPlockValue},
+};
+use serde::{Deserialize, Serialize};
+
+/// Parameter lock type of the first synth parameter.
+const FIRST_SYNTH_PARAMETER_PLOCK_TYPE: u8 = rytm_sys::AR_PLOCK_TYPE_MP0 as u8;
+
+/// What happens to the parameter locks of the synth parameters when the machine of a sound changes.
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+pub enum MachineChangePolicy {
+    /// The parameter locks are cleared.
+    Clear,
+    /// The parameter locks are left as they are.
+    ///
+    /// The stored values are interpreted by the synth parameters of the new machine which share their numbers.
+    #[default]
+    KeepRaw,
+    /// The parameter locks are moved to the equivalent synth parameters of the new machine, see [`MachineType::equivalent_parameter`].
+    ///
+    /// Values are converted with [`MachineParameter::convert_value`].
+    /// Parameter locks which do not have an equivalent in the new machine or can not be converted are cleared.
+    Remap,
+}
+
+/// What happened to a parameter lock of a synth parameter when the machine of a sound changed.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum MachinePlockAction {
+    /// The parameter lock is cleared.
+    Cleared,
+    /// The parameter lock is left as it is.
+    Kept,
+    /// The parameter lock is moved to a synth parameter of the new machine.
+    Remapped {
+        /// Number of the synth parameter in the new machine.
+        ///
+        /// Range `1..=8`
+        number: usize,
+        /// Value of the parameter lock in the new machine.
+        value: f32,
+        /// `true` if the value did not fit the range of the new synth parameter and is clamped.
+        clamped: bool,
+    },
+}
+
+/// A parameter lock of a synth parameter which was affected by a change of machine.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MachinePlockChange {
+    /// Index of the trig which holds the parameter lock.
+    pub trig_index: usize,
+    /// Number of the synth parameter in the old machine.
+    ///
+    /// Range `1..=8`
+    pub number: usize,
+    /// Name of the synth parameter in the old machine, `None` if the old machine does not use the synth parameter.
+    pub name: Option<String>,
+    /// Value of the parameter lock in the old machine, `None` if the value can not be interpreted by the old machine.
+    pub value: Option<f32>,
+    /// What happened to the parameter lock.
+    pub action: MachinePlockAction,
+}
+
+/// The outcome of a change of machine.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MachineChangeReport {
+    /// The machine before the change.
+    pub from: MachineType,
+    /// The machine after the change.
+    pub to: MachineType,
+    /// The parameter locks of the synth parameters which were affected, ordered by trig and synth parameter.
+    pub changes: Vec<MachinePlockChange>,
+}
+
+impl Sound {
+    /// Sets the machine type of the sound and applies a policy to the parameter locks of its synth parameters.
+    ///
+    /// The parameter locks are the ones in the parameter lock pool linked to this sound, on the track which this sound is assigned to.
+    /// If this sound is not linked to a parameter lock pool there are no parameter locks to apply the policy to.
+    ///
+    /// # Errors
+    ///
+    /// Returns the errors of [`Sound::set_machine_type`].
+    ///
+    /// If the remapped parameter locks do not fit [`RytmError::ParameterLockMemoryFullWith`] is returned listing the parameter locks which did not fit.
+    ///
+    /// In case of an error this sound and the parameter locks are left untouched.
+    pub fn set_machine_type_with_policy(
+        &mut self,
+        machine_type: MachineType,
+        policy: MachineChangePolicy,
+    ) -> Result<MachineChangeReport, RytmError> {
+        let from = self.machine_type();
+        let mut report = MachineChangeReport {
+            from,
+            to: machine_type,
+            changes: Vec::new(),
+        };
+
+        let (Some(pool), Some(track_index)) =
+            (self.parameter_lock_pool.clone(), self.assigned_track())
+        else {
+            self.set_machine_type(machine_type)?;
+            return Ok(report);
+        };
+
+        let source = pool.lock().clone();
+        let mut staged = source.clone();
+        let mut remapped = Vec::new();
+
+        let mut plocks = source
+            .values()
+            .into_iter()
+            .filter(|&(track, _, plock_type, _)| {
+                usize::from(track) == track_index
+                    && (FIRST_SYNTH_PARAMETER_PLOCK_TYPE..FIRST_SYNTH_PARAMETER_PLOCK_TYPE + 8)
+                        .contains(&plock_type)
+            })
+            .collect::<Vec<_>>();
+        plocks.sort_by_key(|&(_, trig_index, plock_type, _)| (trig_index, plock_type));
+
+        for (_, trig_index, plock_type, raw_value) in plocks {
+            let number = usize::from(plock_type - FIRST_SYNTH_PARAMETER_PLOCK_TYPE) + 1;
+            let parameter = from.parameter(number);
+            let value = parameter
+                .filter(|parameter| {
+                    parameter.is_compound() == matches!(raw_value, RawPlockValue::Compound(_))
+                })
+                .map(|parameter| parameter.decode(raw_value));
+
+            let action = match policy {
+                MachineChangePolicy::KeepRaw => MachinePlockAction::Kept,
+                MachineChangePolicy::Clear => MachinePlockAction::Cleared,
+                MachineChangePolicy::Remap => {
+                    let converted = parameter.zip(value).and_then(|(parameter, value)| {
+                        let equivalent = machine_type.equivalent_parameter(&parameter)?;
+                        let (new_value, clamped) = parameter.convert_value(value, &equivalent)?;
+                        Some((equivalent, new_value, clamped))
+                    });
+                    match converted {
+                        Some((equivalent, new_value, clamped)) => {
+                            remapped.push((trig_index, equivalent, new_value));
+                            MachinePlockAction::Remapped {
+                                number: equivalent.number(),
+                                value: new_value,
+                                clamped,
+                            }
+                        }
+                        None => MachinePlockAction::Cleared,
+                    }
+                }
+            };
+
+            if !matches!(action, MachinePlockAction::Kept) {
+                clear_plock(
+                    &mut staged,
+                    trig_index,
+                    track_index as u8,
+                    plock_type,
+                    raw_value,
+                );
+            }
+
+            report.changes.push(MachinePlockChange {
+                trig_index,
+                number,
+                name: parameter.map(|parameter| parameter.name().to_owned()),
+                value,
+                action,
+            });
+        }
+
+        set_remapped_plocks(&mut staged, track_index as u8, remapped)?;
+
+        self.set_machine_type(machine_type)?;
+        *pool.lock() = staged;
+
+        Ok(report)
+    }
+}
+
+/// How a synth parameter of the new machine got its value when a sound is converted.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum ParameterConversion {
+    /// The value is converted from the equivalent synth parameter of the old machine.
+    Mapped {
+        /// Name of the synth parameter in the old machine.
+        from: String,
+        /// Value of the synth parameter in the old machine.
+        from_value: f32,
+        /// `true` if the value did not fit the range of the new synth parameter and is clamped.
+        clamped: bool,
+    },
+    /// The old machine does not have an equivalent synth parameter or its value can not be carried over.
+    ///
+    /// The value is the default of the new machine.
+    Default,
+}
+
+/// A synth parameter of the new machine after a sound is converted.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ConvertedParameter {
+    /// Number of the synth parameter in the new machine.
+    ///
+    /// Range `1..=8`
+    pub number: usize,
+    /// Name of the synth parameter in the new machine.
+    pub name: String,
+    /// Value of the synth parameter in the new machine.
+    pub value: f32,
+    /// Where the value comes from.
+    pub conversion: ParameterConversion,
+}
+
+/// The outcome of converting a sound to another machine.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MachineConversionReport {
+    /// The machine before the conversion.
+    pub from: MachineType,
+    /// The machine after the conversion.
+    pub to: MachineType,
+    /// The synth parameters of the new machine ordered by number.
+    pub parameters: Vec<ConvertedParameter>,
+    /// Names of the synth parameters of the old machine which are not carried over.
+    pub dropped: Vec<String>,
+    /// What happened to the parameter locks of the synth parameters.
+    pub plocks: MachineChangeReport,
+}
+
+impl Sound {
+    /// Converts the sound to another machine carrying the synth parameters over to their equivalents in the new machine.
+    ///
+    /// Equivalent synth parameters are found with [`MachineType::equivalent_parameter`] and their values are converted with [`MachineParameter::convert_value`].
+    /// Synth parameters of the new machine without an equivalent keep the defaults of the new machine.
+    ///
+    /// Parameter locks of the synth parameters are moved the same way, see [`MachineChangePolicy::Remap`].
+    ///
+    /// # Errors
+    ///
+    /// Returns the errors of [`Sound::set_machine_type_with_policy`].
+    ///
+    /// In case of an error this sound and the parameter locks are left untouched.
+    pub fn convert_machine_type(
+        &mut self,
+        machine_type: MachineType,
+    ) -> Result<MachineConversionReport, RytmError> {
+        let from = self.machine_type();
+        let values = from
+            .parameters()
+            .into_iter()
+            .flatten()
+            .map(|parameter| {
+                Ok((
+                    parameter,
+                    self.get(SoundParameter::Machine(parameter.number()))?,
+                ))
+            })
+            .collect::<Result<Vec<_>, RytmError>>()?;
+
+        let plocks = self.set_machine_type_with_policy(machine_type, MachineChangePolicy::Remap)?;
+
+        let mut carried = Vec::new();
+        let mut parameters = Vec::new();
+        for parameter in machine_type.parameters().into_iter().flatten() {
+            let converted = values.iter().find_map(|(old, value)| {
+                let equivalent = machine_type.equivalent_parameter(old)?;
+                if equivalent.number() != parameter.number() {
+                    return None;
+                }
+                let (new_value, clamped) = old.convert_value(*value, &parameter)?;
+                Some((old, *value, new_value, clamped))
+            });
+
+            let conversion = match converted {
+                Some((old, from_value, new_value, clamped)) => {
+                    self.set(SoundParameter::Machine(parameter.number()), new_value)?;
+                    carried.push(old.number());
+                    ParameterConversion::Mapped {
+                        from: old.name().to_owned(),
+                        from_value,
+                        clamped,
+                    }
+                }
+                None => ParameterConversion::Default,
+            };
+            parameters.push(ConvertedParameter {
+                number: parameter.number(),
+                name: parameter.name().to_owned(),
+                value: self.get(SoundParameter::Machine(parameter.number()))?,
+                conversion,
+            });
+        }
+
+        let dropped = values
+            .iter()
+            .filter(|(old, _)| !carried.contains(&old.number()))
+            .map(|(old, _)| old.name().to_owned())
+            .collect();
+
+        Ok(MachineConversionReport {
+            from,
+            to: machine_type,
+            parameters,
+            dropped,
+            plocks,
+        })
+    }
+}
+
+/// Clears a parameter lock in the way it is stored.
+pub(crate) fn clear_plock(
+    pool: &mut ParameterLockPool,
+    trig_index: usize,
+    track_index: u8,
+    plock_type: u8,
+    value: RawPlockValue,
+) {
+    match value {
+        RawPlockValue::Basic(_) => pool.clear_basic_plock(trig_index, track_index, plock_type),
+        RawPlockValue::Compound(_) => {
+            pool.clear_compound_plock(trig_index, track_index, plock_type);
+        }
+    }
+}
+
+/// Sets the remapped parameter locks, collecting the ones which do not fit into a single error.
+fn set_remapped_plocks(
+    pool: &mut ParameterLockPool,
+    track_index: u8,
+    remapped: Vec<(usize, MachineParameter, f32)>,
+) -> Result<(), RytmError> {
+    let mut did_not_fit = Vec::new();
+    for (trig_index, parameter, value) in remapped {
+        let result = match parameter.encode(value) {
+            RawPlockValue::Basic(value) => {
+                pool.set_basic_plock(trig_index, track_index, parameter.plock_type(), value)
+            }
+            RawPlockValue::Compound(value) => {
+                pool.set_compound_plock(trig_index, track_index, parameter.plock_type(), value)
+            }
+        };
+        match result {
+            Ok(()) => {}
+            Err(RytmError::ParameterLockMemoryFull) => {
+                did_not_fit.push(describe_plock(track_index, parameter.plock_type()));
+            }
+            Err(error) => return Err(error),
+        }
+    }
+    if !did_not_fit.is_empty() {
+        did_not_fit.dedup();
+        return Err(RytmError::ParameterLockMemoryFullWith { did_not_fit });
+    }
+
+    Ok(())
+}
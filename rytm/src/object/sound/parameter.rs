@@ -0,0 +1,416 @@
+// All casts in this file are intended or safe within the context of this library.
+//
+// One can change `allow` to `warn` to review them if necessary.
+#![allow(
+    clippy::cast_possible_truncation,
+    clippy::cast_possible_wrap,
+    clippy::cast_precision_loss,
+    clippy::cast_sign_loss
+)]
+#![allow(clippy::enum_glob_use, clippy::too_many_lines)]
+
+use super::{
+    machine::{MachineParameter, MachineParameters},
+    types::MachineType,
+    Sound,
+};
+use crate::{
+    error::{ParameterError, RytmError},
+    object::pattern::track::trig::{
+        parameter::{PlockParameter, PlockValueKind},
+        Trig,
+    },
+};
+use rytm_sys::ar_sound_t;
+use serde::{Deserialize, Serialize};
+use std::{ops::RangeInclusive, sync::Arc};
+
+/// Name of [`SoundParameter::AccentLevel`] used by [`Sound::parameter_by_name`].
+const ACCENT_LEVEL_NAME: &str = "accent_level";
+
+/// Identifies a parameter of a sound.
+///
+/// Values of the parameters are numeric, see [`SoundParameter::kind`] for how they are interpreted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum SoundParameter {
+    /// A parameter of the `[SAMP]`, `[FLT]`, `[AMP]` or `[LFO]` pages.
+    ///
+    /// FX parameters are not parameters of a sound, see [`KitParameter`](crate::object::kit::parameter::KitParameter) for them.
+    Page(PlockParameter),
+    /// A synth parameter of the machine of the sound by its number.
+    ///
+    /// Range `1..=8`, the meaning of the parameter depends on the machine, see [`MachineType::parameters`].
+    Machine(usize),
+    /// The accent level of the sound.
+    ///
+    /// The accent level can not be locked.
+    AccentLevel,
+}
+
+impl SoundParameter {
+    /// Returns how the numeric value of the parameter is interpreted for a sound using the given machine.
+    ///
+    /// Returns `None` if the sound does not have the parameter.
+    pub const fn kind(self, machine_type: MachineType) -> Option<PlockValueKind> {
+        match self {
+            Self::Page(parameter) if !parameter.is_fx() => Some(parameter.kind()),
+            Self::Machine(number) => match machine_type.parameter(number) {
+                Some(parameter) => Some(parameter.kind()),
+                None => None,
+            },
+            Self::AccentLevel => Some(PlockValueKind::Integer),
+            Self::Page(_) => None,
+        }
+    }
+
+    /// Returns the range of the numeric value of the parameter for a sound using the given machine.
+    ///
+    /// Returns `None` if the sound does not have the parameter.
+    pub const fn range(self, machine_type: MachineType) -> Option<RangeInclusive<f32>> {
+        match self {
+            Self::Page(parameter) if !parameter.is_fx() => Some(parameter.range()),
+            Self::Machine(number) => match machine_type.parameter(number) {
+                Some(parameter) => Some(parameter.range()),
+                None => None,
+            },
+            Self::AccentLevel => Some(0.0..=127.0),
+            Self::Page(_) => None,
+        }
+    }
+
+    /// Checks if the parameter can be locked in a trig.
+    pub const fn is_lockable(self) -> bool {
+        !matches!(self, Self::AccentLevel)
+    }
+}
+
+impl From<PlockParameter> for SoundParameter {
+    fn from(parameter: PlockParameter) -> Self {
+        Self::Page(parameter)
+    }
+}
+
+const fn toggle(value: bool) -> f32 {
+    if value {
+        1.0
+    } else {
+        0.0
+    }
+}
+
+fn choice<T: Into<u8>>(value: T) -> f32 {
+    f32::from(value.into())
+}
+
+impl Sound {
+    /// Returns every parameter of this sound.
+    ///
+    /// The page parameters are followed by the synth parameters which the machine of this sound uses and the accent level.
+    pub fn parameters(&self) -> Vec<SoundParameter> {
+        PlockParameter::ALL
+            .into_iter()
+            .filter(|parameter| !parameter.is_fx())
+            .map(SoundParameter::Page)
+            .chain(
+                self.machine_type()
+                    .parameters()
+                    .into_iter()
+                    .flatten()
+                    .map(|parameter| SoundParameter::Machine(parameter.number())),
+            )
+            .chain(std::iter::once(SoundParameter::AccentLevel))
+            .collect()
+    }
+
+    /// Finds a parameter of this sound by its name.
+    ///
+    /// Page parameters are named like the variants of [`PlockParameter`], e.g. `filter_cutoff`.
+    /// Synth parameters are named like the setters of the machine parameter types, e.g. `tun` for the `BdHard` machine.
+    /// The accent level is named `accent_level`.
+    pub fn parameter_by_name(&self, name: &str) -> Option<SoundParameter> {
+        if name == ACCENT_LEVEL_NAME {
+            return Some(SoundParameter::AccentLevel);
+        }
+        if let Ok(parameter) = PlockParameter::try_from(name) {
+            return (!parameter.is_fx()).then_some(SoundParameter::Page(parameter));
+        }
+        self.machine_type()
+            .parameter_by_name(name)
+            .map(|parameter| SoundParameter::Machine(parameter.number()))
+    }
+
+    /// Gets the numeric value of a parameter of this sound.
+    ///
+    /// The value is represented according to [`SoundParameter::kind`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if this sound does not have the parameter.
+    pub fn get(&self, parameter: SoundParameter) -> Result<f32, RytmError> {
+        use PlockParameter::*;
+        let page_parameter = match parameter {
+            SoundParameter::Page(page_parameter) => page_parameter,
+            SoundParameter::Machine(number) => {
+                self.machine_parameter(number)?;
+                // The machine parameters always follow the machine type of the sound.
+                return Ok(self
+                    .machine_parameters
+                    .get_parameter(number)
+                    .unwrap_or_default());
+            }
+            SoundParameter::AccentLevel => return Ok(self.accent_level() as f32),
+        };
+
+        let (sample, filter, amplitude, lfo) =
+            (self.sample(), self.filter(), self.amplitude(), self.lfo());
+        Ok(match page_parameter {
+            SampleTune => sample.tune() as f32,
+            SampleFineTune => sample.fine_tune() as f32,
+            SampleNumber => sample.slice_number() as f32,
+            SampleBitReduction => sample.bit_reduction() as f32,
+            SampleStart => sample.start(),
+            SampleEnd => sample.end(),
+            SampleLoopFlag => toggle(sample.loop_flag()),
+            SampleVolume => sample.volume() as f32,
+            FilterAttack => filter.attack() as f32,
+            FilterSustain => filter.sustain() as f32,
+            FilterDecay => filter.decay() as f32,
+            FilterRelease => filter.release() as f32,
+            FilterCutoff => filter.cutoff() as f32,
+            FilterResonance => filter.resonance() as f32,
+            FilterType => choice(filter.filter_type()),
+            FilterEnvelopeAmount => filter.envelope_amount() as f32,
+            AmplitudeAttack => amplitude.attack() as f32,
+            AmplitudeHold => amplitude.hold() as f32,
+            AmplitudeDecay => amplitude.decay() as f32,
+            AmplitudeOverdrive => amplitude.overdrive() as f32,
+            AmplitudeDelaySend => amplitude.delay_send() as f32,
+            AmplitudeReverbSend => amplitude.reverb_send() as f32,
+            // The pan is stored signed and returned as `usize`.
+            AmplitudePan => amplitude.pan() as isize as f32,
+            AmplitudeVolume => amplitude.volume() as f32,
+            LfoSpeed => lfo.speed() as f32,
+            LfoMultiplier => choice(lfo.multiplier()),
+            LfoFade => lfo.fade() as f32,
+            LfoDestination => choice(lfo.destination()),
+            LfoWaveform => choice(lfo.waveform()),
+            LfoStartPhase => lfo.start_phase_or_slew() as f32,
+            LfoMode => choice(lfo.mode()),
+            LfoDepth => lfo.depth(),
+            _ => return Err(not_a_sound_parameter(page_parameter)),
+        })
+    }
+
+    /// Sets a parameter of this sound by its identifier and numeric value.
+    ///
+    /// The value is interpreted according to [`SoundParameter::kind`] and validated against [`SoundParameter::range`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if this sound does not have the parameter or the value is not accepted by the parameter.
+    pub fn set(&mut self, parameter: SoundParameter, value: f32) -> Result<(), RytmError> {
+        use PlockParameter::*;
+        let page_parameter = match parameter {
+            SoundParameter::Page(page_parameter) if page_parameter.is_fx() => {
+                return Err(not_a_sound_parameter(page_parameter))
+            }
+            SoundParameter::Page(page_parameter) => page_parameter,
+            SoundParameter::Machine(number) => {
+                let value = self.machine_parameter(number)?.validate(value)?;
+                return self.machine_parameters.set_parameter(number, value);
+            }
+            SoundParameter::AccentLevel => return self.set_accent_level(value.round() as usize),
+        };
+
+        let value = page_parameter.validate(value)?;
+        let code = value as u8;
+        let whole = value as isize;
+        let toggle = value != 0.0;
+
+        match page_parameter {
+            SampleTune => self.sample_mut().set_tune(whole),
+            SampleFineTune => self.sample_mut().set_fine_tune(whole),
+            SampleNumber => self.sample_mut().set_slice_number(whole as usize),
+            SampleBitReduction => self.sample_mut().set_bit_reduction(whole as usize),
+            SampleStart => self.sample_mut().set_start(value),
+            SampleEnd => self.sample_mut().set_end(value),
+            SampleLoopFlag => {
+                self.sample_mut().set_loop_flag(toggle);
+                Ok(())
+            }
+            SampleVolume => self.sample_mut().set_volume(whole as usize),
+            FilterAttack => self.filter_mut().set_attack(whole as usize),
+            FilterSustain => self.filter_mut().set_sustain(whole as usize),
+            FilterDecay => self.filter_mut().set_decay(whole as usize),
+            FilterRelease => self.filter_mut().set_release(whole as usize),
+            FilterCutoff => self.filter_mut().set_cutoff(whole as usize),
+            FilterResonance => self.filter_mut().set_resonance(whole as usize),
+            FilterType => {
+                self.filter_mut().set_filter_type(code.try_into()?);
+                Ok(())
+            }
+            FilterEnvelopeAmount => self.filter_mut().set_envelope_amount(whole),
+            AmplitudeAttack => self.amplitude_mut().set_attack(whole as usize),
+            AmplitudeHold => self.amplitude_mut().set_hold(whole as usize),
+            AmplitudeDecay => self.amplitude_mut().set_decay(whole as usize),
+            AmplitudeOverdrive => self.amplitude_mut().set_overdrive(whole as usize),
+            AmplitudeDelaySend => self.amplitude_mut().set_delay_send(whole as usize),
+            AmplitudeReverbSend => self.amplitude_mut().set_reverb_send(whole as usize),
+            AmplitudePan => self.amplitude_mut().set_pan(whole),
+            AmplitudeVolume => self.amplitude_mut().set_volume(whole as usize),
+            LfoSpeed => self.lfo_mut().set_speed(whole),
+            LfoMultiplier => {
+                self.lfo_mut().set_multiplier(code.try_into()?);
+                Ok(())
+            }
+            LfoFade => self.lfo_mut().set_fade(whole),
+            LfoDestination => {
+                self.lfo_mut().set_destination(code.try_into()?);
+                Ok(())
+            }
+            LfoWaveform => {
+                self.lfo_mut().set_waveform(code.try_into()?);
+                Ok(())
+            }
+            LfoStartPhase => self.lfo_mut().set_start_phase(whole as usize),
+            LfoMode => {
+                self.lfo_mut().set_mode(code.try_into()?);
+                Ok(())
+            }
+            LfoDepth => self.lfo_mut().set_depth(value),
+            _ => unreachable!("FX parameters are rejected above."),
+        }
+    }
+
+    /// Sets a parameter lock for a parameter of this sound in a trig.
+    ///
+    /// The trig must be on the track which this sound is assigned to, so synth parameters are locked for the right machine.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the parameter can not be locked in the trig, the value is not accepted by the parameter,
+    /// the trig is orphan or the parameter lock memory is full.
+    pub fn plock_set(
+        &self,
+        trig: &Trig,
+        parameter: SoundParameter,
+        value: f32,
+    ) -> Result<(), RytmError> {
+        self.check_parameter_lock(trig, parameter)?;
+        match parameter {
+            SoundParameter::Page(page_parameter) => trig.plock_set_parameter(page_parameter, value),
+            SoundParameter::Machine(number) => {
+                trig.plock_set_machine_parameter(self.machine_parameter(number)?, value)
+            }
+            SoundParameter::AccentLevel => unreachable!("The accent level is rejected above."),
+        }
+    }
+
+    /// Gets the numeric value of a parameter lock for a parameter of this sound in a trig.
+    ///
+    /// The value is represented according to [`SoundParameter::kind`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the parameter can not be locked in the trig or the trig is orphan.
+    pub fn plock_get(
+        &self,
+        trig: &Trig,
+        parameter: SoundParameter,
+    ) -> Result<Option<f32>, RytmError> {
+        self.check_parameter_lock(trig, parameter)?;
+        match parameter {
+            SoundParameter::Page(page_parameter) => trig.plock_get_parameter(page_parameter),
+            SoundParameter::Machine(number) => {
+                trig.plock_get_machine_parameter(self.machine_parameter(number)?)
+            }
+            SoundParameter::AccentLevel => unreachable!("The accent level is rejected above."),
+        }
+    }
+
+    /// Clears a parameter lock for a parameter of this sound in a trig.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the parameter can not be locked in the trig or the trig is orphan.
+    pub fn plock_clear(&self, trig: &Trig, parameter: SoundParameter) -> Result<(), RytmError> {
+        self.check_parameter_lock(trig, parameter)?;
+        match parameter {
+            SoundParameter::Page(page_parameter) => trig.plock_clear_parameter(page_parameter),
+            SoundParameter::Machine(number) => {
+                trig.plock_clear_machine_parameter(self.machine_parameter(number)?)
+            }
+            SoundParameter::AccentLevel => unreachable!("The accent level is rejected above."),
+        }
+    }
+
+    /// Returns the synth parameter of the machine of this sound with the given number.
+    fn machine_parameter(&self, number: usize) -> Result<MachineParameter, RytmError> {
+        self.machine_type().parameter(number).ok_or_else(|| {
+            ParameterError::Compatibility {
+                value: number.to_string(),
+                parameter_name: "machine_parameter".into(),
+                reason: Some(format!(
+                    "The machine {} does not use the synth parameter {number}.",
+                    self.machine_type()
+                )),
+            }
+            .into()
+        })
+    }
+
+    /// Replaces the machine parameters of this sound with the ones in a raw sound, keeping the link to the parameter lock pool.
+    pub(crate) fn set_machine_parameters_from_raw_sound(
+        &mut self,
+        raw_sound: &ar_sound_t,
+    ) -> Result<(), RytmError> {
+        let mut machine_parameters =
+            MachineParameters::try_from_raw_sound(raw_sound, self.assigned_track())?;
+        if let Some(ref pool) = self.parameter_lock_pool {
+            machine_parameters.link_parameter_lock_pool(Arc::clone(pool));
+        }
+        self.machine_parameters = machine_parameters;
+        Ok(())
+    }
+
+    /// Returns an error if the parameter can not be locked in the trig for this sound.
+    fn check_parameter_lock(
+        &self,
+        trig: &Trig,
+        parameter: SoundParameter,
+    ) -> Result<(), RytmError> {
+        if let SoundParameter::Page(page_parameter) = parameter {
+            if page_parameter.is_fx() {
+                return Err(not_a_sound_parameter(page_parameter));
+            }
+        }
+        if !parameter.is_lockable() {
+            return Err(ParameterError::Compatibility {
+                value: ACCENT_LEVEL_NAME.into(),
+                parameter_name: "parameter".into(),
+                reason: Some("The accent level can not be locked.".into()),
+            }
+            .into());
+        }
+        if self.assigned_track() != Some(trig.track_index()) {
+            return Err(ParameterError::Compatibility {
+                value: trig.track_index().to_string(),
+                parameter_name: "track_index".into(),
+                reason: Some(
+                    "The trig must be on the track which the sound is assigned to.".into(),
+                ),
+            }
+            .into());
+        }
+        Ok(())
+    }
+}
+
+fn not_a_sound_parameter(parameter: PlockParameter) -> RytmError {
+    ParameterError::Compatibility {
+        value: <&str>::from(parameter).into(),
+        parameter_name: "parameter".into(),
+        reason: Some("FX parameters are not parameters of a sound.".into()),
+    }
+    .into()
+}
@@ -0,0 +1,313 @@
+// All casts in this file are intended or safe within the context of this library.
+//
+// One can change `allow` to `warn` to review them if necessary.
+#![allow(
+    clippy::cast_possible_truncation,
+    clippy::cast_precision_loss,
+    clippy::cast_sign_loss
+)]
+#![allow(clippy::enum_glob_use)]
+
+use super::{parameter::SoundParameter, types::MachineType, Sound};
+use crate::{
+    error::{ParameterError, RytmError},
+    object::pattern::track::trig::parameter::{PlockParameter, PlockValueKind},
+    util::SeededRng,
+};
+use rytm_rs_macro::parameter_range;
+use serde::{Deserialize, Serialize};
+use std::ops::RangeInclusive;
+
+/// A group of parameters of a sound which can be randomized together.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum SoundPage {
+    /// The synth parameters of the machine of the sound.
+    Machine,
+    /// The `[SAMP]` page.
+    Sample,
+    /// The `[FLT]` page.
+    Filter,
+    /// The `[AMP]` page.
+    Amplitude,
+    /// The `[LFO]` page.
+    Lfo,
+}
+
+impl SoundPage {
+    /// All pages of a sound.
+    pub const ALL: [Self; 5] = [
+        Self::Machine,
+        Self::Sample,
+        Self::Filter,
+        Self::Amplitude,
+        Self::Lfo,
+    ];
+
+    /// Returns the page which a parameter of a sound belongs to.
+    ///
+    /// Returns `None` for FX parameters and the accent level which is not on a page.
+    pub const fn of(parameter: SoundParameter) -> Option<Self> {
+        use PlockParameter::*;
+        match parameter {
+            SoundParameter::Machine(_) => Some(Self::Machine),
+            SoundParameter::AccentLevel => None,
+            SoundParameter::Page(page_parameter) => match page_parameter {
+                SampleTune | SampleFineTune | SampleNumber | SampleBitReduction | SampleStart
+                | SampleEnd | SampleLoopFlag | SampleVolume => Some(Self::Sample),
+                FilterAttack | FilterSustain | FilterDecay | FilterRelease | FilterCutoff
+                | FilterResonance | FilterType | FilterEnvelopeAmount => Some(Self::Filter),
+                AmplitudeAttack | AmplitudeHold | AmplitudeDecay | AmplitudeOverdrive
+                | AmplitudeDelaySend | AmplitudeReverbSend | AmplitudePan | AmplitudeVolume => {
+                    Some(Self::Amplitude)
+                }
+                LfoSpeed | LfoMultiplier | LfoFade | LfoDestination | LfoWaveform
+                | LfoStartPhase | LfoMode | LfoDepth => Some(Self::Lfo),
+                _ => None,
+            },
+        }
+    }
+}
+
+/// Constraints to randomize the parameters of a sound.
+///
+/// By default every page is randomized across the full range of its parameters.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SoundRandomizer {
+    pages: Vec<SoundPage>,
+    variation: Option<f32>,
+    locked_parameters: Vec<SoundParameter>,
+}
+
+impl Default for SoundRandomizer {
+    fn default() -> Self {
+        Self {
+            pages: SoundPage::ALL.to_vec(),
+            variation: None,
+            locked_parameters: Vec::new(),
+        }
+    }
+}
+
+impl SoundRandomizer {
+    /// Sets the pages which are randomized, the parameters of other pages are kept.
+    pub fn set_pages(&mut self, pages: &[SoundPage]) {
+        let mut pages = pages.to_vec();
+        pages.sort_unstable();
+        pages.dedup();
+        self.pages = pages;
+    }
+
+    /// Sets how far randomized values may move away from the current values.
+    ///
+    /// The variation is a percentage of the full range of each parameter,
+    /// a new value is picked around the current value and kept in the range of the parameter.
+    ///
+    /// Range `0.0..=100.0`
+    #[parameter_range(range = "variation:0.0..=100.0")]
+    pub fn set_variation(&mut self, variation: f32) -> Result<(), RytmError> {
+        self.variation = Some(variation);
+        Ok(())
+    }
+
+    /// Locks a parameter so it is not touched by the randomizer.
+    pub fn lock(&mut self, parameter: SoundParameter) {
+        if !self.locked_parameters.contains(&parameter) {
+            self.locked_parameters.push(parameter);
+        }
+    }
+
+    /// Unlocks a parameter which is locked with [`SoundRandomizer::lock`].
+    pub fn unlock(&mut self, parameter: SoundParameter) {
+        self.locked_parameters.retain(|&locked| locked != parameter);
+    }
+
+    /// Clears the variation so values are picked across the full range of each parameter.
+    pub const fn clear_variation(&mut self) {
+        self.variation = None;
+    }
+
+    /// Unlocks every locked parameter.
+    pub fn clear_locked_parameters(&mut self) {
+        self.locked_parameters.clear();
+    }
+
+    /// Returns the pages which are randomized.
+    pub fn pages(&self) -> &[SoundPage] {
+        &self.pages
+    }
+
+    /// Returns the variation as a percentage of the full range of each parameter if set.
+    pub const fn variation(&self) -> Option<f32> {
+        self.variation
+    }
+
+    /// Returns the parameters which are not touched by the randomizer.
+    pub fn locked_parameters(&self) -> &[SoundParameter] {
+        &self.locked_parameters
+    }
+
+    /// Checks if a parameter would be randomized.
+    pub fn randomizes(&self, parameter: SoundParameter) -> bool {
+        SoundPage::of(parameter).is_some_and(|page| self.pages.contains(&page))
+            && !self.locked_parameters.contains(&parameter)
+    }
+
+    /// Picks a value for a parameter which is accepted by it.
+    fn pick(
+        &self,
+        parameter: SoundParameter,
+        machine_type: MachineType,
+        current: f32,
+        rng: &mut SeededRng,
+    ) -> Option<f32> {
+        let (kind, full_range) = match parameter {
+            // Synth parameters are described by the metadata generated along with the machine parameters.
+            SoundParameter::Machine(number) => {
+                let machine_parameter = machine_type.parameter(number)?;
+                (machine_parameter.kind(), machine_parameter.range())
+            }
+            _ => (
+                parameter.kind(machine_type)?,
+                parameter.range(machine_type)?,
+            ),
+        };
+        let range = self.variation.map_or_else(
+            || full_range.clone(),
+            |variation| {
+                let span = (full_range.end() - full_range.start()) * variation / 100.0;
+                (current - span).max(*full_range.start())..=(current + span).min(*full_range.end())
+            },
+        );
+
+        match kind {
+            PlockValueKind::Float => Some(rng.range_f32(*range.start(), *range.end())),
+            PlockValueKind::Integer | PlockValueKind::Toggle | PlockValueKind::Choice => {
+                let choices = choices(parameter, machine_type, &range);
+                (!choices.is_empty()).then(|| choices[rng.range_usize(0, choices.len() - 1)])
+            }
+        }
+    }
+}
+
+/// Returns the whole values in the range which are accepted by the parameter.
+fn choices(
+    parameter: SoundParameter,
+    machine_type: MachineType,
+    range: &RangeInclusive<f32>,
+) -> Vec<f32> {
+    let accepts = |value: f32| match parameter {
+        SoundParameter::Page(page_parameter) => page_parameter.accepts(value),
+        SoundParameter::Machine(number) => machine_type
+            .parameter(number)
+            .is_some_and(|machine_parameter| machine_parameter.accepts(value)),
+        SoundParameter::AccentLevel => range.contains(&value),
+    };
+
+    (range.start().ceil() as isize..=range.end().floor() as isize)
+        .map(|value| value as f32)
+        .filter(|&value| accepts(value))
+        .collect()
+}
+
+impl Sound {
+    /// Randomizes the parameters of this sound within the constraints of a randomizer.
+    ///
+    /// Values are picked in the ranges of the parameters, choices only from the values which the parameters accept.
+    /// The accent level, the sound settings and parameters of pages which are not selected or locked are kept.
+    ///
+    /// The same seed, constraints and sound always produce the same result.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a picked value can not be set, in that case this sound is left untouched.
+    pub fn randomize(&mut self, randomizer: &SoundRandomizer, seed: u64) -> Result<(), RytmError> {
+        let machine_type = self.machine_type();
+        let mut rng = SeededRng::new(seed);
+        let mut randomized = self.clone();
+
+        for parameter in self.parameters() {
+            if !randomizer.randomizes(parameter) {
+                continue;
+            }
+            let current = self.get(parameter)?;
+            if let Some(value) = randomizer.pick(parameter, machine_type, current, &mut rng) {
+                randomized.set(parameter, value)?;
+            }
+        }
+
+        *self = randomized;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MACHINE_TYPES: [MachineType; 33] = [
+        MachineType::BdHard,
+        MachineType::BdClassic,
+        MachineType::SdHard,
+        MachineType::SdClassic,
+        MachineType::RsHard,
+        MachineType::RsClassic,
+        MachineType::CpClassic,
+        MachineType::BtClassic,
+        MachineType::XtClassic,
+        MachineType::ChClassic,
+        MachineType::OhClassic,
+        MachineType::CyClassic,
+        MachineType::CbClassic,
+        MachineType::BdFm,
+        MachineType::SdFm,
+        MachineType::UtNoise,
+        MachineType::UtImpulse,
+        MachineType::ChMetallic,
+        MachineType::OhMetallic,
+        MachineType::CyMetallic,
+        MachineType::CbMetallic,
+        MachineType::BdPlastic,
+        MachineType::BdSilky,
+        MachineType::SdNatural,
+        MachineType::HhBasic,
+        MachineType::CyRide,
+        MachineType::BdSharp,
+        MachineType::SyDualVco,
+        MachineType::SyChip,
+        MachineType::BdAcoustic,
+        MachineType::SdAcoustic,
+        MachineType::SyRaw,
+        MachineType::HhLab,
+    ];
+
+    #[test]
+    fn synth_parameters_of_every_machine_are_randomized_within_their_metadata() {
+        let mut randomizer = SoundRandomizer::default();
+        randomizer.set_pages(&[SoundPage::Machine]);
+
+        for machine_type in MACHINE_TYPES {
+            let mut sound = Sound::try_default(0).unwrap();
+            sound.set_machine_type(machine_type).unwrap();
+            let mut again = sound.clone();
+
+            sound.randomize(&randomizer, 7).unwrap();
+            again.randomize(&randomizer, 7).unwrap();
+
+            for machine_parameter in machine_type.parameters().into_iter().flatten() {
+                let parameter = SoundParameter::Machine(machine_parameter.number());
+                let value = sound.get(parameter).unwrap();
+                assert!(
+                    machine_parameter.accepts(value),
+                    "{machine_type} {} = {value}",
+                    machine_parameter.name()
+                );
+                assert_eq!(
+                    value.to_bits(),
+                    again.get(parameter).unwrap().to_bits(),
+                    "{machine_type} {}",
+                    machine_parameter.name()
+                );
+            }
+        }
+    }
+}
@@ -8,7 +8,10 @@
     clippy::cast_possible_wrap
 )]
 
-use super::types::{MachineType, SoundModTarget, SoundSettingsChromaticMode};
+use super::{
+    morph::blend,
+    types::{MachineType, SoundModTarget, SoundSettingsChromaticMode},
+};
 use crate::error::{ConversionError, ParameterError, RytmError};
 use rytm_rs_macro::parameter_range;
 use rytm_sys::ar_sound_t;
@@ -113,6 +116,64 @@ impl SoundSettings {
         raw_sound.at_target_4 = self.after_touch_modulation_target_4.into();
     }
 
+    /// Interpolates between two sound settings.
+    ///
+    /// Modulation amounts are blended, switches and selections are taken from `to` once `amount` reaches `threshold`.
+    pub(crate) fn morph(&self, to: &Self, amount: f32, threshold: f32) -> Self {
+        let blend = |from: i8, to: i8| blend(f32::from(from), f32::from(to), amount).round() as i8;
+        let switched = if amount >= threshold { to } else { self };
+
+        Self {
+            machine_type: self.machine_type,
+            chromatic_mode: switched.chromatic_mode,
+            env_reset_filter: switched.env_reset_filter,
+            velocity_to_volume: switched.velocity_to_volume,
+            legacy_fx_send: switched.legacy_fx_send,
+
+            velocity_modulation_amt_1: blend(
+                self.velocity_modulation_amt_1,
+                to.velocity_modulation_amt_1,
+            ),
+            velocity_modulation_target_1: switched.velocity_modulation_target_1,
+            velocity_modulation_amt_2: blend(
+                self.velocity_modulation_amt_2,
+                to.velocity_modulation_amt_2,
+            ),
+            velocity_modulation_target_2: switched.velocity_modulation_target_2,
+            velocity_modulation_amt_3: blend(
+                self.velocity_modulation_amt_3,
+                to.velocity_modulation_amt_3,
+            ),
+            velocity_modulation_target_3: switched.velocity_modulation_target_3,
+            velocity_modulation_amt_4: blend(
+                self.velocity_modulation_amt_4,
+                to.velocity_modulation_amt_4,
+            ),
+            velocity_modulation_target_4: switched.velocity_modulation_target_4,
+
+            after_touch_modulation_amt_1: blend(
+                self.after_touch_modulation_amt_1,
+                to.after_touch_modulation_amt_1,
+            ),
+            after_touch_modulation_target_1: switched.after_touch_modulation_target_1,
+            after_touch_modulation_amt_2: blend(
+                self.after_touch_modulation_amt_2,
+                to.after_touch_modulation_amt_2,
+            ),
+            after_touch_modulation_target_2: switched.after_touch_modulation_target_2,
+            after_touch_modulation_amt_3: blend(
+                self.after_touch_modulation_amt_3,
+                to.after_touch_modulation_amt_3,
+            ),
+            after_touch_modulation_target_3: switched.after_touch_modulation_target_3,
+            after_touch_modulation_amt_4: blend(
+                self.after_touch_modulation_amt_4,
+                to.after_touch_modulation_amt_4,
+            ),
+            after_touch_modulation_target_4: switched.after_touch_modulation_target_4,
+        }
+    }
+
     /// Sets the chromatic mode of the sound.
     pub fn set_chromatic_mode(&mut self, chromatic_mode: SoundSettingsChromaticMode) {
         self.chromatic_mode = chromatic_mode;
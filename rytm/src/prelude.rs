@@ -4,11 +4,12 @@
 
 pub use crate::{
     object::{
+        diff::Diffable,
         global::types::*,
         kit::types::*,
         pattern::{
             track::{
-                trig::{types::*, HoldsTrigFlags},
+                trig::{parameter::*, types::*, HoldsTrigFlags},
                 types::*,
             },
             types::*,
@@ -145,6 +145,24 @@ macro_rules! impl_sysex_compatible {
     };
 }
 
+/// Splits a buffer into the sysex messages it holds, for example the contents of a `.syx` file.
+///
+/// Bytes outside of sysex messages are skipped, an unterminated message at the end is returned as it is.
+pub fn split_sysex_messages(data: &[u8]) -> Vec<&[u8]> {
+    let mut messages = Vec::new();
+    let mut rest = data;
+    while let Some(start) = rest.iter().position(|&byte| byte == 0xF0) {
+        rest = &rest[start..];
+        let end = rest
+            .iter()
+            .position(|&byte| byte == 0xF7)
+            .map_or(rest.len(), |end| end + 1);
+        messages.push(&rest[..end]);
+        rest = &rest[end..];
+    }
+    messages
+}
+
 /// This function assumes that the response is a valid sysex response.
 ///
 /// It should be used in a context where this case is true and validity check is not necessary.
@@ -311,6 +311,62 @@ where
     }
 }
 
+/// A small deterministic pseudo random number generator (`SplitMix64`).
+///
+/// Used by the features which accept a seed, the same seed always produces the same sequence.
+#[derive(Debug, Clone)]
+pub struct SeededRng {
+    state: u64,
+}
+
+impl SeededRng {
+    pub const fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    pub const fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Returns a value in `0.0..1.0`.
+    #[allow(clippy::cast_precision_loss)]
+    pub fn next_f32(&mut self) -> f32 {
+        // 24 bits are enough to fill the mantissa.
+        (self.next_u64() >> 40) as f32 / (1u64 << 24) as f32
+    }
+
+    /// Returns `true` with the given probability, `0.0..=1.0`.
+    pub fn chance(&mut self, probability: f32) -> bool {
+        self.next_f32() < probability
+    }
+
+    /// Returns a value in the inclusive range.
+    pub const fn range_usize(&mut self, start: usize, end: usize) -> usize {
+        if end <= start {
+            return start;
+        }
+        start + (self.next_u64() % (end - start + 1) as u64) as usize
+    }
+
+    /// Returns a value in the inclusive range.
+    #[allow(clippy::cast_possible_wrap)]
+    pub const fn range_isize(&mut self, start: isize, end: isize) -> isize {
+        if end <= start {
+            return start;
+        }
+        start + (self.next_u64() % (end - start + 1) as u64) as isize
+    }
+
+    /// Returns a value in the range `start..end`.
+    pub fn range_f32(&mut self, start: f32, end: f32) -> f32 {
+        (end - start).mul_add(self.next_f32(), start)
+    }
+}
+
 pub mod arc_mutex_owner {
     use parking_lot::Mutex;
     use serde::{de::Deserializer, ser::Serializer, Deserialize, Serialize};